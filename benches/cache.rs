@@ -0,0 +1,30 @@
+//! Compares loading a module from a cache written by `Module::to_cache()`
+//! with parsing it from bitcode.
+//!
+//! Run with `cargo bench --bench cache --features bincode`. By default this
+//! uses the largest bitcode file bundled with the tests; set
+//! `LLVM_IR_BENCH_BC` to the path of another bitcode file to measure that
+//! instead.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use llvm_ir::Module;
+
+fn bench_cache(c: &mut Criterion) {
+    let path = std::env::var("LLVM_IR_BENCH_BC").unwrap_or_else(|_| "tests/basic_bc/rust.bc".to_owned());
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let mut cache = Vec::new();
+    module.to_cache(&mut cache).expect("Failed to write cache");
+
+    let mut group = c.benchmark_group(&path);
+    group.bench_function("from_bc_path", |b| b.iter(|| Module::from_bc_path(&path).expect("Failed to parse module")));
+    group.bench_function("from_cache", |b| b.iter(|| Module::from_cache(&cache[..]).expect("Failed to read cache")));
+    group.bench_function("to_cache", |b| b.iter(|| {
+        let mut cache = Vec::with_capacity(cache.len());
+        module.to_cache(&mut cache).expect("Failed to write cache");
+        cache
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, bench_cache);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Measures how much memory `Module::intern_strings()` saves.
+//!
+//! Run with `cargo bench --bench intern_strings`. By default this uses the
+//! largest bitcode file bundled with the tests; set `LLVM_IR_BENCH_BC` to the
+//! path of another bitcode file (e.g., a large C++ module) to measure that
+//! instead.
+
+use llvm_ir::Module;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, keeping count of the number of bytes currently
+/// allocated
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let path = std::env::var("LLVM_IR_BENCH_BC").unwrap_or_else(|_| "tests/basic_bc/rust.bc".to_owned());
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let before = ALLOCATED.load(Ordering::Relaxed) - baseline;
+    module.intern_strings();
+    let after = ALLOCATED.load(Ordering::Relaxed) - baseline;
+    println!("{}", path);
+    println!("  before intern_strings: {:>10} bytes", before);
+    println!("  after intern_strings:  {:>10} bytes", after);
+    let saved = before as i64 - after as i64;
+    println!("  saved:                 {:>10} bytes ({:.1}%)", saved, 100.0 * saved as f64 / before as f64);
+}
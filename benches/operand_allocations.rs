@@ -0,0 +1,48 @@
+//! Counts the heap allocations made while parsing a module, to compare
+//! instruction operand storage with and without the `smallvec` feature.
+//!
+//! Run with `cargo bench --bench operand_allocations`, and again with
+//! `cargo bench --bench operand_allocations --features smallvec`. By default
+//! this uses the largest bitcode file bundled with the tests; set
+//! `LLVM_IR_BENCH_BC` to the path of another bitcode file to measure that
+//! instead.
+
+use llvm_ir::Module;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, keeping count of the number of allocations made
+/// and the number of those which are still live
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(1, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let path = std::env::var("LLVM_IR_BENCH_BC").unwrap_or_else(|_| "tests/basic_bc/rust.bc".to_owned());
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let live_before = LIVE.load(Ordering::Relaxed);
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    let live = LIVE.load(Ordering::Relaxed) - live_before;
+    println!("{} (smallvec feature {})", path, if cfg!(feature = "smallvec") { "on" } else { "off" });
+    println!("  allocations while parsing: {:>10}", allocations);
+    println!("  allocations held by Module: {:>9}", live);
+    drop(module);
+}
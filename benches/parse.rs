@@ -0,0 +1,44 @@
+//! Benchmarks parsing a module, both as a whole (`Module::from_bc_path()`)
+//! and phase by phase, as reported by `Module::from_bc_path_timed()`.
+//!
+//! Run with `cargo bench --bench parse`. By default this parses the largest
+//! bitcode file bundled with the tests; set `LLVM_IR_BENCH_BC` to the path of
+//! another bitcode file to benchmark that instead.
+//!
+//! Types are decoded as they are encountered, so their decoding is included
+//! in the time for the phase which encountered them (mostly `functions` and
+//! `globals`).
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use llvm_ir::module::ParseTimings;
+use llvm_ir::Module;
+use std::time::Duration;
+
+/// Gets the time for one phase of parsing out of the `ParseTimings`
+type Phase = fn(&ParseTimings) -> Duration;
+
+fn bench_parse(c: &mut Criterion) {
+    let path = std::env::var("LLVM_IR_BENCH_BC").unwrap_or_else(|_| "tests/basic_bc/rust.bc".to_owned());
+    let mut group = c.benchmark_group(&path);
+    group.bench_function("from_bc_path", |b| b.iter(|| Module::from_bc_path(&path).expect("Failed to parse module")));
+
+    let phases: [(&str, Phase); 6] = [
+        ("bitcode", |t| t.bitcode),
+        ("name_mapping", |t| t.name_mapping),
+        ("functions", |t| t.functions),
+        ("globals", |t| t.globals),
+        ("metadata", |t| t.metadata),
+        ("total", |t| t.total),
+    ];
+    for (phase, get) in phases.iter() {
+        group.bench_function(*phase, |b| b.iter_custom(|iters| {
+            (0 .. iters)
+                .map(|_| get(&Module::from_bc_path_timed(&path).expect("Failed to parse module").1))
+                .sum()
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+//! Minimal reader for Unix `ar` archives (as used for static libraries),
+//! supporting both regular and thin archives, and both GNU- and BSD-style
+//! long member names
+
+use std::path::Path;
+
+const MAGIC: &[u8] = b"!<arch>\n";
+const THIN_MAGIC: &[u8] = b"!<thin>\n";
+const HEADER_LEN: usize = 60;
+
+pub(crate) struct ArchiveMember {
+    pub(crate) name: String,
+    pub(crate) data: Vec<u8>,
+}
+
+/// Read all of the (non-special) members of the archive at the given path.
+/// For thin archives, the member data is read from the referenced files.
+pub(crate) fn read_archive(path: &Path) -> Result<Vec<ArchiveMember>, String> {
+    let contents = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let is_thin = if contents.starts_with(MAGIC) {
+        false
+    } else if contents.starts_with(THIN_MAGIC) {
+        true
+    } else {
+        return Err(format!("{} is not an ar archive", path.display()));
+    };
+
+    let mut members = vec![];
+    let mut long_names: &[u8] = &[];
+    let mut offset = MAGIC.len();
+    while offset + HEADER_LEN <= contents.len() {
+        let header = &contents[offset..offset + HEADER_LEN];
+        if &header[58..60] != b"`\n" {
+            return Err(format!("Malformed archive member header at offset {}", offset));
+        }
+        let raw_name = String::from_utf8_lossy(&header[0..16]).trim_end().to_owned();
+        let size: usize = String::from_utf8_lossy(&header[48..58])
+            .trim()
+            .parse()
+            .map_err(|_| format!("Malformed archive member size at offset {}", offset))?;
+        offset += HEADER_LEN;
+
+        // Special members (symbol table and long-name table) are always stored
+        // in the archive, even in thin archives
+        let is_special = raw_name == "/" || raw_name == "//" || raw_name == "/SYM64/" || raw_name.starts_with("__.SYMDEF");
+        let stored_size = if is_thin && !is_special { 0 } else { size };
+        let stored = contents
+            .get(offset..offset + stored_size)
+            .ok_or_else(|| format!("Archive member at offset {} is truncated", offset))?;
+        offset += stored_size + stored_size % 2; // members are 2-byte aligned
+
+        if raw_name == "//" {
+            long_names = stored;
+            continue;
+        }
+        if is_special {
+            continue;
+        }
+
+        let (name, data) = if let Some(len) = raw_name.strip_prefix("#1/") {
+            // BSD-style: name is stored at the beginning of the data
+            let len: usize = len.parse().map_err(|_| format!("Malformed BSD member name {:?}", raw_name))?;
+            let name = String::from_utf8_lossy(&stored[..len]).trim_end_matches('\0').to_owned();
+            (name, stored[len..].to_vec())
+        } else if let Some(index) = raw_name.strip_prefix('/') {
+            // GNU-style: name is an offset into the long-name table
+            let index: usize = index.parse().map_err(|_| format!("Malformed GNU member name {:?}", raw_name))?;
+            let rest = long_names.get(index..).ok_or_else(|| format!("Long name offset {} out of range", index))?;
+            let end = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+            let name = String::from_utf8_lossy(&rest[..end]).trim_end_matches('/').to_owned();
+            (name, stored.to_vec())
+        } else {
+            (raw_name.trim_end_matches('/').to_owned(), stored.to_vec())
+        };
+
+        let data = if is_thin {
+            let member_path = path.parent().unwrap_or_else(|| Path::new("")).join(&name);
+            std::fs::read(&member_path).map_err(|e| format!("Failed to read thin archive member {}: {}", member_path.display(), e))?
+        } else {
+            data
+        };
+        members.push(ArchiveMember { name, data });
+    }
+    Ok(members)
+}
+
+/// Does the data look like LLVM bitcode (either raw or with a wrapper header)?
+pub(crate) fn is_bitcode(data: &[u8]) -> bool {
+    data.starts_with(b"BC\xC0\xDE") || data.starts_with(&[0xDE, 0xC0, 0x17, 0x0B])
+}
@@ -5,7 +5,8 @@ use crate::terminator::Terminator;
 /// A `BasicBlock` is a sequence of zero or more non-terminator instructions
 /// followed by a single terminator instruction which ends the block.
 /// Basic blocks are discussed in the [LLVM 9 docs on Functions](https://releases.llvm.org/9.0.0/docs/LangRef.html#functionstructure)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BasicBlock {
     pub name: Name,
     pub instrs: Vec<Instruction>,
@@ -24,6 +25,23 @@ impl BasicBlock {
             }),
         }
     }
+
+    /// Get the first non-terminator `Instruction` in the `BasicBlock`, if it
+    /// has any
+    pub fn first_instruction(&self) -> Option<&Instruction> {
+        self.instrs.first()
+    }
+
+    /// Get the last non-terminator `Instruction` in the `BasicBlock` (i.e.,
+    /// the one immediately before the terminator), if it has any
+    pub fn last_non_terminator(&self) -> Option<&Instruction> {
+        self.instrs.last()
+    }
+
+    /// Get the `Terminator` of the `BasicBlock`
+    pub fn terminator(&self) -> &Terminator {
+        &self.term
+    }
 }
 
 // ********* //
@@ -31,6 +49,8 @@ impl BasicBlock {
 // ********* //
 
 use crate::constant::GlobalNameMap;
+use crate::debuginfo::DITypeMap;
+use crate::debugloc::DebugLoc;
 use crate::from_llvm::*;
 use crate::operand::ValToNameMap;
 use crate::types::TyNameMap;
@@ -48,23 +68,28 @@ impl BasicBlock {
         bbmap: &BBMap,
         gnmap: &GlobalNameMap,
         tnmap: &mut TyNameMap,
+        ditypemap: &mut DITypeMap,
     ) -> Self {
         let name = Name::name_or_num(unsafe { get_bb_name(bb) }, ctr);
         assert_eq!(&name, bbmap.get(&bb).expect("Expected to find bb in bbmap"));
         debug!("Processing a basic block named {:?}", name);
+        let instrs = all_but_last(get_instructions(bb))
+            .map(|i| {
+                let mut inst = Instruction::from_llvm_ref(i, ctr, vnmap, bbmap, gnmap, tnmap);
+                DebugLoc::decode_scopes(inst.debugloc_mut(), i, ditypemap);
+                if let Instruction::Call(call) = &mut inst {
+                    call.debug_variable = ditypemap.decode_dbg_variable(i, vnmap);
+                }
+                inst
+            })
+            .collect();
+        let term = unsafe { LLVMGetBasicBlockTerminator(bb) };
+        let mut decoded_term = Terminator::from_llvm_ref(term, ctr, vnmap, bbmap, gnmap, tnmap);
+        DebugLoc::decode_scopes(decoded_term.debugloc_mut(), term, ditypemap);
         Self {
             name,
-            instrs: all_but_last(get_instructions(bb))
-                .map(|i| Instruction::from_llvm_ref(i, ctr, vnmap, bbmap, gnmap, tnmap))
-                .collect(),
-            term: Terminator::from_llvm_ref(
-                unsafe { LLVMGetBasicBlockTerminator(bb) },
-                ctr,
-                vnmap,
-                bbmap,
-                gnmap,
-                tnmap,
-            ),
+            instrs,
+            term: decoded_term,
         }
     }
 
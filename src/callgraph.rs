@@ -0,0 +1,150 @@
+use crate::module::Module;
+use crate::name::Name;
+use std::collections::{HashMap, HashSet};
+
+/// The call graph of a [`Module`](../struct.Module.html): an edge from
+/// function `a` to function `b` indicates that `a` contains a direct call to
+/// `b` (possibly through one or more `GlobalAlias`es).
+///
+/// Indirect calls and calls to inline assembly are not represented.
+/// Functions which are only declared in the `Module` (not defined) may appear
+/// as callees.
+#[derive(Clone, Debug)]
+pub struct CallGraph<'m> {
+    /// Names of the functions defined in the `Module`, in module order
+    functions: Vec<&'m str>,
+    callees: HashMap<&'m str, Vec<&'m str>>,
+    callers: HashMap<&'m str, Vec<&'m str>>,
+}
+
+impl<'m> CallGraph<'m> {
+    /// Construct the call graph of the given `Module`
+    pub fn new(module: &'m Module) -> Self {
+        let mut callees: HashMap<&'m str, Vec<&'m str>> = HashMap::new();
+        let mut callers: HashMap<&'m str, Vec<&'m str>> = HashMap::new();
+        for func in &module.functions {
+            callees.entry(&func.name).or_default();
+            callers.entry(&func.name).or_default();
+        }
+        for call_site in module.call_sites() {
+            let caller: &'m str = &call_site.caller.name;
+            let callee: &'m str = match call_site.callee.and_then(Name::as_str) {
+                Some(callee) => callee,
+                None => continue,
+            };
+            let caller_callees = callees.entry(caller).or_default();
+            if !caller_callees.contains(&callee) {
+                caller_callees.push(callee);
+                callers.entry(callee).or_default().push(caller);
+            }
+        }
+        let functions = module.functions.iter().map(|func| func.name.as_str()).collect();
+        Self { functions, callees, callers }
+    }
+
+    /// Get the names of the functions which the given function directly calls
+    pub fn callees(&self, func_name: &str) -> &[&'m str] {
+        self.callees.get(func_name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the names of the functions which directly call the given function
+    pub fn callers(&self, func_name: &str) -> &[&'m str] {
+        self.callers.get(func_name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Is the given function recursive: that is, can it (directly or via
+    /// other functions) call itself?
+    pub fn is_recursive(&self, func_name: &str) -> bool {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut worklist: Vec<&str> = self.callees(func_name).to_vec();
+        while let Some(name) = worklist.pop() {
+            if name == func_name {
+                return true;
+            }
+            if seen.insert(name) {
+                worklist.extend(self.callees(name));
+            }
+        }
+        false
+    }
+
+    /// Is the given function a leaf: that is, does it not directly call any
+    /// function defined in the `Module`? (It may still call functions which
+    /// are only declared, or make indirect calls.)
+    pub fn is_leaf(&self, func_name: &str) -> bool {
+        self.callees(func_name)
+            .iter()
+            .all(|callee| !self.callees.contains_key(callee))
+    }
+
+    /// Group the functions defined in the `Module` into strongly-connected
+    /// components (sets of mutually recursive functions), and order the groups
+    /// so that each group comes after every group it calls into (i.e.,
+    /// callees before callers). This is the order in which a bottom-up
+    /// interprocedural analysis should process the functions.
+    ///
+    /// Functions within a group are in the order they appear in the `Module`.
+    /// Calls to functions which are only declared are ignored.
+    pub fn topo_order(&self) -> Vec<Vec<String>> {
+        // Tarjan's algorithm, which finds the SCCs in reverse topological
+        // order. It is implemented iteratively so that long call chains don't
+        // overflow the stack.
+        let position: HashMap<&str, usize> = self.functions.iter().enumerate().map(|(i, &name)| (name, i)).collect();
+        let mut next_index = 0;
+        let mut indices: HashMap<&str, (usize, usize)> = HashMap::new(); // (index, lowlink)
+        let mut stack: Vec<&str> = vec![];
+        let mut on_stack: HashSet<&str> = HashSet::new();
+        let mut groups = vec![];
+        for &root in &self.functions {
+            if indices.contains_key(root) {
+                continue;
+            }
+            // each frame is a function and the index of the next callee to visit
+            let mut dfs: Vec<(&str, usize)> = vec![(root, 0)];
+            while let Some(&(node, next)) = dfs.last() {
+                if next == 0 && !indices.contains_key(node) {
+                    indices.insert(node, (next_index, next_index));
+                    next_index += 1;
+                    stack.push(node);
+                    on_stack.insert(node);
+                }
+                if let Some(&callee) = self.callees(node).get(next) {
+                    dfs.last_mut().unwrap().1 += 1;
+                    if !self.callees.contains_key(callee) {
+                        continue; // only declared, not defined
+                    }
+                    match indices.get(callee) {
+                        None => dfs.push((callee, 0)),
+                        Some(&(callee_index, _)) => {
+                            if on_stack.contains(callee) {
+                                let lowlink = &mut indices.get_mut(node).unwrap().1;
+                                *lowlink = std::cmp::min(*lowlink, callee_index);
+                            }
+                        },
+                    }
+                } else {
+                    dfs.pop();
+                    let (index, lowlink) = indices[node];
+                    if let Some(&(parent, _)) = dfs.last() {
+                        let parent_lowlink = &mut indices.get_mut(parent).unwrap().1;
+                        *parent_lowlink = std::cmp::min(*parent_lowlink, lowlink);
+                    }
+                    if lowlink == index {
+                        let mut group = vec![];
+                        loop {
+                            let member = stack.pop().unwrap();
+                            on_stack.remove(member);
+                            group.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        group.sort_by_key(|name| position[name]);
+                        groups.push(group.into_iter().map(str::to_owned).collect());
+                    }
+                }
+            }
+        }
+        groups
+    }
+}
@@ -0,0 +1,106 @@
+use crate::function::Function;
+use crate::instruction::{called_function_name, Instruction};
+use crate::module::Module;
+use crate::name::Name;
+use std::collections::{HashMap, HashSet};
+
+/// The control-flow graph of a [`Function`](../struct.Function.html): an
+/// edge from block `a` to block `b` indicates that `a`'s terminator may
+/// transfer control to `b`.
+#[derive(Clone, Debug)]
+pub struct Cfg<'f> {
+    function: &'f Function,
+    succs: HashMap<&'f Name, Vec<&'f Name>>,
+    preds: HashMap<&'f Name, Vec<&'f Name>>,
+}
+
+impl<'f> Cfg<'f> {
+    /// Construct the control-flow graph of the given `Function`
+    pub fn new(function: &'f Function) -> Self {
+        let mut succs: HashMap<&'f Name, Vec<&'f Name>> = HashMap::new();
+        let mut preds: HashMap<&'f Name, Vec<&'f Name>> = HashMap::new();
+        for bb in &function.basic_blocks {
+            preds.entry(&bb.name).or_default();
+            let mut bb_succs: Vec<&'f Name> = vec![];
+            for succ in bb.term.successors() {
+                if !bb_succs.contains(&succ) {
+                    bb_succs.push(succ);
+                    preds.entry(succ).or_default().push(&bb.name);
+                }
+            }
+            succs.insert(&bb.name, bb_succs);
+        }
+        Self { function, succs, preds }
+    }
+
+    /// Get the `Name` of the entry block, or `None` if the `Function` has no blocks
+    pub fn entry(&self) -> Option<&'f Name> {
+        self.function.entry_block().map(|bb| &bb.name)
+    }
+
+    /// Get the `Name`s of the blocks which the given block may transfer control to
+    pub fn successors(&self, block: &Name) -> &[&'f Name] {
+        self.succs.get(block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the `Name`s of the blocks which may transfer control to the given block
+    pub fn predecessors(&self, block: &Name) -> &[&'f Name] {
+        self.preds.get(block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the `Name`s of all blocks reachable from the entry block (including
+    /// the entry block itself).
+    ///
+    /// Blocks ending in `unreachable` have no successors, so reachability
+    /// stops there.
+    pub fn reachable_blocks(&self) -> HashSet<Name> {
+        self.reachable(None)
+    }
+
+    /// Like [`reachable_blocks()`](struct.Cfg.html#method.reachable_blocks),
+    /// but additionally treats any block containing a `noreturn` call as having
+    /// no successors, so blocks only reachable after such calls are pruned.
+    ///
+    /// A call is `noreturn` if it has the `noreturn` attribute at the call
+    /// site, or if it is a direct call to a function defined in `module`
+    /// which has the `noreturn` attribute. (Functions which are only declared
+    /// in `module` aren't decoded, so for calls to those, only the call-site
+    /// attributes are considered.)
+    pub fn reachable_blocks_pruning_noreturn(&self, module: &Module) -> HashSet<Name> {
+        self.reachable(Some(module))
+    }
+
+    /// If `prune_noreturn_in` is `Some`, prune after `noreturn` calls, looking
+    /// up the attributes of called functions in the given `Module`
+    fn reachable(&self, prune_noreturn_in: Option<&Module>) -> HashSet<Name> {
+        let mut reachable: HashSet<&'f Name> = HashSet::new();
+        let mut worklist: Vec<&'f Name> = self.entry().into_iter().collect();
+        while let Some(block) = worklist.pop() {
+            if !reachable.insert(block) {
+                continue;
+            }
+            if let Some(module) = prune_noreturn_in {
+                if self.has_noreturn_call(block, module) {
+                    continue;
+                }
+            }
+            worklist.extend(self.successors(block).iter().copied());
+        }
+        reachable.into_iter().cloned().collect()
+    }
+
+    fn has_noreturn_call(&self, block: &Name, module: &Module) -> bool {
+        match self.function.get_bb_by_name(block) {
+            Some(bb) => bb.instrs.iter().any(|inst| match inst {
+                Instruction::Call(call) => {
+                    call.function_attributes.iter().any(|attr| attr.is_enum_attribute_named("noreturn"))
+                        || called_function_name(&call.function)
+                            .and_then(|name| module.get_func_by_name(name))
+                            .is_some_and(|callee| callee.has_enum_attribute("noreturn"))
+                },
+                _ => false,
+            }),
+            None => false,
+        }
+    }
+}
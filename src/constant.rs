@@ -1,13 +1,18 @@
-use crate::name::Name;
+use crate::name::{write_identifier, Name};
 use crate::predicates::*;
-use crate::types::{Type, Typed};
+use crate::module::Module;
+use crate::types::{extractvalue_type_in, Type, Typed, TypedInModule};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::convert::TryFrom;
 use std::ops::Deref;
 use std::sync::{Arc, RwLock};
 
 /// See [LLVM 9 docs on Constants](https://releases.llvm.org/9.0.0/docs/LangRef.html#constants).
 /// Constants can be either values, or expressions involving other constants (see [LLVM 9 docs on Constant Expressions](https://releases.llvm.org/9.0.0/docs/LangRef.html#constant-expressions)).
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Constant {
     Int {
         bits: u32,
@@ -30,9 +35,11 @@ pub enum Constant {
     Vector(Vec<Constant>),
     /// `Undef` can be used anywhere a constant is expected. See [LLVM 9 docs on Undefined Values](https://releases.llvm.org/9.0.0/docs/LangRef.html#undefined-values)
     Undef(Type),
-    /// The address of the given (non-entry) [`BasicBlock`](../struct.BasicBlock.html). See [LLVM 9 docs on Addresses of Basic Blocks](https://releases.llvm.org/9.0.0/docs/LangRef.html#addresses-of-basic-blocks).
-    /// `BlockAddress` needs more fields, but the necessary getter functions are apparently not exposed in the LLVM C API (only the C++ API)
-    BlockAddress, // --TODO ideally we want BlockAddress { function: Name, block: Name },
+    /// The address of the given (non-entry) [`BasicBlock`](../struct.BasicBlock.html) in the given function. See [LLVM 9 docs on Addresses of Basic Blocks](https://releases.llvm.org/9.0.0/docs/LangRef.html#addresses-of-basic-blocks).
+    BlockAddress {
+        function: Name,
+        block: Name,
+    },
     GlobalReference {
         name: Name,
         ty: Type,
@@ -99,29 +106,31 @@ pub enum Constant {
 }
 
 /// All of these `Float` variants should have data associated with them, but
-/// Rust only has `f32` and `f64` floating-point types, and furthermore,
-/// it's not clear how to get 16-, 80-, or 128-bit FP constant values through
-/// the LLVM C API (the getters seem to only be exposed in the C++ API?)
-#[derive(PartialEq, Clone, Debug)]
+/// Rust only has `f32` and `f64` floating-point types, so 16-, 80-, and
+/// 128-bit FP constants are given by their bit patterns
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum Float {
-    Half, // TODO perhaps Half(u16)
+    Half(u16),
     Single(f32),
     Double(f64),
-    Quadruple, // TODO perhaps Quadruple(u128)
-    X86_FP80,  // TODO perhaps X86_FP80((u16, u64)) with the most-significant bits on the left
-    PPC_FP128, // TODO perhaps PPC_FP128((u64, u64)) with the most-significant bits on the left
+    Quadruple(u128),
+    /// With the most-significant bits (the sign and exponent) on the left
+    X86_FP80((u16, u64)),
+    /// With the most-significant bits on the left
+    PPC_FP128((u64, u64)),
 }
 
 impl Typed for Float {
     fn get_type(&self) -> Type {
         match self {
-            Float::Half => Type::half(),
+            Float::Half(_) => Type::half(),
             Float::Single(_) => Type::single(),
             Float::Double(_) => Type::double(),
-            Float::Quadruple => Type::FPType(FPType::FP128),
-            Float::X86_FP80 => Type::FPType(FPType::X86_FP80),
-            Float::PPC_FP128 => Type::FPType(FPType::PPC_FP128),
+            Float::Quadruple(_) => Type::FPType(FPType::FP128),
+            Float::X86_FP80(_) => Type::FPType(FPType::X86_FP80),
+            Float::PPC_FP128(_) => Type::FPType(FPType::PPC_FP128),
         }
     }
 }
@@ -193,6 +202,673 @@ impl Typed for Constant {
     }
 }
 
+impl TypedInModule for Constant {
+    fn get_type_in(&self, module: &Module) -> Type {
+        match self {
+            Constant::ExtractValue(e) => e.get_type_in(module),
+            Constant::GetElementPtr(g) => g.get_type_in(module),
+            _ => self.get_type(),
+        }
+    }
+}
+
+impl Eq for Constant {}
+
+/// `Constant`s are totally ordered: first by kind, in the order the variants
+/// of `Constant` are declared (so, e.g., every `Int` is less than every
+/// `Float`). Within a kind:
+///   - `Int`s are ordered by `value` (treated as unsigned), then by `bits`;
+///   - `Float`s are ordered as described on [`Float`](enum.Float.html);
+///   - `GlobalReference`s are ordered by `name`;
+///   - everything else is ordered lexicographically by its operands (e.g.,
+///     the elements of a `Struct`, `Array`, or `Vector`, or the operands of a
+///     constant expression).
+///
+/// Any remaining ties between unequal `Constant`s (e.g., `Struct`s differing
+/// only in packedness, or `trunc`s of the same operand to different types)
+/// are broken by comparing their textual representations (see the `Display`
+/// impl).
+impl Ord for Constant {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Constant::Int { bits: b1, value: v1 }, Constant::Int { bits: b2, value: v2 }) => {
+                v1.cmp(v2).then(b1.cmp(b2))
+            },
+            (Constant::Float(f1), Constant::Float(f2)) => f1.cmp(f2),
+            (Constant::GlobalReference { name: n1, .. }, Constant::GlobalReference { name: n2, .. }) => {
+                n1.cmp(n2).then_with(|| self.cmp_text(other))
+            },
+            _ => self.kind_index().cmp(&other.kind_index())
+                .then_with(|| self.operands().cmp(&other.operands()))
+                .then_with(|| self.cmp_text(other)),
+        }
+    }
+}
+
+impl PartialOrd for Constant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats the `Constant` as it would appear as an operand in LLVM IR, i.e.,
+/// preceded by its type: e.g., `i32 -1`, `{ i32, i8* } { i32 1, i8* null }`,
+/// or `i32* getelementptr inbounds ([4 x i32], [4 x i32]* @arr, i64 0, i64 1)`.
+///
+/// Information this crate doesn't decode is not printed: constant
+/// expressions are printed without `nsw`, `nuw`, or `exact` flags.
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ty = match self {
+            Constant::Struct { name: Some(name), .. } => Type::NamedStructType { name: name.clone(), ty: None },
+            Constant::BlockAddress { .. } => Type::pointer_to(Type::i8()),
+            _ => self.get_type(),
+        };
+        write!(f, "{} ", ty)?;
+        self.fmt_value(f)
+    }
+}
+
+impl Constant {
+    /// For an `Int`, get its value interpreted as a signed (two's complement)
+    /// integer of its width, e.g., `-1` for `i8 255`. Returns `None` for
+    /// anything other than an `Int`.
+    ///
+    /// `Int`s wider than 64 bits also give `None`, because only their lowest
+    /// 64 bits are stored (see `Constant::Int`).
+    pub fn as_signed(&self) -> Option<i128> {
+        match self {
+            Constant::Int { bits, value } if *bits <= 64 => Some(i128::from(sign_extend(*bits, *value))),
+            _ => None,
+        }
+    }
+
+    /// For an `Int`, get its value interpreted as an unsigned integer, e.g.,
+    /// `255` for `i8 255`. Returns `None` for anything other than an `Int`, or
+    /// for an `Int` wider than 64 bits, as with
+    /// [`as_signed()`](enum.Constant.html#method.as_signed).
+    pub fn as_unsigned(&self) -> Option<u128> {
+        match self {
+            Constant::Int { bits, value } if *bits <= 64 => Some(u128::from(*value)),
+            _ => None,
+        }
+    }
+
+    /// Evaluate an `icmp` with the given predicate on two constant operands.
+    /// Returns `None` unless both operands are `Int`s of the same width (at
+    /// most 64 bits).
+    /// See [LLVM 9 docs on the 'icmp' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#icmp-instruction)
+    pub fn fold_icmp(pred: IntPredicate, lhs: &Constant, rhs: &Constant) -> Option<bool> {
+        let (bits, a, b) = match (lhs, rhs) {
+            (Constant::Int { bits: b1, value: v1 }, Constant::Int { bits: b2, value: v2 }) if b1 == b2 && *b1 <= 64 => {
+                (*b1, *v1, *v2)
+            },
+            _ => return None,
+        };
+        let (sa, sb) = (sign_extend(bits, a), sign_extend(bits, b));
+        Some(match pred {
+            IntPredicate::EQ => a == b,
+            IntPredicate::NE => a != b,
+            IntPredicate::UGT => a > b,
+            IntPredicate::UGE => a >= b,
+            IntPredicate::ULT => a < b,
+            IntPredicate::ULE => a <= b,
+            IntPredicate::SGT => sa > sb,
+            IntPredicate::SGE => sa >= sb,
+            IntPredicate::SLT => sa < sb,
+            IntPredicate::SLE => sa <= sb,
+        })
+    }
+
+    /// Evaluate an `fcmp` with the given predicate on two constant operands.
+    /// Ordered predicates are false if either operand is NaN; unordered
+    /// predicates are true if either operand is NaN.
+    /// Returns `None` unless both operands are `Float`s of the same kind, with
+    /// known values.
+    /// See [LLVM 9 docs on the 'fcmp' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fcmp-instruction)
+    pub fn fold_fcmp(pred: FPPredicate, lhs: &Constant, rhs: &Constant) -> Option<bool> {
+        let (a, b) = match (lhs, rhs) {
+            (Constant::Float(Float::Single(a)), Constant::Float(Float::Single(b))) => (f64::from(*a), f64::from(*b)),
+            (Constant::Float(Float::Double(a)), Constant::Float(Float::Double(b))) => (*a, *b),
+            _ => return None,
+        };
+        let unordered = a.is_nan() || b.is_nan();
+        Some(match pred {
+            FPPredicate::False => false,
+            FPPredicate::OEQ => !unordered && a == b,
+            FPPredicate::OGT => !unordered && a > b,
+            FPPredicate::OGE => !unordered && a >= b,
+            FPPredicate::OLT => !unordered && a < b,
+            FPPredicate::OLE => !unordered && a <= b,
+            FPPredicate::ONE => !unordered && a != b,
+            FPPredicate::ORD => !unordered,
+            FPPredicate::UNO => unordered,
+            FPPredicate::UEQ => unordered || a == b,
+            FPPredicate::UGT => unordered || a > b,
+            FPPredicate::UGE => unordered || a >= b,
+            FPPredicate::ULT => unordered || a < b,
+            FPPredicate::ULE => unordered || a <= b,
+            FPPredicate::UNE => unordered || a != b,
+            FPPredicate::True => true,
+        })
+    }
+
+    /// Get the underlying `Constant` after peeling off any pointer-to-pointer
+    /// `BitCast`s, `AddrSpaceCast`s, and `GetElementPtr`s whose indices are
+    /// all zero, e.g., `@g` for `bitcast (i8* @g to i32*)`.
+    ///
+    /// Like LLVM's `Value::stripPointerCasts()`, this does not look through
+    /// `GlobalAlias`es.
+    pub fn strip_pointer_casts(&self) -> &Constant {
+        let mut cur = self;
+        loop {
+            cur = match cur {
+                Constant::BitCast(bc) if is_pointer(&bc.operand) => &bc.operand,
+                Constant::AddrSpaceCast(asc) => &asc.operand,
+                Constant::GetElementPtr(gep) if gep.indices.iter().all(is_zero) => &gep.address,
+                _ => return cur,
+            };
+        }
+    }
+
+    /// Get the `Name`s of all globals referenced anywhere in this `Constant`,
+    /// including inside aggregates and nested constant expressions. Each
+    /// `Name` appears once, in the order it is first encountered.
+    pub fn referenced_globals(&self) -> Vec<&Name> {
+        let mut names: Vec<&Name> = vec![];
+        let mut worklist = vec![self];
+        while let Some(c) = worklist.pop() {
+            match c {
+                Constant::GlobalReference { name, .. } => {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                },
+                // push in reverse so that operands are visited in order
+                _ => worklist.extend(c.operands().into_iter().rev()),
+            }
+        }
+        names
+    }
+
+    /// Get mutable references to the `Type`s stored directly in this
+    /// `Constant` (e.g., the `to_type` of a constant cast), not including
+    /// those of the `Constant`s it is built from
+    pub(crate) fn types_mut(&mut self) -> Vec<&mut Type> {
+        match self {
+            Constant::Null(ty) => vec![ty],
+            Constant::AggregateZero(ty) => vec![ty],
+            Constant::Array { element_type, .. } => vec![element_type],
+            Constant::Undef(ty) => vec![ty],
+            Constant::GlobalReference { ty, .. } => vec![ty],
+            Constant::Trunc(c) => vec![&mut c.to_type],
+            Constant::ZExt(c) => vec![&mut c.to_type],
+            Constant::SExt(c) => vec![&mut c.to_type],
+            Constant::FPTrunc(c) => vec![&mut c.to_type],
+            Constant::FPExt(c) => vec![&mut c.to_type],
+            Constant::FPToUI(c) => vec![&mut c.to_type],
+            Constant::FPToSI(c) => vec![&mut c.to_type],
+            Constant::UIToFP(c) => vec![&mut c.to_type],
+            Constant::SIToFP(c) => vec![&mut c.to_type],
+            Constant::PtrToInt(c) => vec![&mut c.to_type],
+            Constant::IntToPtr(c) => vec![&mut c.to_type],
+            Constant::BitCast(c) => vec![&mut c.to_type],
+            Constant::AddrSpaceCast(c) => vec![&mut c.to_type],
+            _ => vec![],
+        }
+    }
+
+    /// Get the `Constant`s this `Constant` is directly built from: elements
+    /// of aggregates, and operands of constant expressions
+    pub(crate) fn operands(&self) -> Vec<&Constant> {
+        match self {
+            Constant::Int { .. }
+            | Constant::Float(_)
+            | Constant::Null(_)
+            | Constant::AggregateZero(_)
+            | Constant::Undef(_)
+            | Constant::BlockAddress { .. }
+            | Constant::GlobalReference { .. }
+            | Constant::TokenNone => vec![],
+            Constant::Struct { values, .. } => values.iter().collect(),
+            Constant::Array { elements, .. } => elements.iter().collect(),
+            Constant::Vector(elements) => elements.iter().collect(),
+            Constant::Add(x) => vec![&x.operand0, &x.operand1],
+            Constant::Sub(x) => vec![&x.operand0, &x.operand1],
+            Constant::Mul(x) => vec![&x.operand0, &x.operand1],
+            Constant::UDiv(x) => vec![&x.operand0, &x.operand1],
+            Constant::SDiv(x) => vec![&x.operand0, &x.operand1],
+            Constant::URem(x) => vec![&x.operand0, &x.operand1],
+            Constant::SRem(x) => vec![&x.operand0, &x.operand1],
+            Constant::And(x) => vec![&x.operand0, &x.operand1],
+            Constant::Or(x) => vec![&x.operand0, &x.operand1],
+            Constant::Xor(x) => vec![&x.operand0, &x.operand1],
+            Constant::Shl(x) => vec![&x.operand0, &x.operand1],
+            Constant::LShr(x) => vec![&x.operand0, &x.operand1],
+            Constant::AShr(x) => vec![&x.operand0, &x.operand1],
+            Constant::FAdd(x) => vec![&x.operand0, &x.operand1],
+            Constant::FSub(x) => vec![&x.operand0, &x.operand1],
+            Constant::FMul(x) => vec![&x.operand0, &x.operand1],
+            Constant::FDiv(x) => vec![&x.operand0, &x.operand1],
+            Constant::FRem(x) => vec![&x.operand0, &x.operand1],
+            Constant::ExtractElement(ee) => vec![&ee.vector, &ee.index],
+            Constant::InsertElement(ie) => vec![&ie.vector, &ie.element, &ie.index],
+            Constant::ShuffleVector(sv) => vec![&sv.operand0, &sv.operand1, &sv.mask],
+            Constant::ExtractValue(ev) => vec![&ev.aggregate],
+            Constant::InsertValue(iv) => vec![&iv.aggregate, &iv.element],
+            Constant::GetElementPtr(gep) => std::iter::once(&gep.address).chain(gep.indices.iter()).collect(),
+            Constant::Trunc(x) => vec![&x.operand],
+            Constant::ZExt(x) => vec![&x.operand],
+            Constant::SExt(x) => vec![&x.operand],
+            Constant::FPTrunc(x) => vec![&x.operand],
+            Constant::FPExt(x) => vec![&x.operand],
+            Constant::FPToUI(x) => vec![&x.operand],
+            Constant::FPToSI(x) => vec![&x.operand],
+            Constant::UIToFP(x) => vec![&x.operand],
+            Constant::SIToFP(x) => vec![&x.operand],
+            Constant::PtrToInt(x) => vec![&x.operand],
+            Constant::IntToPtr(x) => vec![&x.operand],
+            Constant::BitCast(x) => vec![&x.operand],
+            Constant::AddrSpaceCast(x) => vec![&x.operand],
+            Constant::ICmp(x) => vec![&x.operand0, &x.operand1],
+            Constant::FCmp(x) => vec![&x.operand0, &x.operand1],
+            Constant::Select(s) => vec![&s.condition, &s.true_value, &s.false_value],
+        }
+    }
+
+    /// Like `operands()`, but get mutable references
+    pub(crate) fn operands_mut(&mut self) -> Vec<&mut Constant> {
+        match self {
+            Constant::Int { .. }
+            | Constant::Float(_)
+            | Constant::Null(_)
+            | Constant::AggregateZero(_)
+            | Constant::Undef(_)
+            | Constant::BlockAddress { .. }
+            | Constant::GlobalReference { .. }
+            | Constant::TokenNone => vec![],
+            Constant::Struct { values, .. } => values.iter_mut().collect(),
+            Constant::Array { elements, .. } => elements.iter_mut().collect(),
+            Constant::Vector(elements) => elements.iter_mut().collect(),
+            Constant::Add(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::Sub(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::Mul(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::UDiv(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::SDiv(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::URem(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::SRem(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::And(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::Or(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::Xor(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::Shl(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::LShr(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::AShr(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::FAdd(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::FSub(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::FMul(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::FDiv(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::FRem(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::ExtractElement(ee) => vec![&mut ee.vector, &mut ee.index],
+            Constant::InsertElement(ie) => vec![&mut ie.vector, &mut ie.element, &mut ie.index],
+            Constant::ShuffleVector(sv) => vec![&mut sv.operand0, &mut sv.operand1, &mut sv.mask],
+            Constant::ExtractValue(ev) => vec![&mut ev.aggregate],
+            Constant::InsertValue(iv) => vec![&mut iv.aggregate, &mut iv.element],
+            Constant::GetElementPtr(gep) => std::iter::once(&mut gep.address).chain(gep.indices.iter_mut()).collect(),
+            Constant::Trunc(x) => vec![&mut x.operand],
+            Constant::ZExt(x) => vec![&mut x.operand],
+            Constant::SExt(x) => vec![&mut x.operand],
+            Constant::FPTrunc(x) => vec![&mut x.operand],
+            Constant::FPExt(x) => vec![&mut x.operand],
+            Constant::FPToUI(x) => vec![&mut x.operand],
+            Constant::FPToSI(x) => vec![&mut x.operand],
+            Constant::UIToFP(x) => vec![&mut x.operand],
+            Constant::SIToFP(x) => vec![&mut x.operand],
+            Constant::PtrToInt(x) => vec![&mut x.operand],
+            Constant::IntToPtr(x) => vec![&mut x.operand],
+            Constant::BitCast(x) => vec![&mut x.operand],
+            Constant::AddrSpaceCast(x) => vec![&mut x.operand],
+            Constant::ICmp(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::FCmp(x) => vec![&mut x.operand0, &mut x.operand1],
+            Constant::Select(s) => vec![&mut s.condition, &mut s.true_value, &mut s.false_value],
+        }
+    }
+
+    // Order two `Constant`s by their textual representations, as the final
+    // tiebreak in `cmp()`. This is only reached for constants of the same
+    // kind with equal operands, so any difference in the text comes from
+    // their types, predicates, indices, and the like.
+    fn cmp_text(&self, other: &Self) -> Ordering {
+        if self == other {
+            Ordering::Equal
+        } else {
+            self.to_string().cmp(&other.to_string())
+        }
+    }
+
+    /// Index of this `Constant`'s variant, in declaration order
+    fn kind_index(&self) -> u32 {
+        match self {
+            Constant::Int { .. } => 0,
+            Constant::Float(_) => 1,
+            Constant::Null(_) => 2,
+            Constant::AggregateZero(_) => 3,
+            Constant::Struct { .. } => 4,
+            Constant::Array { .. } => 5,
+            Constant::Vector(_) => 6,
+            Constant::Undef(_) => 7,
+            Constant::BlockAddress { .. } => 8,
+            Constant::GlobalReference { .. } => 9,
+            Constant::TokenNone => 10,
+            Constant::Add(_) => 11,
+            Constant::Sub(_) => 12,
+            Constant::Mul(_) => 13,
+            Constant::UDiv(_) => 14,
+            Constant::SDiv(_) => 15,
+            Constant::URem(_) => 16,
+            Constant::SRem(_) => 17,
+            Constant::And(_) => 18,
+            Constant::Or(_) => 19,
+            Constant::Xor(_) => 20,
+            Constant::Shl(_) => 21,
+            Constant::LShr(_) => 22,
+            Constant::AShr(_) => 23,
+            Constant::FAdd(_) => 24,
+            Constant::FSub(_) => 25,
+            Constant::FMul(_) => 26,
+            Constant::FDiv(_) => 27,
+            Constant::FRem(_) => 28,
+            Constant::ExtractElement(_) => 29,
+            Constant::InsertElement(_) => 30,
+            Constant::ShuffleVector(_) => 31,
+            Constant::ExtractValue(_) => 32,
+            Constant::InsertValue(_) => 33,
+            Constant::GetElementPtr(_) => 34,
+            Constant::Trunc(_) => 35,
+            Constant::ZExt(_) => 36,
+            Constant::SExt(_) => 37,
+            Constant::FPTrunc(_) => 38,
+            Constant::FPExt(_) => 39,
+            Constant::FPToUI(_) => 40,
+            Constant::FPToSI(_) => 41,
+            Constant::UIToFP(_) => 42,
+            Constant::SIToFP(_) => 43,
+            Constant::PtrToInt(_) => 44,
+            Constant::IntToPtr(_) => 45,
+            Constant::BitCast(_) => 46,
+            Constant::AddrSpaceCast(_) => 47,
+            Constant::ICmp(_) => 48,
+            Constant::FCmp(_) => 49,
+            Constant::Select(_) => 50,
+        }
+    }
+}
+
+impl Constant {
+    /// Write the `Constant` as it would appear in LLVM IR, without its type
+    fn fmt_value(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Constant::Int { bits: 1, value } => write!(f, "{}", *value != 0),
+            Constant::Int { bits, value } if *bits <= 64 => {
+                let shift = 64 - bits;
+                write!(f, "{}", ((value << shift) as i64) >> shift)
+            },
+            Constant::Int { value, .. } => write!(f, "{}", value),
+            Constant::Float(Float::Single(value)) => write_float(f, f64::from(*value)),
+            Constant::Float(Float::Double(value)) => write_float(f, *value),
+            Constant::Float(Float::Half(bits)) => write!(f, "0xH{:04X}", bits),
+            Constant::Float(Float::Quadruple(bits)) => write!(f, "0xL{:016X}{:016X}", *bits as u64, (bits >> 64) as u64),
+            Constant::Float(Float::X86_FP80((high, low))) => write!(f, "0xK{:04X}{:016X}", high, low),
+            Constant::Float(Float::PPC_FP128((high, low))) => write!(f, "0xM{:016X}{:016X}", low, high),
+            Constant::BlockAddress { function, block } => {
+                write!(f, "blockaddress(")?;
+                write_name(f, '@', function)?;
+                write!(f, ", ")?;
+                write_name(f, '%', block)?;
+                write!(f, ")")
+            },
+            Constant::Undef(_) => write!(f, "undef"),
+            Constant::Null(_) => write!(f, "null"),
+            Constant::AggregateZero(_) => write!(f, "zeroinitializer"),
+            Constant::Struct { values, is_packed, .. } => {
+                if *is_packed {
+                    write!(f, "<")?;
+                }
+                if values.is_empty() {
+                    write!(f, "{{}}")?;
+                } else {
+                    write!(f, "{{ ")?;
+                    write_list(f, values)?;
+                    write!(f, " }}")?;
+                }
+                if *is_packed {
+                    write!(f, ">")?;
+                }
+                Ok(())
+            },
+            Constant::Array { element_type, elements } if *element_type == Type::i8() && !elements.is_empty() && elements.iter().all(|c| matches!(c, Constant::Int { .. })) => {
+                // like LLVM, print arrays of bytes as strings
+                write!(f, "c\"")?;
+                for element in elements {
+                    if let Constant::Int { value, .. } = element {
+                        let byte = *value as u8;
+                        match byte {
+                            b'"' | b'\\' => write!(f, "\\{:02X}", byte)?,
+                            _ if byte.is_ascii_graphic() || byte == b' ' => write!(f, "{}", byte as char)?,
+                            _ => write!(f, "\\{:02X}", byte)?,
+                        }
+                    }
+                }
+                write!(f, "\"")
+            },
+            Constant::Array { elements, .. } => {
+                write!(f, "[")?;
+                write_list(f, elements)?;
+                write!(f, "]")
+            },
+            Constant::Vector(elements) => {
+                write!(f, "<")?;
+                write_list(f, elements)?;
+                write!(f, ">")
+            },
+            Constant::GlobalReference { name, .. } => write_name(f, '@', name),
+            Constant::TokenNone => write!(f, "none"),
+            Constant::Add(a) => write!(f, "add ({}, {})", a.operand0, a.operand1),
+            Constant::Sub(s) => write!(f, "sub ({}, {})", s.operand0, s.operand1),
+            Constant::Mul(m) => write!(f, "mul ({}, {})", m.operand0, m.operand1),
+            Constant::UDiv(d) => write!(f, "udiv ({}, {})", d.operand0, d.operand1),
+            Constant::SDiv(d) => write!(f, "sdiv ({}, {})", d.operand0, d.operand1),
+            Constant::URem(r) => write!(f, "urem ({}, {})", r.operand0, r.operand1),
+            Constant::SRem(r) => write!(f, "srem ({}, {})", r.operand0, r.operand1),
+            Constant::And(a) => write!(f, "and ({}, {})", a.operand0, a.operand1),
+            Constant::Or(o) => write!(f, "or ({}, {})", o.operand0, o.operand1),
+            Constant::Xor(x) => write!(f, "xor ({}, {})", x.operand0, x.operand1),
+            Constant::Shl(s) => write!(f, "shl ({}, {})", s.operand0, s.operand1),
+            Constant::LShr(l) => write!(f, "lshr ({}, {})", l.operand0, l.operand1),
+            Constant::AShr(a) => write!(f, "ashr ({}, {})", a.operand0, a.operand1),
+            Constant::FAdd(a) => write!(f, "fadd ({}, {})", a.operand0, a.operand1),
+            Constant::FSub(s) => write!(f, "fsub ({}, {})", s.operand0, s.operand1),
+            Constant::FMul(m) => write!(f, "fmul ({}, {})", m.operand0, m.operand1),
+            Constant::FDiv(d) => write!(f, "fdiv ({}, {})", d.operand0, d.operand1),
+            Constant::FRem(r) => write!(f, "frem ({}, {})", r.operand0, r.operand1),
+            Constant::ExtractElement(e) => write!(f, "extractelement ({}, {})", e.vector, e.index),
+            Constant::InsertElement(i) => write!(f, "insertelement ({}, {}, {})", i.vector, i.element, i.index),
+            Constant::ShuffleVector(s) => write!(f, "shufflevector ({}, {}, {})", s.operand0, s.operand1, s.mask),
+            Constant::ExtractValue(e) => {
+                write!(f, "extractvalue ({}", e.aggregate)?;
+                for index in &e.indices {
+                    write!(f, ", {}", index)?;
+                }
+                write!(f, ")")
+            },
+            Constant::InsertValue(i) => {
+                write!(f, "insertvalue ({}, {}", i.aggregate, i.element)?;
+                for index in &i.indices {
+                    write!(f, ", {}", index)?;
+                }
+                write!(f, ")")
+            },
+            Constant::GetElementPtr(g) => {
+                // the source element type is the type pointed to by the address
+                let source_type = match g.address.get_type() {
+                    Type::VectorType { element_type, .. } => *element_type,
+                    ty => ty,
+                };
+                let source_type = match source_type {
+                    Type::PointerType { pointee_type, .. } => *pointee_type,
+                    ty => ty,
+                };
+                let inbounds = if g.in_bounds { "inbounds " } else { "" };
+                write!(f, "getelementptr {}({}, {}", inbounds, source_type, g.address)?;
+                for index in &g.indices {
+                    write!(f, ", {}", index)?;
+                }
+                write!(f, ")")
+            },
+            Constant::Trunc(c) => write!(f, "trunc ({} to {})", c.operand, c.to_type),
+            Constant::ZExt(c) => write!(f, "zext ({} to {})", c.operand, c.to_type),
+            Constant::SExt(c) => write!(f, "sext ({} to {})", c.operand, c.to_type),
+            Constant::FPTrunc(c) => write!(f, "fptrunc ({} to {})", c.operand, c.to_type),
+            Constant::FPExt(c) => write!(f, "fpext ({} to {})", c.operand, c.to_type),
+            Constant::FPToUI(c) => write!(f, "fptoui ({} to {})", c.operand, c.to_type),
+            Constant::FPToSI(c) => write!(f, "fptosi ({} to {})", c.operand, c.to_type),
+            Constant::UIToFP(c) => write!(f, "uitofp ({} to {})", c.operand, c.to_type),
+            Constant::SIToFP(c) => write!(f, "sitofp ({} to {})", c.operand, c.to_type),
+            Constant::PtrToInt(c) => write!(f, "ptrtoint ({} to {})", c.operand, c.to_type),
+            Constant::IntToPtr(c) => write!(f, "inttoptr ({} to {})", c.operand, c.to_type),
+            Constant::BitCast(c) => write!(f, "bitcast ({} to {})", c.operand, c.to_type),
+            Constant::AddrSpaceCast(c) => write!(f, "addrspacecast ({} to {})", c.operand, c.to_type),
+            Constant::ICmp(i) => {
+                let predicate = format!("{:?}", i.predicate).to_lowercase();
+                write!(f, "icmp {} ({}, {})", predicate, i.operand0, i.operand1)
+            },
+            Constant::FCmp(fc) => {
+                let predicate = format!("{:?}", fc.predicate).to_lowercase();
+                write!(f, "fcmp {} ({}, {})", predicate, fc.operand0, fc.operand1)
+            },
+            Constant::Select(s) => write!(f, "select ({}, {}, {})", s.condition, s.true_value, s.false_value),
+        }
+    }
+}
+
+/// Write the `Constant`s separated by commas
+fn write_list(f: &mut fmt::Formatter, constants: &[Constant]) -> fmt::Result {
+    for (i, constant) in constants.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", constant)?;
+    }
+    Ok(())
+}
+
+/// Write a (global or local) `Name` as an LLVM identifier with the given prefix
+fn write_name(f: &mut fmt::Formatter, prefix: char, name: &Name) -> fmt::Result {
+    match name.as_str() {
+        Some(name) => write_identifier(f, prefix, name),
+        None => write!(f, "{}{}", prefix, name.as_number().expect("a Name should be a string or a number")),
+    }
+}
+
+/// Write a floating-point value the way LLVM does: in exponential notation
+/// if that represents the value exactly, otherwise as the hex bits of the
+/// value as a `double` (even for a `float`)
+fn write_float(f: &mut fmt::Formatter, value: f64) -> fmt::Result {
+    if value.is_finite() {
+        let decimal = format!("{:.6e}", value);
+        if decimal.parse::<f64>().map(f64::to_bits) == Ok(value.to_bits()) {
+            let (mantissa, exponent) = decimal.split_at(decimal.find('e').expect("exponential notation should have an 'e'"));
+            let exponent: i32 = exponent[1 ..].parse().expect("exponent should be an integer");
+            let sign = if exponent < 0 { '-' } else { '+' };
+            return write!(f, "{}e{}{:02}", mantissa, sign, exponent.abs());
+        }
+    }
+    write!(f, "0x{:016X}", value.to_bits())
+}
+
+/// `Float`s are equal if they are of the same kind and have the same bit
+/// pattern, so, e.g., `-0.0 != 0.0`, but a `NaN` is equal to itself.
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Float {}
+
+// `Hash` must be consistent with our `PartialEq`, so hashes the bit patterns
+impl Hash for Float {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind_index().hash(state);
+        match self {
+            Float::Half(bits) => bits.hash(state),
+            Float::Single(f) => f.to_bits().hash(state),
+            Float::Double(f) => f.to_bits().hash(state),
+            Float::Quadruple(bits) => bits.hash(state),
+            Float::X86_FP80(bits) => bits.hash(state),
+            Float::PPC_FP128(bits) => bits.hash(state),
+        }
+    }
+}
+
+/// `Float`s are ordered first by kind, in the order the variants of `Float`
+/// are declared. `Single`s and `Double`s are then ordered by value, with
+/// negative values (including `-0.0`) before positive ones and `NaN`s at the
+/// ends (as with `f64::total_cmp()`); other kinds are ordered by their bit
+/// patterns.
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Float::Half(b1), Float::Half(b2)) => b1.cmp(b2),
+            (Float::Single(f1), Float::Single(f2)) => f1.total_cmp(f2),
+            (Float::Double(f1), Float::Double(f2)) => f1.total_cmp(f2),
+            (Float::Quadruple(b1), Float::Quadruple(b2)) => b1.cmp(b2),
+            (Float::X86_FP80(b1), Float::X86_FP80(b2)) => b1.cmp(b2),
+            (Float::PPC_FP128(b1), Float::PPC_FP128(b2)) => b1.cmp(b2),
+            _ => self.kind_index().cmp(&other.kind_index()),
+        }
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Float {
+    /// Index of this `Float`'s variant, in declaration order
+    fn kind_index(&self) -> u32 {
+        match self {
+            Float::Half(_) => 0,
+            Float::Single(_) => 1,
+            Float::Double(_) => 2,
+            Float::Quadruple(_) => 3,
+            Float::X86_FP80(_) => 4,
+            Float::PPC_FP128(_) => 5,
+        }
+    }
+}
+
+/// Does the constant have pointer type?
+fn is_pointer(c: &Constant) -> bool {
+    matches!(c.get_type(), Type::PointerType { .. })
+}
+
+/// Is the constant an integer zero or a `zeroinitializer`?
+fn is_zero(c: &Constant) -> bool {
+    match c {
+        Constant::Int { value, .. } => *value == 0,
+        Constant::AggregateZero(_) => true,
+        _ => false,
+    }
+}
+
+/// Interpret the low `bits` bits of `value` as a signed integer
+fn sign_extend(bits: u32, value: u64) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
 pub trait ConstUnaryOp {
     fn get_operand(&self) -> &Constant;
 }
@@ -280,7 +956,8 @@ macro_rules! explicitly_typed {
     };
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Add {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -292,7 +969,8 @@ impl_constexpr!(Add, Add);
 impl_binop!(Add);
 binop_same_type!(Add);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sub {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -304,7 +982,8 @@ impl_constexpr!(Sub, Sub);
 impl_binop!(Sub);
 binop_same_type!(Sub);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mul {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -316,7 +995,8 @@ impl_constexpr!(Mul, Mul);
 impl_binop!(Mul);
 binop_same_type!(Mul);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UDiv {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -327,7 +1007,8 @@ impl_constexpr!(UDiv, UDiv);
 impl_binop!(UDiv);
 binop_same_type!(UDiv);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SDiv {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -338,7 +1019,8 @@ impl_constexpr!(SDiv, SDiv);
 impl_binop!(SDiv);
 binop_same_type!(SDiv);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct URem {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -348,7 +1030,8 @@ impl_constexpr!(URem, URem);
 impl_binop!(URem);
 binop_same_type!(URem);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SRem {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -358,7 +1041,8 @@ impl_constexpr!(SRem, SRem);
 impl_binop!(SRem);
 binop_same_type!(SRem);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct And {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -368,7 +1052,8 @@ impl_constexpr!(And, And);
 impl_binop!(And);
 binop_same_type!(And);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Or {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -378,7 +1063,8 @@ impl_constexpr!(Or, Or);
 impl_binop!(Or);
 binop_same_type!(Or);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Xor {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -388,7 +1074,8 @@ impl_constexpr!(Xor, Xor);
 impl_binop!(Xor);
 binop_same_type!(Xor);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shl {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -400,7 +1087,8 @@ impl_constexpr!(Shl, Shl);
 impl_binop!(Shl);
 binop_left_type!(Shl);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LShr {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -411,7 +1099,8 @@ impl_constexpr!(LShr, LShr);
 impl_binop!(LShr);
 binop_left_type!(LShr);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AShr {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -422,7 +1111,8 @@ impl_constexpr!(AShr, AShr);
 impl_binop!(AShr);
 binop_left_type!(AShr);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FAdd {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -432,7 +1122,8 @@ impl_constexpr!(FAdd, FAdd);
 impl_binop!(FAdd);
 binop_same_type!(FAdd);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FSub {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -442,7 +1133,8 @@ impl_constexpr!(FSub, FSub);
 impl_binop!(FSub);
 binop_same_type!(FSub);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FMul {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -452,7 +1144,8 @@ impl_constexpr!(FMul, FMul);
 impl_binop!(FMul);
 binop_same_type!(FMul);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FDiv {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -462,7 +1155,8 @@ impl_constexpr!(FDiv, FDiv);
 impl_binop!(FDiv);
 binop_same_type!(FDiv);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FRem {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -472,7 +1166,8 @@ impl_constexpr!(FRem, FRem);
 impl_binop!(FRem);
 binop_same_type!(FRem);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtractElement {
     pub vector: Constant,
     pub index: Constant,
@@ -489,7 +1184,8 @@ impl Typed for ExtractElement {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InsertElement {
     pub vector: Constant,
     pub element: Constant,
@@ -504,7 +1200,8 @@ impl Typed for InsertElement {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShuffleVector {
     pub operand0: Constant,
     pub operand1: Constant,
@@ -528,7 +1225,8 @@ impl Typed for ShuffleVector {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtractValue {
     pub aggregate: Constant,
     pub indices: Vec<u32>,
@@ -542,6 +1240,14 @@ impl Typed for ExtractValue {
     }
 }
 
+impl TypedInModule for ExtractValue {
+    fn get_type_in(&self, module: &Module) -> Type {
+        extractvalue_type_in(module, self.aggregate.get_type_in(module), &self.indices).unwrap_or_else(|| {
+            panic!("ExtractValue index out of range, or into an opaque struct; aggregate is {:?}", self.aggregate)
+        })
+    }
+}
+
 fn ev_type(cur_type: Type, mut indices: impl Iterator<Item = u32>) -> Type {
     match indices.next() {
         None => cur_type,
@@ -559,7 +1265,8 @@ fn ev_type(cur_type: Type, mut indices: impl Iterator<Item = u32>) -> Type {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InsertValue {
     pub aggregate: Constant,
     pub element: Constant,
@@ -574,7 +1281,8 @@ impl Typed for InsertValue {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetElementPtr {
     pub address: Constant,
     pub indices: Vec<Constant>,
@@ -585,46 +1293,84 @@ impl_constexpr!(GetElementPtr, GetElementPtr);
 
 impl Typed for GetElementPtr {
     fn get_type(&self) -> Type {
-        gep_type(&self.address.get_type(), self.indices.iter())
+        self.get_type_with(None)
+    }
+}
+
+impl TypedInModule for GetElementPtr {
+    fn get_type_in(&self, module: &Module) -> Type {
+        self.get_type_with(Some(module))
     }
 }
 
-fn gep_type<'a, 'b>(cur_type: &'a Type, mut indices: impl Iterator<Item = &'b Constant>) -> Type {
+impl GetElementPtr {
+    /// If `module` is `None`, named struct types are resolved through their
+    /// weak references instead
+    fn get_type_with(&self, module: Option<&Module>) -> Type {
+        // see notes on `instruction::GetElementPtr::get_type()`
+        let (address_type, base_width) = match self.address.get_type() {
+            Type::VectorType { element_type, num_elements } => (*element_type, Some(num_elements)),
+            ty => (ty, None),
+        };
+        let width = base_width.or_else(|| {
+            self.indices.iter().find_map(|index| match index.get_type() {
+                Type::VectorType { num_elements, .. } => Some(num_elements),
+                _ => None,
+            })
+        });
+        let ty = gep_type(module, &address_type, self.indices.iter());
+        match width {
+            Some(num_elements) => Type::VectorType { element_type: Box::new(ty), num_elements },
+            None => ty,
+        }
+    }
+}
+
+fn gep_type<'a, 'b>(module: Option<&Module>, cur_type: &'a Type, mut indices: impl Iterator<Item = &'b Constant>) -> Type {
     match indices.next() {
         None => Type::pointer_to(cur_type.clone()), // iterator is done
         Some(index) => match cur_type {
-            Type::PointerType { pointee_type, .. } => gep_type(pointee_type, indices),
-            Type::VectorType { element_type, .. } => gep_type(element_type, indices),
-            Type::ArrayType { element_type, .. } => gep_type(element_type, indices),
-            Type::StructType { element_types, .. } => {
-                if let Constant::Int { value, .. } = index {
-                    gep_type(
-                        element_types.get(*value as usize).expect("GEP index out of range"),
-                        indices,
-                    )
-                } else {
-                    panic!("Expected GEP index on a constant struct to be a Constant::Int; got {:?}", index)
-                }
-            },
-            Type::NamedStructType { ty, .. } => match ty {
-                None => panic!("GEP on an opaque struct type"),
-                Some(weak) => match weak.upgrade().expect("Weak reference disappeared").read().unwrap().deref() {
+            Type::PointerType { pointee_type, .. } => gep_type(module, pointee_type, indices),
+            Type::VectorType { element_type, .. } => gep_type(module, element_type, indices),
+            Type::ArrayType { element_type, .. } => gep_type(module, element_type, indices),
+            Type::StructType { element_types, .. } => gep_type(
+                module,
+                element_types.get(gep_struct_index(index)).expect("GEP index out of range"),
+                indices,
+            ),
+            Type::NamedStructType { name, ty } => {
+                let arc = match module {
+                    Some(module) => module.named_struct_types.get(name).cloned().flatten(),
+                    None => ty.as_ref().map(|weak| weak.upgrade().expect("Weak reference disappeared")),
+                };
+                match arc.expect("GEP on an opaque struct type").read().unwrap().deref() {
                     Type::StructType { element_types, .. } => {
-                        if let Constant::Int { value, .. } = index {
-                            gep_type(element_types.get(*value as usize).expect("GEP index out of range"), indices)
-                        } else {
-                            panic!("Expected GEP index on a struct to be a Constant::Int; got {:?}", index)
-                        }
+                        gep_type(module, element_types.get(gep_struct_index(index)).expect("GEP index out of range"), indices)
                     },
                     ty => panic!("Expected NamedStructType inner type to be a StructType; got {:?}", ty),
-                },
-            }
+                }
+            },
             _ => panic!("Expected GEP base type to be a PointerType, VectorType, ArrayType, StructType, or NamedStructType; got {:?}", cur_type),
         },
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// Get the value of a GEP index into a struct, which must be a constant
+/// integer (or, in a vector GEP, a splat of one)
+fn gep_struct_index(index: &Constant) -> usize {
+    match index {
+        Constant::Int { value, .. } => *value as usize,
+        Constant::Vector(elements) => match elements.first() {
+            Some(Constant::Int { value, .. }) => *value as usize,
+            _ => panic!("Expected vector GEP index on a constant struct to be a splat of a Constant::Int; got {:?}", index),
+        },
+        Constant::AggregateZero(_) => 0,
+        _ => panic!("Expected GEP index on a constant struct to be a Constant::Int; got {:?}", index),
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trunc {
     pub operand: Constant,
     pub to_type: Type,
@@ -634,7 +1380,8 @@ impl_constexpr!(Trunc, Trunc);
 impl_unop!(Trunc);
 explicitly_typed!(Trunc);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExt {
     pub operand: Constant,
     pub to_type: Type,
@@ -644,7 +1391,8 @@ impl_constexpr!(ZExt, ZExt);
 impl_unop!(ZExt);
 explicitly_typed!(ZExt);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SExt {
     pub operand: Constant,
     pub to_type: Type,
@@ -654,7 +1402,8 @@ impl_constexpr!(SExt, SExt);
 impl_unop!(SExt);
 explicitly_typed!(SExt);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPTrunc {
     pub operand: Constant,
     pub to_type: Type,
@@ -664,7 +1413,8 @@ impl_constexpr!(FPTrunc, FPTrunc);
 impl_unop!(FPTrunc);
 explicitly_typed!(FPTrunc);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPExt {
     pub operand: Constant,
     pub to_type: Type,
@@ -674,7 +1424,8 @@ impl_constexpr!(FPExt, FPExt);
 impl_unop!(FPExt);
 explicitly_typed!(FPExt);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPToUI {
     pub operand: Constant,
     pub to_type: Type,
@@ -684,7 +1435,8 @@ impl_constexpr!(FPToUI, FPToUI);
 impl_unop!(FPToUI);
 explicitly_typed!(FPToUI);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPToSI {
     pub operand: Constant,
     pub to_type: Type,
@@ -694,7 +1446,8 @@ impl_constexpr!(FPToSI, FPToSI);
 impl_unop!(FPToSI);
 explicitly_typed!(FPToSI);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UIToFP {
     pub operand: Constant,
     pub to_type: Type,
@@ -704,7 +1457,8 @@ impl_constexpr!(UIToFP, UIToFP);
 impl_unop!(UIToFP);
 explicitly_typed!(UIToFP);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SIToFP {
     pub operand: Constant,
     pub to_type: Type,
@@ -714,7 +1468,8 @@ impl_constexpr!(SIToFP, SIToFP);
 impl_unop!(SIToFP);
 explicitly_typed!(SIToFP);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PtrToInt {
     pub operand: Constant,
     pub to_type: Type,
@@ -724,7 +1479,8 @@ impl_constexpr!(PtrToInt, PtrToInt);
 impl_unop!(PtrToInt);
 explicitly_typed!(PtrToInt);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntToPtr {
     pub operand: Constant,
     pub to_type: Type,
@@ -734,7 +1490,8 @@ impl_constexpr!(IntToPtr, IntToPtr);
 impl_unop!(IntToPtr);
 explicitly_typed!(IntToPtr);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitCast {
     pub operand: Constant,
     pub to_type: Type,
@@ -744,7 +1501,8 @@ impl_constexpr!(BitCast, BitCast);
 impl_unop!(BitCast);
 explicitly_typed!(BitCast);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddrSpaceCast {
     pub operand: Constant,
     pub to_type: Type,
@@ -754,7 +1512,8 @@ impl_constexpr!(AddrSpaceCast, AddrSpaceCast);
 impl_unop!(AddrSpaceCast);
 explicitly_typed!(AddrSpaceCast);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ICmp {
     pub predicate: IntPredicate,
     pub operand0: Constant,
@@ -778,7 +1537,8 @@ impl Typed for ICmp {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FCmp {
     pub predicate: FPPredicate,
     pub operand0: Constant,
@@ -802,7 +1562,8 @@ impl Typed for FCmp {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Select {
     pub condition: Constant,
     pub true_value: Constant,
@@ -823,6 +1584,7 @@ impl Typed for Select {
 // from_llvm //
 // ********* //
 
+use crate::basicblock::BasicBlock;
 use crate::from_llvm::*;
 use crate::types::FPType;
 use crate::types::TyNameMap;
@@ -856,7 +1618,7 @@ impl Constant {
             LLVMValueKind::LLVMConstantFPValueKind => {
                 match Type::from_llvm_ref( unsafe { LLVMTypeOf(constant) }, tnmap ) {
                     Type::FPType(fptype) => Constant::Float(match fptype {
-                        FPType::Half => Float::Half,
+                        FPType::Half => Float::Half(Float::bits_from_llvm_ref(constant, 'H').unwrap_or_default() as u16),
                         FPType::Single => Float::Single( unsafe {
                             let mut b = 0;
                             let b_ptr: *mut std::os::raw::c_int = &mut b;
@@ -867,24 +1629,30 @@ impl Constant {
                             let b_ptr: *mut std::os::raw::c_int = &mut b;
                             LLVMConstRealGetDouble(constant, b_ptr)
                         } ),
-                        FPType::FP128 => Float::Quadruple,
-                        FPType::X86_FP80 => Float::X86_FP80,
-                        FPType::PPC_FP128 => Float::PPC_FP128,
+                        FPType::FP128 => Float::Quadruple(Float::bits_from_llvm_ref(constant, 'L').unwrap_or_default()),
+                        FPType::X86_FP80 => {
+                            let bits = Float::bits_from_llvm_ref(constant, 'K').unwrap_or_default();
+                            Float::X86_FP80(((bits >> 64) as u16, bits as u64))
+                        },
+                        FPType::PPC_FP128 => {
+                            let bits = Float::bits_from_llvm_ref(constant, 'M').unwrap_or_default();
+                            Float::PPC_FP128(((bits >> 64) as u64, bits as u64))
+                        },
                     }),
                     ty => panic!("Expected Constant::Float to have type Type::FPType; got {:?}", ty),
                 }
             },
             LLVMValueKind::LLVMConstantStructValueKind => {
-                let (num_elements, is_packed) = match Type::from_llvm_ref( unsafe { LLVMTypeOf(constant) }, tnmap ) {
-                    Type::StructType { element_types, is_packed } => (element_types.len(), is_packed),
-                    Type::NamedStructType { ref ty, .. } => {
+                let (name, num_elements, is_packed) = match Type::from_llvm_ref( unsafe { LLVMTypeOf(constant) }, tnmap ) {
+                    Type::StructType { element_types, is_packed } => (None, element_types.len(), is_packed),
+                    Type::NamedStructType { name, ref ty } => {
                         let arc: Arc<RwLock<Type>> = ty.as_ref()
                             .expect("Constant of opaque struct type")
                             .upgrade()
                             .expect("Weak reference should be valid for at least the lifetime of tnmap");
                         let innerty: &Type = &arc.read().unwrap();
                         if let Type::StructType { element_types, is_packed } = innerty {
-                            (element_types.len(), *is_packed)
+                            (Some(name.clone()), element_types.len(), *is_packed)
                         } else {
                             panic!("Expected NamedStructType inner type to be a StructType, but it actually is a {:?}", innerty)
                         }
@@ -892,7 +1660,7 @@ impl Constant {
                     ty => panic!("Expected Constant::Struct to have type StructType or NamedStructType; got {:?}", ty),
                 };
                 Constant::Struct {
-                    name: None,  // --TODO not yet implemented: Constant::Struct name
+                    name,
                     values: {
                         (0 .. num_elements).map(|i| {
                             Constant::from_llvm_ref( unsafe { LLVMGetOperand(constant, i as u32) }, gnmap, tnmap)
@@ -950,7 +1718,13 @@ impl Constant {
                 Constant::TokenNone
             },
             LLVMValueKind::LLVMBlockAddressValueKind => {
-                Constant::BlockAddress
+                // the operands of a `blockaddress` are the function and the block
+                let function = unsafe { LLVMGetOperand(constant, 0) };
+                let block = unsafe { op_to_bb(LLVMGetOperand(constant, 1)) };
+                Constant::BlockAddress {
+                    function: gnmap.get(&function).cloned().expect("Function not found in gnmap"),
+                    block: block_name(function, block),
+                }
             },
             LLVMValueKind::LLVMConstantExprValueKind => {
                 use llvm_sys::LLVMOpcode;
@@ -1011,6 +1785,58 @@ impl Constant {
     }
 }
 
+impl Float {
+    /// Get the bits of a 16-, 80-, or 128-bit FP constant. The LLVM C API
+    /// has no getter for these, so we decode them from the constant's
+    /// textual representation, in which LLVM always writes them in hex, with
+    /// the given `kind` letter: e.g., `half 0xH3C00`.
+    ///
+    /// If the value can't be decoded, the error is recorded with
+    /// `record_decode_error()`, so that parsing fails.
+    fn bits_from_llvm_ref(constant: LLVMValueRef, kind: char) -> Option<u128> {
+        let text = unsafe { print_to_string(constant) };
+        let bits = Self::bits_from_text(&text, kind);
+        if bits.is_none() {
+            record_decode_error(format!("Failed to decode the value of FP constant {:?}", text));
+        }
+        bits
+    }
+
+    fn bits_from_text(text: &str, kind: char) -> Option<u128> {
+        let prefix = format!("0x{}", kind);
+        let digits = &text[text.find(&prefix)? + prefix.len() ..];
+        let digits = &digits[.. digits.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(digits.len())];
+        let parse = |digits: &str| u128::from_str_radix(digits, 16).ok();
+        match (kind, digits.len()) {
+            // the sign and exponent are written first
+            ('H', 4) | ('K', 20) => parse(digits),
+            // the low 64 bits are written first
+            ('L', 32) | ('M', 32) => Some(parse(&digits[16 ..])? << 64 | parse(&digits[.. 16])?),
+            _ => None,
+        }
+    }
+}
+
+/// Get the `Name` of the given basic block of the given function, numbering
+/// an unnamed block the same way `Function::from_llvm_ref()` does
+fn block_name(function: LLVMValueRef, block: LLVMBasicBlockRef) -> Name {
+    let name = unsafe { get_bb_name(block) };
+    if !name.is_empty() {
+        return Name::from(name);
+    }
+    let mut ctr = 0;
+    for param in get_parameters(function) {
+        Name::name_or_num(unsafe { get_value_name(param) }, &mut ctr);
+    }
+    for bb in get_basic_blocks(function) {
+        let (name, _) = BasicBlock::first_pass_names(bb, &mut ctr);
+        if bb == block {
+            return name;
+        }
+    }
+    panic!("blockaddress refers to a block which isn't in its function")
+}
+
 macro_rules! binop_from_llvm {
     ($expr:ident) => {
         impl $expr {
@@ -1091,11 +1917,18 @@ impl ShuffleVector {
         gnmap: &GlobalNameMap,
         tnmap: &mut TyNameMap,
     ) -> Self {
-        assert_eq!(unsafe { LLVMGetNumOperands(expr) }, 3);
+        // see notes on `instruction::ShuffleVector::from_llvm_ref()`
+        let num_operands = unsafe { LLVMGetNumOperands(expr) };
+        assert!(num_operands == 2 || num_operands == 3);
+        let mask = if num_operands == 3 {
+            Constant::from_llvm_ref(unsafe { LLVMGetOperand(expr, 2) }, gnmap, tnmap)
+        } else {
+            crate::instruction::ShuffleVector::mask_from_llvm_ref(expr)
+        };
         Self {
             operand0: Constant::from_llvm_ref(unsafe { LLVMGetOperand(expr, 0) }, gnmap, tnmap),
             operand1: Constant::from_llvm_ref(unsafe { LLVMGetOperand(expr, 1) }, gnmap, tnmap),
-            mask: Constant::from_llvm_ref(unsafe { LLVMGetOperand(expr, 2) }, gnmap, tnmap),
+            mask,
         }
     }
 }
@@ -0,0 +1,45 @@
+use llvm_sys::core::{LLVMContextCreate, LLVMContextDispose};
+use llvm_sys::prelude::LLVMContextRef;
+
+/// An owned LLVM context, for users who want to work with the llvm-sys
+/// representation of IR directly. The underlying `LLVMContextRef` is disposed
+/// of when the `Context` is dropped.
+///
+/// FFI contract: every LLVM object created in this context (modules, types,
+/// values, metadata, ...) is freed along with it, so no `LLVMContextRef` or
+/// other LLVM reference obtained via [`as_raw()`](struct.Context.html#method.as_raw)
+/// may be used after the `Context` is dropped, and you must not dispose of the
+/// `LLVMContextRef` yourself. LLVM contexts are not thread-safe, so `Context`
+/// is neither `Send` nor `Sync`.
+/// See [LLVM 9 docs on LLVMContext](https://releases.llvm.org/9.0.0/docs/ProgrammersManual.html#achieving-isolation-with-llvmcontext)
+pub struct Context {
+    pub(crate) ctx: LLVMContextRef,
+}
+
+impl Context {
+    /// Create a new, empty LLVM context
+    pub fn new() -> Self {
+        Self {
+            ctx: unsafe { LLVMContextCreate() },
+        }
+    }
+
+    /// Get the underlying `LLVMContextRef`, which remains owned by this `Context`
+    pub fn as_raw(&self) -> LLVMContextRef {
+        self.ctx
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMContextDispose(self.ctx);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use crate::module::AddrSpace;
+use crate::types::{FPType, Type};
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+
+/// A parsed data layout string, which specifies how data is to be laid out in
+/// memory. All sizes and alignments here are in bits.
+/// See [LLVM 9 docs on Data Layout](https://releases.llvm.org/9.0.0/docs/LangRef.html#data-layout)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DataLayout {
+    pub endianness: Endianness,
+    pub mangling: Option<Mangling>,
+    pub stack_alignment: Option<u32>,
+    /// Map from address space to (pointer size, alignment) in that address space
+    pub pointer_layouts: HashMap<AddrSpace, (u32, AlignmentInfo)>,
+    /// Map from (kind of type, size of type) to alignment
+    pub type_layouts: HashMap<(AlignType, u32), AlignmentInfo>,
+    pub aggregate_layout: AlignmentInfo,
+    /// Native integer widths of the target, if specified
+    pub native_sizes: Option<HashSet<u32>>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Endianness {
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Mangling {
+    ELF,
+    MIPS,
+    MachO,
+    WindowsCOFF,
+    WindowsX86COFF,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AlignmentInfo {
+    pub abi_alignment: u32,
+    pub preferred_alignment: u32,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum AlignType {
+    Integer,
+    Vector,
+    Float,
+}
+
+/// The layout in memory of a struct type, as computed by
+/// [`Type::struct_layout()`](../enum.Type.html#method.struct_layout).
+/// All offsets and sizes here are in bytes.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StructLayout {
+    /// The fields of the struct and the padding between them, in order of offset
+    pub entries: Vec<LayoutEntry>,
+    /// Total size of the struct, including any padding at the end
+    pub size: u64,
+    /// ABI alignment of the struct
+    pub alignment: u32,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum LayoutEntry {
+    /// The field with the given index in the struct
+    Field { index: usize, offset: u64, size: u64, ty: Type },
+    /// Padding inserted to satisfy the alignment of the following field (or,
+    /// at the end of the struct, of the struct itself)
+    Padding { offset: u64, size: u64 },
+}
+
+impl Default for DataLayout {
+    /// The layout LLVM uses when the data layout string is empty
+    fn default() -> Self {
+        let align = |abi, pref| AlignmentInfo { abi_alignment: abi, preferred_alignment: pref };
+        let mut pointer_layouts = HashMap::new();
+        pointer_layouts.insert(0, (64, align(64, 64)));
+        let mut type_layouts = HashMap::new();
+        type_layouts.insert((AlignType::Integer, 1), align(8, 8));
+        type_layouts.insert((AlignType::Integer, 8), align(8, 8));
+        type_layouts.insert((AlignType::Integer, 16), align(16, 16));
+        type_layouts.insert((AlignType::Integer, 32), align(32, 32));
+        type_layouts.insert((AlignType::Integer, 64), align(32, 64));
+        type_layouts.insert((AlignType::Float, 16), align(16, 16));
+        type_layouts.insert((AlignType::Float, 32), align(32, 32));
+        type_layouts.insert((AlignType::Float, 64), align(64, 64));
+        type_layouts.insert((AlignType::Float, 128), align(128, 128));
+        type_layouts.insert((AlignType::Vector, 64), align(64, 64));
+        type_layouts.insert((AlignType::Vector, 128), align(128, 128));
+        Self {
+            endianness: Endianness::LittleEndian,
+            mangling: None,
+            stack_alignment: None,
+            pointer_layouts,
+            type_layouts,
+            aggregate_layout: align(0, 64),
+            native_sizes: None,
+        }
+    }
+}
+
+impl DataLayout {
+    /// Parse a data layout string, such as the `data_layout` of a
+    /// [`Module`](../struct.Module.html). Anything not specified in the string
+    /// takes LLVM's default value.
+    pub fn parse(layout: &str) -> Result<Self, String> {
+        let mut dl = Self::default();
+        for spec in layout.split('-').filter(|spec| !spec.is_empty()) {
+            let (kind, rest) = spec.split_at(1);
+            match kind {
+                "e" => dl.endianness = Endianness::LittleEndian,
+                "E" => dl.endianness = Endianness::BigEndian,
+                "S" => dl.stack_alignment = Some(parse_u32(rest, spec)?).filter(|&a| a != 0),
+                "m" => {
+                    dl.mangling = Some(match rest {
+                        ":e" => Mangling::ELF,
+                        ":m" => Mangling::MIPS,
+                        ":o" => Mangling::MachO,
+                        ":w" => Mangling::WindowsCOFF,
+                        ":x" => Mangling::WindowsX86COFF,
+                        _ => return Err(format!("Unknown mangling specification {:?}", spec)),
+                    })
+                },
+                "p" => {
+                    let mut fields = rest.split(':');
+                    let addr_space = match fields.next() {
+                        Some("") | None => 0,
+                        Some(s) => parse_u32(s, spec)?,
+                    };
+                    let size = parse_u32(fields.next().unwrap_or(""), spec)?;
+                    let alignment = parse_alignment(&mut fields, spec)?;
+                    dl.pointer_layouts.insert(addr_space, (size, alignment));
+                },
+                "i" | "v" | "f" => {
+                    let align_type = match kind {
+                        "i" => AlignType::Integer,
+                        "v" => AlignType::Vector,
+                        _ => AlignType::Float,
+                    };
+                    let mut fields = rest.split(':');
+                    let size = parse_u32(fields.next().unwrap_or(""), spec)?;
+                    let alignment = parse_alignment(&mut fields, spec)?;
+                    dl.type_layouts.insert((align_type, size), alignment);
+                },
+                "a" => {
+                    let mut fields = rest.split(':');
+                    fields.next(); // optional (and ignored) size
+                    dl.aggregate_layout = parse_alignment(&mut fields, spec)?;
+                },
+                "n" if rest.starts_with('i') => {}, // non-integral pointer address spaces
+                "n" => {
+                    dl.native_sizes = Some(
+                        rest.split(':')
+                            .map(|s| parse_u32(s, spec))
+                            .collect::<Result<_, _>>()?,
+                    )
+                },
+                "A" | "P" | "G" | "F" => {}, // alloca, program, and globals address spaces; function pointer alignment
+                _ => return Err(format!("Unknown data layout specification {:?}", spec)),
+            }
+        }
+        Ok(dl)
+    }
+
+    /// Get the size of a pointer in the given address space, in bits.
+    /// Address spaces without an explicit layout use address space 0's.
+    pub fn pointer_size_bits(&self, addr_space: AddrSpace) -> u32 {
+        self.pointer_layout(addr_space).0
+    }
+
+    /// Get the number of bits needed to hold a value of the given type, or
+    /// `None` if the type is unsized (e.g., a function type or an opaque struct)
+    pub fn type_size_in_bits(&self, ty: &Type) -> Option<u64> {
+        match ty {
+            Type::IntegerType { bits } => Some(u64::from(*bits)),
+            Type::PointerType { addr_space, .. } => Some(u64::from(self.pointer_size_bits(*addr_space))),
+            Type::FPType(fpt) => Some(match fpt {
+                FPType::Half => 16,
+                FPType::Single => 32,
+                FPType::Double => 64,
+                FPType::X86_FP80 => 80,
+                FPType::FP128 | FPType::PPC_FP128 => 128,
+            }),
+            Type::VectorType { element_type, num_elements } => {
+                Some(self.type_size_in_bits(element_type)? * *num_elements as u64)
+            },
+            Type::ArrayType { element_type, num_elements } => {
+                Some(self.alloc_size(element_type)? * *num_elements as u64 * 8)
+            },
+            Type::StructType { element_types, is_packed } => {
+                Some(self.struct_layout(element_types, *is_packed)?.0 * 8)
+            },
+            Type::NamedStructType { .. } => self.with_named_struct(ty, |ty| self.type_size_in_bits(ty)),
+            Type::X86_MMXType => Some(64),
+            Type::VoidType | Type::FuncType { .. } | Type::MetadataType | Type::LabelType | Type::TokenType => None,
+        }
+    }
+
+    /// Get the maximum number of bytes which may be overwritten by storing a
+    /// value of the given type
+    pub fn store_size(&self, ty: &Type) -> Option<u64> {
+        self.type_size_in_bits(ty).map(|bits| bits.div_ceil(8))
+    }
+
+    /// Get the offset in bytes between successive values of the given type
+    /// (e.g., in an array), including alignment padding
+    pub fn alloc_size(&self, ty: &Type) -> Option<u64> {
+        let store_size = self.store_size(ty)?;
+        let align = u64::from(self.abi_alignment(ty)?);
+        Some(align_to(store_size, align))
+    }
+
+    /// Get the minimum ABI-required alignment of the given type, in bytes
+    pub fn abi_alignment(&self, ty: &Type) -> Option<u32> {
+        match ty {
+            Type::IntegerType { bits } => {
+                let info = self.type_layouts.get(&(AlignType::Integer, *bits)).copied().or_else(|| {
+                    // use the smallest integer alignment which is larger than `bits`, or failing that, the largest
+                    let mut ints: Vec<(u32, AlignmentInfo)> = self
+                        .type_layouts
+                        .iter()
+                        .filter(|((align_type, _), _)| *align_type == AlignType::Integer)
+                        .map(|((_, size), info)| (*size, *info))
+                        .collect();
+                    ints.sort_by_key(|(size, _)| *size);
+                    ints.iter().find(|(size, _)| size > bits).or_else(|| ints.last()).map(|(_, info)| *info)
+                });
+                info.map(|info| bits_to_bytes(info.abi_alignment))
+            },
+            Type::PointerType { addr_space, .. } => Some(bits_to_bytes(self.pointer_layout(*addr_space).1.abi_alignment)),
+            Type::FPType(_) | Type::VectorType { .. } | Type::X86_MMXType => {
+                let align_type = match ty {
+                    Type::FPType(_) => AlignType::Float,
+                    _ => AlignType::Vector,
+                };
+                let bits = self.type_size_in_bits(ty)?;
+                match self.type_layouts.get(&(align_type, bits as u32)) {
+                    Some(info) => Some(bits_to_bytes(info.abi_alignment)),
+                    // natural alignment: the store size rounded up to a power of two
+                    None => Some(bits.div_ceil(8).next_power_of_two() as u32),
+                }
+            },
+            Type::ArrayType { element_type, .. } => self.abi_alignment(element_type),
+            Type::StructType { element_types, is_packed } => {
+                let struct_align = self.struct_layout(element_types, *is_packed)?.1;
+                Some(std::cmp::max(struct_align, bits_to_bytes(self.aggregate_layout.abi_alignment)))
+            },
+            Type::NamedStructType { .. } => self.with_named_struct(ty, |ty| self.abi_alignment(ty)),
+            Type::VoidType | Type::FuncType { .. } | Type::MetadataType | Type::LabelType | Type::TokenType => None,
+        }
+    }
+
+    /// Get the byte offset of each field of a struct with the given element
+    /// types, or `None` if any element type is unsized
+    pub fn struct_field_offsets(&self, element_types: &[Type], is_packed: bool) -> Option<Vec<u64>> {
+        let mut offsets = Vec::with_capacity(element_types.len());
+        let mut offset = 0;
+        for ty in element_types {
+            if !is_packed {
+                offset = align_to(offset, u64::from(self.abi_alignment(ty)?));
+            }
+            offsets.push(offset);
+            offset += self.alloc_size(ty)?;
+        }
+        Some(offsets)
+    }
+
+    /// Get the (size in bytes, alignment in bytes) of a struct with the given
+    /// element types, not considering the aggregate alignment
+    fn struct_layout(&self, element_types: &[Type], is_packed: bool) -> Option<(u64, u32)> {
+        let offsets = self.struct_field_offsets(element_types, is_packed)?;
+        let end = match (offsets.last(), element_types.last()) {
+            (Some(offset), Some(ty)) => offset + self.alloc_size(ty)?,
+            _ => 0,
+        };
+        let align = if is_packed {
+            1
+        } else {
+            element_types
+                .iter()
+                .map(|ty| self.abi_alignment(ty))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .max()
+                .unwrap_or(1)
+        };
+        Some((align_to(end, u64::from(align)), align))
+    }
+
+    fn pointer_layout(&self, addr_space: AddrSpace) -> (u32, AlignmentInfo) {
+        match self.pointer_layouts.get(&addr_space).or_else(|| self.pointer_layouts.get(&0)) {
+            Some(layout) => *layout,
+            None => (64, AlignmentInfo { abi_alignment: 64, preferred_alignment: 64 }),
+        }
+    }
+
+    /// Apply `f` to the body of the given `NamedStructType`, returning `None`
+    /// if it is opaque
+    fn with_named_struct<T>(&self, ty: &Type, f: impl FnOnce(&Type) -> Option<T>) -> Option<T> {
+        match ty {
+            Type::NamedStructType { ty: Some(weak), .. } => {
+                let arc = weak.upgrade().expect("Weak reference disappeared");
+                let inner = arc.read().unwrap();
+                f(inner.deref())
+            },
+            _ => None,
+        }
+    }
+}
+
+fn parse_u32(s: &str, spec: &str) -> Result<u32, String> {
+    s.parse().map_err(|_| format!("Invalid number {:?} in data layout specification {:?}", s, spec))
+}
+
+/// Parse `abi[:pref]` alignments; `pref` defaults to `abi`
+fn parse_alignment<'a>(fields: &mut impl Iterator<Item = &'a str>, spec: &str) -> Result<AlignmentInfo, String> {
+    let abi_alignment = parse_u32(fields.next().unwrap_or(""), spec)?;
+    let preferred_alignment = match fields.next() {
+        Some(s) => parse_u32(s, spec)?,
+        None => abi_alignment,
+    };
+    Ok(AlignmentInfo { abi_alignment, preferred_alignment })
+}
+
+fn bits_to_bytes(bits: u32) -> u32 {
+    std::cmp::max(bits / 8, 1)
+}
+
+fn align_to(offset: u64, align: u64) -> u64 {
+    offset.div_ceil(align) * align
+}
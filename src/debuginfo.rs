@@ -0,0 +1,437 @@
+//! Source-level types, as described by debug metadata.
+//! See [LLVM 9 docs on Source Level Debugging](https://releases.llvm.org/9.0.0/docs/SourceLevelDebugging.html)
+
+use crate::name::Name;
+
+/// Identifies a [`DIType`](enum.DIType.html), a [`DIScope`](enum.DIScope.html),
+/// a [`DIGlobalVariable`](struct.DIGlobalVariable.html), or a
+/// [`DILocalVariable`](struct.DILocalVariable.html) within its `Module`; see
+/// [`Module::resolve_ditype()`](../struct.Module.html#method.resolve_ditype),
+/// [`Module::resolve_discope()`](../struct.Module.html#method.resolve_discope),
+/// [`GlobalVariable::debug_info()`](../module/struct.GlobalVariable.html#method.debug_info), and
+/// [`Function::local_variables()`](../function/struct.Function.html#method.local_variables).
+/// Types, scopes, global variables, and local variables are numbered separately.
+///
+/// These IDs are assigned by `llvm-ir` during parsing, and do not correspond
+/// to the `!N` numbers which appear in textual LLVM IR.
+pub type MetadataNodeID = usize;
+
+/// A source-level type, decoded from debug metadata.
+///
+/// Types refer to other types by `MetadataNodeID` rather than directly,
+/// because the graph of types may be cyclic (e.g., a linked-list node which
+/// contains a pointer to its own type).
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DIType {
+    /// A builtin type, such as `int` or `char`.
+    /// See [LLVM 9 docs on DIBasicType](https://releases.llvm.org/9.0.0/docs/LangRef.html#dibasictype)
+    Basic { name: String, size_in_bits: u64 },
+    /// A type derived from another type: a pointer, reference, typedef,
+    /// qualified type (`const` etc), or a member of a `Composite` type.
+    /// See [LLVM 9 docs on DIDerivedType](https://releases.llvm.org/9.0.0/docs/LangRef.html#diderivedtype)
+    Derived {
+        tag: DITag,
+        name: Option<String>,
+        /// The type this is derived from; `None` represents `void` (e.g., for `void *`)
+        base: Option<MetadataNodeID>,
+        size_in_bits: u64,
+        /// For members, the offset of the member within its containing type
+        offset_in_bits: u64,
+    },
+    /// A struct, union, class, enum, or array type.
+    /// See [LLVM 9 docs on DICompositeType](https://releases.llvm.org/9.0.0/docs/LangRef.html#dicompositetype)
+    Composite {
+        tag: DITag,
+        name: Option<String>,
+        /// For arrays, the element type; for enums, the underlying type
+        base: Option<MetadataNodeID>,
+        /// For structs, unions, and classes, the members (which will be
+        /// `Derived` types with tag `DITag::Member`)
+        members: Vec<MetadataNodeID>,
+        size_in_bits: u64,
+    },
+    /// The type of a function: first the return type, then the parameter
+    /// types. `None` represents `void`.
+    /// See [LLVM 9 docs on DISubroutineType](https://releases.llvm.org/9.0.0/docs/LangRef.html#disubroutinetype)
+    Subroutine { types: Vec<Option<MetadataNodeID>> },
+}
+
+impl DIType {
+    /// Get the name of the type, if it has one
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            DIType::Basic { name, .. } => Some(name),
+            DIType::Derived { name, .. } => name.as_deref(),
+            DIType::Composite { name, .. } => name.as_deref(),
+            DIType::Subroutine { .. } => None,
+        }
+    }
+}
+
+/// A source-level scope containing a [`DebugLoc`](../debugloc/struct.DebugLoc.html),
+/// decoded from debug metadata. See
+/// [`DebugLoc::scope`](../debugloc/struct.DebugLoc.html#structfield.scope).
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DIScope {
+    /// A source-level function.
+    /// See [LLVM 9 docs on DISubprogram](https://releases.llvm.org/9.0.0/docs/LangRef.html#disubprogram)
+    Subprogram { name: Option<String> },
+    /// A block (e.g., the body of a loop) nested in another scope; this also
+    /// includes `DILexicalBlockFile`s.
+    /// See [LLVM 9 docs on DILexicalBlock](https://releases.llvm.org/9.0.0/docs/LangRef.html#dilexicalblock)
+    LexicalBlock { parent: Option<MetadataNodeID> },
+    /// Any other kind of scope (e.g., a namespace or compile unit)
+    Other,
+}
+
+/// A source-level global variable, decoded from the `DIGlobalVariableExpression`
+/// attached to a [`GlobalVariable`](../module/struct.GlobalVariable.html).
+/// See [LLVM 9 docs on DIGlobalVariable](https://releases.llvm.org/9.0.0/docs/LangRef.html#diglobalvariable)
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DIGlobalVariable {
+    /// The name of the variable in the source
+    pub name: Option<String>,
+    /// The mangled name of the variable, if different from `name`
+    pub linkage_name: Option<String>,
+    /// The file in which the variable is declared
+    pub filename: Option<String>,
+    /// The line on which the variable is declared (0 if unknown)
+    pub line: u32,
+    /// The source-level type of the variable
+    pub ty: Option<MetadataNodeID>,
+}
+
+/// A source-level local variable, decoded from the `DILocalVariable` operand
+/// of a call to `llvm.dbg.declare` or `llvm.dbg.value`.
+/// See [LLVM 9 docs on DILocalVariable](https://releases.llvm.org/9.0.0/docs/LangRef.html#dilocalvariable)
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DILocalVariable {
+    /// The name of the variable in the source
+    pub name: String,
+    /// The line on which the variable is declared (0 if unknown)
+    pub line: u32,
+    /// The source-level type of the variable
+    pub ty: Option<MetadataNodeID>,
+}
+
+/// The arguments of a call to `llvm.dbg.declare` or `llvm.dbg.value`. These
+/// are all metadata, so they appear among the call's `arguments` only as
+/// `Operand::MetadataOperand`s.
+/// See [`Function::local_variables()`](../function/struct.Function.html#method.local_variables).
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DbgVariableRef {
+    /// The local value the call refers to: for `llvm.dbg.declare`, the
+    /// address of the variable (typically an `alloca`); for `llvm.dbg.value`,
+    /// the variable's new value. `None` if this is not a local value (e.g.,
+    /// it is a constant or `undef`).
+    pub value: Option<Name>,
+    /// The variable, indexed by `MetadataNodeID` in
+    /// [`Module::debug_local_variables`](../module/struct.Module.html#structfield.debug_local_variables)
+    pub variable: MetadataNodeID,
+}
+
+/// The DWARF tag of a `Derived` or `Composite` type
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DITag {
+    ArrayType,
+    ClassType,
+    EnumerationType,
+    StructureType,
+    UnionType,
+    Member,
+    Inheritance,
+    PointerType,
+    PtrToMemberType,
+    ReferenceType,
+    RValueReferenceType,
+    Typedef,
+    ConstType,
+    VolatileType,
+    RestrictType,
+    AtomicType,
+    /// Any other tag, e.g., `"DW_TAG_friend"`
+    Other(String),
+}
+
+impl DITag {
+    fn from_dwarf_name(name: &str) -> Self {
+        match name {
+            "DW_TAG_array_type" => DITag::ArrayType,
+            "DW_TAG_class_type" => DITag::ClassType,
+            "DW_TAG_enumeration_type" => DITag::EnumerationType,
+            "DW_TAG_structure_type" => DITag::StructureType,
+            "DW_TAG_union_type" => DITag::UnionType,
+            "DW_TAG_member" => DITag::Member,
+            "DW_TAG_inheritance" => DITag::Inheritance,
+            "DW_TAG_pointer_type" => DITag::PointerType,
+            "DW_TAG_ptr_to_member_type" => DITag::PtrToMemberType,
+            "DW_TAG_reference_type" => DITag::ReferenceType,
+            "DW_TAG_rvalue_reference_type" => DITag::RValueReferenceType,
+            "DW_TAG_typedef" => DITag::Typedef,
+            "DW_TAG_const_type" => DITag::ConstType,
+            "DW_TAG_volatile_type" => DITag::VolatileType,
+            "DW_TAG_restrict_type" => DITag::RestrictType,
+            "DW_TAG_atomic_type" => DITag::AtomicType,
+            _ => DITag::Other(name.to_owned()),
+        }
+    }
+}
+
+// ********* //
+// from_llvm //
+// ********* //
+
+use crate::from_llvm::*;
+use crate::operand::ValToNameMap;
+use llvm_sys::debuginfo::*;
+use std::collections::HashMap;
+
+/// Assigns `MetadataNodeID`s to the debug-info types (and scopes)
+/// encountered during parsing, decoding each type (and the types it refers
+/// to) the first time it is seen
+pub(crate) struct DITypeMap {
+    ids: HashMap<LLVMMetadataRef, MetadataNodeID>,
+    types: Vec<Option<DIType>>,
+    scope_ids: HashMap<LLVMMetadataRef, MetadataNodeID>,
+    scopes: Vec<DIScope>,
+    global_vars: Vec<DIGlobalVariable>,
+    local_var_ids: HashMap<LLVMMetadataRef, MetadataNodeID>,
+    local_vars: Vec<DILocalVariable>,
+}
+
+impl DITypeMap {
+    pub(crate) fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            types: vec![],
+            scope_ids: HashMap::new(),
+            scopes: vec![],
+            global_vars: vec![],
+            local_var_ids: HashMap::new(),
+            local_vars: vec![],
+        }
+    }
+
+    /// Get the decoded types, scopes, global variables, and local variables,
+    /// each indexed by `MetadataNodeID`
+    pub(crate) fn into_parts(self) -> (Vec<DIType>, Vec<DIScope>, Vec<DIGlobalVariable>, Vec<DILocalVariable>) {
+        let types = self.types
+            .into_iter()
+            .map(|ty| ty.expect("DIType should have been decoded"))
+            .collect();
+        (types, self.scopes, self.global_vars, self.local_vars)
+    }
+
+    /// Decode the arguments of the given call, if it is a call to
+    /// `llvm.dbg.declare` or `llvm.dbg.value`. Its `DILocalVariable` is
+    /// assigned a `MetadataNodeID` the first time it is seen.
+    pub(crate) fn decode_dbg_variable(&mut self, call: LLVMValueRef, vnmap: &ValToNameMap) -> Option<DbgVariableRef> {
+        match unsafe { get_value_name(LLVMGetCalledValue(call)) }.as_str() {
+            "llvm.dbg.declare" | "llvm.dbg.value" => {},
+            _ => return None,
+        }
+        let value = match unsafe { md_node_operands(LLVMGetOperand(call, 0)) }.first() {
+            Some(&value) if !value.is_null() => vnmap.get(&value).cloned(),
+            _ => None,
+        };
+        let var = unsafe { LLVMGetOperand(call, 1) };
+        let var_md = unsafe { LLVMValueAsMetadata(var) };
+        if let Some(&id) = self.local_var_ids.get(&var_md) {
+            return Some(DbgVariableRef { value, variable: id });
+        }
+
+        // the operands of a `DILocalVariable` are its scope, name, file, and type
+        let operands = unsafe { md_node_operands(var) };
+        let name = match operands.get(1) {
+            Some(&name) if !name.is_null() => unsafe { md_string(name) }?,
+            _ => return None,
+        };
+        let decoded = DILocalVariable {
+            name,
+            line: unsafe { LLVMDIVariableGetLine(var_md) },
+            ty: self.get_or_decode(operands.get(3).copied().unwrap_or(std::ptr::null_mut())),
+        };
+        let id = self.local_vars.len();
+        self.local_vars.push(decoded);
+        self.local_var_ids.insert(var_md, id);
+        Some(DbgVariableRef { value, variable: id })
+    }
+
+    /// Decode the `DIGlobalVariable` described by the (first)
+    /// `DIGlobalVariableExpression` attached to the given global variable, if
+    /// any, and get its `MetadataNodeID`
+    pub(crate) fn decode_global_variable(&mut self, global: LLVMValueRef) -> Option<MetadataNodeID> {
+        let ctx = unsafe { LLVMGetTypeContext(LLVMTypeOf(global)) };
+        let dbg_kind = unsafe { LLVMGetMDKindIDInContext(ctx, "dbg".as_ptr() as *const _, 3) };
+        let expr = unsafe {
+            let mut num_entries = 0;
+            let entries = LLVMGlobalCopyAllMetadata(global, &mut num_entries);
+            let expr = (0..num_entries as u32)
+                .find(|&i| LLVMValueMetadataEntriesGetKind(entries, i) == dbg_kind)
+                .map(|i| LLVMValueMetadataEntriesGetMetadata(entries, i));
+            if !entries.is_null() {
+                LLVMDisposeValueMetadataEntries(entries);
+            }
+            expr?
+        };
+        if !matches!(unsafe { LLVMGetMetadataKind(expr) }, LLVMMetadataKind::LLVMDIGlobalVariableExpressionMetadataKind) {
+            return None;
+        }
+        let var = unsafe { LLVMDIGlobalVariableExpressionGetVariable(expr) };
+        if var.is_null() {
+            return None;
+        }
+
+        // the operands of a `DIGlobalVariable` are its scope, name, file,
+        // type, name (again), and linkage name
+        let operands = unsafe { md_node_operands(LLVMMetadataAsValue(ctx, var)) };
+        let operand = |i: usize| operands.get(i).copied().unwrap_or(std::ptr::null_mut());
+        let string = |md: LLVMValueRef| if md.is_null() { None } else { unsafe { md_string(md) } };
+        let file = unsafe { LLVMDIVariableGetFile(var) };
+        let decoded = DIGlobalVariable {
+            name: string(operand(1)),
+            linkage_name: string(operand(5)),
+            filename: if file.is_null() {
+                None
+            } else {
+                unsafe {
+                    let mut len = 0;
+                    let ptr = LLVMDIFileGetFilename(file, &mut len);
+                    string_from_parts(ptr, len as usize)
+                }
+            },
+            line: unsafe { LLVMDIVariableGetLine(var) },
+            ty: self.get_or_decode(operand(3)),
+        };
+        let id = self.global_vars.len();
+        self.global_vars.push(decoded);
+        Some(id)
+    }
+
+    /// Get the `MetadataNodeID` of the given scope, decoding it (and its
+    /// parent scopes) if we haven't seen it before.
+    /// Returns `None` if `scope` is null.
+    pub(crate) fn get_or_decode_scope(&mut self, ctx: LLVMContextRef, scope: LLVMMetadataRef) -> Option<MetadataNodeID> {
+        if scope.is_null() {
+            return None;
+        }
+        if let Some(&id) = self.scope_ids.get(&scope) {
+            return Some(id);
+        }
+        // the operands of a scope begin with its file and parent scope; for a
+        // `DISubprogram`, those are followed by its name
+        let operands = unsafe { md_node_operands(LLVMMetadataAsValue(ctx, scope)) };
+        let decoded = match unsafe { LLVMGetMetadataKind(scope) } {
+            LLVMMetadataKind::LLVMDISubprogramMetadataKind => DIScope::Subprogram {
+                name: operands.get(2).and_then(|&name| if name.is_null() { None } else { unsafe { md_string(name) } }),
+            },
+            LLVMMetadataKind::LLVMDILexicalBlockMetadataKind | LLVMMetadataKind::LLVMDILexicalBlockFileMetadataKind => {
+                DIScope::LexicalBlock {
+                    parent: match operands.get(1) {
+                        Some(&parent) if !parent.is_null() => self.get_or_decode_scope(ctx, unsafe { LLVMValueAsMetadata(parent) }),
+                        _ => None,
+                    },
+                }
+            },
+            _ => DIScope::Other,
+        };
+        let id = self.scopes.len();
+        self.scope_ids.insert(scope, id);
+        self.scopes.push(decoded);
+        Some(id)
+    }
+
+    /// Get the `MetadataNodeID` of the type wrapped by the given
+    /// `MetadataAsValue`, decoding it if we haven't seen it before.
+    /// Returns `None` if `md` is null or is not a type.
+    pub(crate) fn get_or_decode(&mut self, md: LLVMValueRef) -> Option<MetadataNodeID> {
+        if md.is_null() {
+            return None;
+        }
+        let mdref = unsafe { LLVMValueAsMetadata(md) };
+        if let Some(&id) = self.ids.get(&mdref) {
+            return Some(id);
+        }
+        let kind = unsafe { LLVMGetMetadataKind(mdref) };
+        match kind {
+            LLVMMetadataKind::LLVMDIBasicTypeMetadataKind
+            | LLVMMetadataKind::LLVMDIDerivedTypeMetadataKind
+            | LLVMMetadataKind::LLVMDICompositeTypeMetadataKind
+            | LLVMMetadataKind::LLVMDISubroutineTypeMetadataKind => {},
+            _ => return None,
+        }
+
+        // reserve the ID before decoding the operands, so that cycles terminate
+        let id = self.types.len();
+        self.ids.insert(mdref, id);
+        self.types.push(None);
+
+        // the operands of any DIType begin with its file, scope, name, and
+        // (except for basic types) base type; composite types then have their
+        // elements
+        let operands = unsafe { md_node_operands(md) };
+        let operand = |i: usize| operands.get(i).copied().unwrap_or(std::ptr::null_mut());
+        let name = unsafe {
+            let mut len = 0;
+            let ptr = LLVMDITypeGetName(mdref, &mut len);
+            string_from_parts(ptr, len)
+        };
+        let size_in_bits = unsafe { LLVMDITypeGetSizeInBits(mdref) };
+        let ty = match kind {
+            LLVMMetadataKind::LLVMDIBasicTypeMetadataKind => DIType::Basic {
+                name: name.unwrap_or_default(),
+                size_in_bits,
+            },
+            LLVMMetadataKind::LLVMDIDerivedTypeMetadataKind => DIType::Derived {
+                tag: dwarf_tag(md),
+                name,
+                base: self.get_or_decode(operand(3)),
+                size_in_bits,
+                offset_in_bits: unsafe { LLVMDITypeGetOffsetInBits(mdref) },
+            },
+            LLVMMetadataKind::LLVMDICompositeTypeMetadataKind => DIType::Composite {
+                tag: dwarf_tag(md),
+                name,
+                base: self.get_or_decode(operand(3)),
+                members: self.get_or_decode_all(operand(4)).into_iter().flatten().collect(),
+                size_in_bits,
+            },
+            LLVMMetadataKind::LLVMDISubroutineTypeMetadataKind => DIType::Subroutine {
+                types: self.get_or_decode_all(operand(3)),
+            },
+            _ => unreachable!(),
+        };
+        self.types[id] = Some(ty);
+        Some(id)
+    }
+
+    /// Call `get_or_decode()` on each element of the given tuple (if it isn't null)
+    fn get_or_decode_all(&mut self, tuple: LLVMValueRef) -> Vec<Option<MetadataNodeID>> {
+        if tuple.is_null() {
+            return vec![];
+        }
+        unsafe { md_node_operands(tuple) }
+            .into_iter()
+            .map(|md| self.get_or_decode(md))
+            .collect()
+    }
+}
+
+/// The LLVM 9 C API has no way to get the DWARF tag of a debug-info node, so
+/// we recover it from the node's textual representation, which will be
+/// something like `!DIDerivedType(tag: DW_TAG_pointer_type, ...)`
+fn dwarf_tag(md: LLVMValueRef) -> DITag {
+    let text = unsafe { print_to_string(md) };
+    let tag = text
+        .split("tag: ")
+        .nth(1)
+        .and_then(|rest| rest.split([',', ')']).next())
+        .unwrap_or("");
+    DITag::from_dwarf_name(tag)
+}
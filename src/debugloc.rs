@@ -1,38 +1,101 @@
+use crate::debuginfo::{DIScope, MetadataNodeID};
+use crate::module::Module;
 use std::cmp::{PartialOrd, Ordering};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 /// Describes a "debug location" (source location)
-#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DebugLoc {
     /// The source line number
     pub line: u32,
     /// The source column number
     ///
-    /// `Instruction`s and `Terminator`s have this info (and will have `Some`
-    /// here), while `GlobalVariable`s and `Function`s do not have this info (and
-    /// will have `None`)
+    /// `Instruction`s and `Terminator`s usually have this info (and will have
+    /// `Some` here), while `GlobalVariable`s and `Function`s do not have this
+    /// info (and will have `None`). Instructions whose column is recorded as
+    /// 0, meaning no column information, also have `None` here.
     pub col: Option<u32>,
     /// The source filename
     pub filename: String,
     /// The source directory, if available
     pub directory: Option<String>,
+    /// The name of the source-level function (`DISubprogram`) containing
+    /// this location, if available.
+    ///
+    /// `GlobalVariable`s and `Function`s will have `None` here.
+    pub function: Option<String>,
+    /// If this location is in code which was inlined, the location of the
+    /// call site it was inlined at (which may itself have been inlined).
+    /// See [`Module::inline_chain()`](../module/struct.Module.html#method.inline_chain).
+    pub inlined_at: Option<Box<DebugLoc>>,
+    /// The innermost source-level scope (a `DISubprogram`, or a lexical block
+    /// nested in one) containing this location, which can be looked up with
+    /// [`Module::resolve_discope()`](../module/struct.Module.html#method.resolve_discope).
+    ///
+    /// `GlobalVariable`s and `Function`s will have `None` here.
+    ///
+    /// `MetadataNodeID`s are only meaningful within the `Module` they came
+    /// from, so this is not considered when comparing or hashing `DebugLoc`s.
+    pub scope: Option<MetadataNodeID>,
+}
+
+impl DebugLoc {
+    /// Get the `DISubprogram` scope containing this location, walking up
+    /// through any enclosing lexical blocks from
+    /// [`scope`](struct.DebugLoc.html#structfield.scope).
+    /// The returned ID can be looked up with
+    /// [`Module::resolve_discope()`](../module/struct.Module.html#method.resolve_discope).
+    pub fn subprogram_scope(&self, module: &Module) -> Option<MetadataNodeID> {
+        let mut scope = self.scope;
+        while let Some(id) = scope {
+            scope = match module.resolve_discope(id)? {
+                DIScope::Subprogram { .. } => return Some(id),
+                DIScope::LexicalBlock { parent } => *parent,
+                DIScope::Other => return None,
+            };
+        }
+        None
+    }
+}
+
+/// The fields of a `DebugLoc` which are considered when comparing or hashing
+type DebugLocKey<'a> = (&'a Option<String>, &'a String, &'a u32, &'a Option<u32>, &'a Option<String>, &'a Option<Box<DebugLoc>>);
+
+impl DebugLoc {
+    /// The fields which are considered when comparing or hashing `DebugLoc`s:
+    /// everything except the `scope`
+    fn key(&self) -> DebugLocKey<'_> {
+        (&self.directory, &self.filename, &self.line, &self.col, &self.function, &self.inlined_at)
+    }
+}
+
+impl PartialEq for DebugLoc {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for DebugLoc {}
+
+impl Hash for DebugLoc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
 }
 
 impl PartialOrd for DebugLoc {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        // compare in the order (directory, filename, line, col)
-        Some(
-            (&self.directory, &self.filename, &self.line, &self.col)
-                .cmp(&(&other.directory, &other.filename, &other.line, &other.col))
-        )
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for DebugLoc {
     fn cmp(&self, other: &Self) -> Ordering {
-        // compare in the order (directory, filename, line, col)
-        (&self.directory, &self.filename, &self.line, &self.col)
-            .cmp(&(&other.directory, &other.filename, &other.line, &other.col))
+        // compare in the order (directory, filename, line, col), then by the
+        // inlining information
+        self.key().cmp(&other.key())
     }
 }
 
@@ -67,6 +130,18 @@ impl fmt::Display for DebugLoc {
     }
 }
 
+/// One frame of an inline chain, as produced by
+/// [`Module::inline_chain()`](../module/struct.Module.html#method.inline_chain)
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct Frame {
+    /// The name of the source-level function, if available
+    pub function: Option<String>,
+    /// The source line number
+    pub line: u32,
+    /// The source column number, if available
+    pub col: Option<u32>,
+}
+
 pub trait HasDebugLoc {
     /// Returns the `DebugLoc` associated with the given `Instruction`,
     /// `Terminator`, `GlobalVariable`, or `Function`; or `None` if it doesn't
@@ -84,7 +159,9 @@ pub trait HasDebugLoc {
 // from_llvm //
 // ********* //
 
+use crate::debuginfo::DITypeMap;
 use crate::from_llvm::*;
+use llvm_sys::debuginfo::*;
 
 impl DebugLoc {
     /// `value`: must represent an Instruction, Terminator, GlobalVariable, or Function
@@ -98,6 +175,9 @@ impl DebugLoc {
                 col: None,
                 filename,
                 directory: unsafe { get_debugloc_directory(value) },
+                function: None,
+                inlined_at: None,
+                scope: None,
             }),
         }
     }
@@ -108,10 +188,82 @@ impl DebugLoc {
     pub(crate) fn from_llvm_with_col(value: LLVMValueRef) -> Option<Self> {
         match Self::from_llvm_no_col(value) {
             Some(mut debugloc) => {
-                debugloc.col = Some(unsafe { LLVMGetDebugLocColumn(value) });
+                debugloc.col = Some(unsafe { LLVMGetDebugLocColumn(value) }).filter(|&col| col != 0);
+                let diloc = unsafe { LLVMInstructionGetDebugLoc(value) };
+                if !diloc.is_null() {
+                    let ctx = unsafe { LLVMGetTypeContext(LLVMTypeOf(value)) };
+                    debugloc.function = unsafe { subprogram_name(ctx, LLVMDILocationGetScope(diloc)) };
+                    debugloc.inlined_at = unsafe { Self::inlined_at(ctx, diloc) };
+                }
                 Some(debugloc)
             },
             None => None,
         }
     }
+
+    /// Decode the `inlinedAt` location (if any) of the given `DILocation`
+    unsafe fn inlined_at(ctx: LLVMContextRef, diloc: LLVMMetadataRef) -> Option<Box<Self>> {
+        let inlined_at = LLVMDILocationGetInlinedAt(diloc);
+        if inlined_at.is_null() {
+            return None;
+        }
+        let scope = LLVMDILocationGetScope(inlined_at);
+        let file = LLVMDIScopeGetFile(scope);
+        let (filename, directory) = if file.is_null() {
+            (None, None)
+        } else {
+            let mut len = 0;
+            let filename = string_from_parts(LLVMDIFileGetFilename(file, &mut len), len as usize);
+            let directory = string_from_parts(LLVMDIFileGetDirectory(file, &mut len), len as usize);
+            (filename, directory)
+        };
+        Some(Box::new(Self {
+            line: LLVMDILocationGetLine(inlined_at),
+            col: Some(LLVMDILocationGetColumn(inlined_at)).filter(|&col| col != 0),
+            filename: filename.unwrap_or_default(),
+            directory,
+            function: subprogram_name(ctx, scope),
+            inlined_at: Self::inlined_at(ctx, inlined_at),
+            scope: None,
+        }))
+    }
+
+    /// Fill in the `scope` of the given `DebugLoc` of the instruction or
+    /// terminator `inst`, and of the locations it was inlined at
+    pub(crate) fn decode_scopes(debugloc: &mut Option<Self>, inst: LLVMValueRef, ditypemap: &mut DITypeMap) {
+        let ctx = unsafe { LLVMGetTypeContext(LLVMTypeOf(inst)) };
+        let mut diloc = unsafe { LLVMInstructionGetDebugLoc(inst) };
+        let mut debugloc = debugloc.as_mut();
+        while let Some(loc) = debugloc {
+            if diloc.is_null() {
+                break;
+            }
+            loc.scope = ditypemap.get_or_decode_scope(ctx, unsafe { LLVMDILocationGetScope(diloc) });
+            diloc = unsafe { LLVMDILocationGetInlinedAt(diloc) };
+            debugloc = loc.inlined_at.as_deref_mut();
+        }
+    }
+}
+
+/// Get the name of the `DISubprogram` which is, or (through lexical blocks)
+/// contains, the given scope
+unsafe fn subprogram_name(ctx: LLVMContextRef, mut scope: LLVMMetadataRef) -> Option<String> {
+    while !scope.is_null() {
+        // the operands of a scope begin with its file and parent scope; for a
+        // `DISubprogram`, those are followed by its name
+        let operands = md_node_operands(LLVMMetadataAsValue(ctx, scope));
+        match LLVMGetMetadataKind(scope) {
+            LLVMMetadataKind::LLVMDISubprogramMetadataKind => {
+                return operands.get(2).and_then(|&name| if name.is_null() { None } else { md_string(name) });
+            },
+            LLVMMetadataKind::LLVMDILexicalBlockMetadataKind | LLVMMetadataKind::LLVMDILexicalBlockFileMetadataKind => {
+                scope = match operands.get(1) {
+                    Some(&parent) if !parent.is_null() => LLVMValueAsMetadata(parent),
+                    _ => return None,
+                };
+            },
+            _ => return None,
+        }
+    }
+    None
 }
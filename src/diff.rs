@@ -0,0 +1,127 @@
+use crate::function::Function;
+use crate::module::Module;
+use crate::name::Name;
+
+/// A structural summary of the differences between two `Module`s, as
+/// produced by [`Module::diff()`](../struct.Module.html#method.diff).
+///
+/// Functions and global variables are matched up by name. Debug information
+/// (`DebugLoc`s, and the debug information of local and global variables) is
+/// ignored, so that changes which only affect source locations aren't
+/// reported.
+///
+/// With the `serde` feature, `ModuleDiff` can be serialized (e.g., to JSON).
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleDiff {
+    /// Functions defined in the new `Module` but not the old one
+    pub added_functions: Vec<String>,
+    /// Functions defined in the old `Module` but not the new one
+    pub removed_functions: Vec<String>,
+    /// Functions defined in both `Module`s, but differently
+    pub changed_functions: Vec<FunctionDiff>,
+    /// Global variables in the new `Module` but not the old one
+    pub added_globals: Vec<Name>,
+    /// Global variables in the old `Module` but not the new one
+    pub removed_globals: Vec<Name>,
+    /// Global variables in both `Module`s, but which differ (e.g., in their
+    /// initializers)
+    pub changed_globals: Vec<Name>,
+}
+
+/// A summary of the differences between two versions of a `Function`.
+///
+/// Basic blocks are matched up by name.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionDiff {
+    pub name: String,
+    /// Basic blocks in the new version but not the old one
+    pub added_blocks: Vec<Name>,
+    /// Basic blocks in the old version but not the new one
+    pub removed_blocks: Vec<Name>,
+    /// Basic blocks in both versions, but which differ
+    pub changed_blocks: Vec<Name>,
+    /// Number of instructions (including terminators) in the old version
+    pub old_instruction_count: usize,
+    /// Number of instructions (including terminators) in the new version
+    pub new_instruction_count: usize,
+}
+
+impl ModuleDiff {
+    /// Are the two `Module`s the same (for the purposes of the diff)?
+    pub fn is_empty(&self) -> bool {
+        self.added_functions.is_empty()
+            && self.removed_functions.is_empty()
+            && self.changed_functions.is_empty()
+            && self.added_globals.is_empty()
+            && self.removed_globals.is_empty()
+            && self.changed_globals.is_empty()
+    }
+
+    pub(crate) fn between(old: &Module, new: &Module) -> Self {
+        let mut diff = Self::default();
+        if old.structural_hash() == new.structural_hash() {
+            return diff;
+        }
+        for old_func in &old.functions {
+            match new.get_func_by_name(&old_func.name) {
+                None => diff.removed_functions.push(old_func.name.clone()),
+                Some(new_func) if new_func.structural_hash() != old_func.structural_hash() => {
+                    diff.changed_functions.push(FunctionDiff::between(&old_func.without_debug_info(), &new_func.without_debug_info()))
+                },
+                Some(_) => {},
+            }
+        }
+        for new_func in &new.functions {
+            if old.get_func_by_name(&new_func.name).is_none() {
+                diff.added_functions.push(new_func.name.clone());
+            }
+        }
+        for old_var in &old.global_vars {
+            match new.get_global_var_by_name(&old_var.name) {
+                None => diff.removed_globals.push(old_var.name.clone()),
+                Some(new_var) if new_var.without_debug_info() != old_var.without_debug_info() => {
+                    diff.changed_globals.push(old_var.name.clone())
+                },
+                Some(_) => {},
+            }
+        }
+        for new_var in &new.global_vars {
+            if old.get_global_var_by_name(&new_var.name).is_none() {
+                diff.added_globals.push(new_var.name.clone());
+            }
+        }
+        diff
+    }
+}
+
+impl FunctionDiff {
+    fn between(old: &Function, new: &Function) -> Self {
+        let mut diff = Self {
+            name: old.name.clone(),
+            added_blocks: vec![],
+            removed_blocks: vec![],
+            changed_blocks: vec![],
+            old_instruction_count: instruction_count(old),
+            new_instruction_count: instruction_count(new),
+        };
+        for old_bb in &old.basic_blocks {
+            match new.get_bb_by_name(&old_bb.name) {
+                None => diff.removed_blocks.push(old_bb.name.clone()),
+                Some(new_bb) if new_bb != old_bb => diff.changed_blocks.push(old_bb.name.clone()),
+                Some(_) => {},
+            }
+        }
+        for new_bb in &new.basic_blocks {
+            if old.get_bb_by_name(&new_bb.name).is_none() {
+                diff.added_blocks.push(new_bb.name.clone());
+            }
+        }
+        diff
+    }
+}
+
+fn instruction_count(func: &Function) -> usize {
+    func.basic_blocks.iter().map(|bb| bb.instrs.len() + 1).sum()
+}
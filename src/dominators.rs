@@ -0,0 +1,173 @@
+use crate::cfg::Cfg;
+use crate::function::Function;
+use crate::name::Name;
+use std::collections::{HashMap, HashSet};
+
+/// The dominator tree of a [`Function`](../struct.Function.html): block `a`
+/// dominates block `b` if every path from the entry block to `b` passes
+/// through `a`.
+///
+/// Only blocks reachable from the entry block are included.
+#[derive(Clone, Debug)]
+pub struct DominatorTree<'f> {
+    /// Immediate dominator of each reachable block; the entry block has none
+    idoms: HashMap<&'f Name, Option<&'f Name>>,
+    /// Dominance frontier of each reachable block
+    frontiers: HashMap<&'f Name, Vec<&'f Name>>,
+}
+
+impl<'f> DominatorTree<'f> {
+    /// Construct the dominator tree of the given `Function`
+    pub fn new(function: &'f Function) -> Self {
+        let cfg = Cfg::new(function);
+        let rpo = reverse_postorder(&cfg);
+        let rpo_index: HashMap<&'f Name, usize> = rpo.iter().enumerate().map(|(i, &block)| (block, i)).collect();
+
+        // "A Simple, Fast Dominance Algorithm" (Cooper, Harvey, and Kennedy),
+        // operating on indices into `rpo`. The entry block is its own idom
+        // until the end.
+        let mut idoms: Vec<Option<usize>> = vec![None; rpo.len()];
+        if !rpo.is_empty() {
+            idoms[0] = Some(0);
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, &block) in rpo.iter().enumerate().skip(1) {
+                let mut new_idom: Option<usize> = None;
+                for pred in cfg.predecessors(block) {
+                    let pred = match rpo_index.get(pred) {
+                        Some(&pred) if idoms[pred].is_some() => pred,
+                        _ => continue, // unreachable, or not yet processed
+                    };
+                    new_idom = Some(match new_idom {
+                        None => pred,
+                        Some(new_idom) => intersect(&idoms, pred, new_idom),
+                    });
+                }
+                if new_idom.is_some() && idoms[i] != new_idom {
+                    idoms[i] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        // dominance frontiers, per Cooper, Harvey, and Kennedy's Figure 5
+        let mut frontiers: HashMap<&'f Name, Vec<&'f Name>> = rpo.iter().map(|&block| (block, vec![])).collect();
+        for (i, &block) in rpo.iter().enumerate() {
+            let preds: Vec<usize> = cfg
+                .predecessors(block)
+                .iter()
+                .filter_map(|pred| rpo_index.get(pred).copied())
+                .collect();
+            if preds.len() < 2 {
+                continue;
+            }
+            let idom = idoms[i].expect("reachable block should have an idom");
+            for mut runner in preds {
+                while runner != idom {
+                    let frontier = frontiers.get_mut(rpo[runner]).unwrap();
+                    if !frontier.contains(&block) {
+                        frontier.push(block);
+                    }
+                    runner = idoms[runner].expect("reachable block should have an idom");
+                }
+            }
+        }
+
+        let idoms = rpo
+            .iter()
+            .enumerate()
+            .map(|(i, &block)| (block, if i == 0 { None } else { idoms[i].map(|idom| rpo[idom]) }))
+            .collect();
+        Self { idoms, frontiers }
+    }
+
+    /// Get the `Name` of the immediate dominator of the given block, or `None`
+    /// if the block is the entry block (or is unreachable)
+    pub fn idom(&self, block: &Name) -> Option<&'f Name> {
+        self.idoms.get(block).copied().flatten()
+    }
+
+    /// Does block `a` dominate block `b`? (Every block dominates itself.)
+    pub fn dominates(&self, a: &Name, b: &Name) -> bool {
+        if !self.idoms.contains_key(b) {
+            return false;
+        }
+        let mut block = Some(b);
+        while let Some(cur) = block {
+            if cur == a {
+                return true;
+            }
+            block = self.idom(cur).map(|idom| idom as &Name);
+        }
+        false
+    }
+
+    /// Get the `Name`s of the blocks in the dominance frontier of the given
+    /// block: the blocks which have a predecessor dominated by the given block
+    /// but are not themselves strictly dominated by it
+    pub fn dominance_frontier(&self, block: &Name) -> &[&'f Name] {
+        self.frontiers.get(block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the `Name`s of the blocks which would need a `Phi` for a variable
+    /// which is assigned in each of the given blocks, i.e., the iterated
+    /// dominance frontier of `def_blocks`.
+    ///
+    /// This is the placement used by the classic SSA construction algorithm
+    /// (Cytron et al.), so some of the resulting `Phi`s may be dead.
+    pub fn phi_placement(&self, def_blocks: &HashSet<Name>) -> HashSet<Name> {
+        let mut phi_blocks: HashSet<&'f Name> = HashSet::new();
+        let mut worklist: Vec<&Name> = def_blocks.iter().collect();
+        while let Some(block) = worklist.pop() {
+            for &frontier_block in self.dominance_frontier(block) {
+                if phi_blocks.insert(frontier_block) {
+                    // the new phi is itself a definition
+                    worklist.push(frontier_block);
+                }
+            }
+        }
+        phi_blocks.into_iter().cloned().collect()
+    }
+}
+
+/// Get the `Name`s of the blocks reachable from the entry block, in reverse
+/// postorder
+fn reverse_postorder<'f>(cfg: &Cfg<'f>) -> Vec<&'f Name> {
+    let mut postorder: Vec<&'f Name> = vec![];
+    let mut visited: HashSet<&'f Name> = HashSet::new();
+    // each stack entry is a block and the index of its next successor to visit
+    let mut stack: Vec<(&'f Name, usize)> = vec![];
+    if let Some(entry) = cfg.entry() {
+        visited.insert(entry);
+        stack.push((entry, 0));
+    }
+    while let Some((block, next_succ)) = stack.pop() {
+        match cfg.successors(block).get(next_succ) {
+            Some(&succ) => {
+                stack.push((block, next_succ + 1));
+                if visited.insert(succ) {
+                    stack.push((succ, 0));
+                }
+            },
+            None => postorder.push(block),
+        }
+    }
+    postorder.reverse();
+    postorder
+}
+
+/// Find the nearest common dominator of the blocks with reverse-postorder
+/// indices `a` and `b`
+fn intersect(idoms: &[Option<usize>], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while a > b {
+            a = idoms[a].expect("processed block should have an idom");
+        }
+        while b > a {
+            b = idoms[b].expect("processed block should have an idom");
+        }
+    }
+    a
+}
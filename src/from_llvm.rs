@@ -1,6 +1,7 @@
 pub use crate::iterators::*;
 pub use llvm_sys::core::*;
 pub use llvm_sys::prelude::*;
+use std::cell::RefCell;
 use std::ffi::CStr;
 use std::os::raw::c_char;
 
@@ -80,31 +81,66 @@ pub unsafe fn op_to_bb(op: LLVMValueRef) -> LLVMBasicBlockRef {
     LLVMValueAsBasicBlock(op)
 }
 
-macro_rules! debug {
-    ($($arg:expr),+) => {
-        if log::log_enabled!(log::Level::Debug) {
-            log::debug!($($arg),+)
-        }
-    };
+// Get the numeric kind of the enum attribute with the given name (e.g., "noreturn").
+// Returns 0 if there is no such attribute.
+pub fn enum_attribute_kind_for_name(name: &str) -> u32 {
+    unsafe { LLVMGetEnumAttributeKindForName(name.as_ptr() as *const _, name.len()) }
 }
 
-/// LLVM Context wrapper that frees the underlying context when the wrapper is dropped
-pub struct Context {
-    pub ctx: LLVMContextRef,
+// Get the operands of the metadata node wrapped by the given
+// `MetadataAsValue` (null for null operands).
+// For a wrapped `ValueAsMetadata`, this is the wrapped value.
+pub unsafe fn md_node_operands(md: LLVMValueRef) -> Vec<LLVMValueRef> {
+    let num_operands = LLVMGetMDNodeNumOperands(md) as usize;
+    let mut operands: Vec<LLVMValueRef> = Vec::with_capacity(num_operands);
+    LLVMGetMDNodeOperands(md, operands.as_mut_ptr());
+    operands.set_len(num_operands);
+    operands
 }
 
-impl Context {
-    pub fn new() -> Self {
-        Self {
-            ctx: unsafe { LLVMContextCreate() },
-        }
+// Get the contents of the `MDString` wrapped by the given `MetadataAsValue`,
+// or `None` if it is empty or not an `MDString`
+pub unsafe fn md_string(md: LLVMValueRef) -> Option<String> {
+    let mut len = 0;
+    let ptr = LLVMGetMDString(md, &mut len);
+    string_from_parts(ptr, len as usize)
+}
+
+// For strings given by pointer and length. LLVM returns these as a null
+// pointer when empty, and they need not be null-terminated, so we can't use
+// `raw_to_string`. Returns `None` for empty strings.
+pub unsafe fn string_from_parts(ptr: *const c_char, len: usize) -> Option<String> {
+    if ptr.is_null() || len == 0 {
+        None
+    } else {
+        Some(String::from_utf8_lossy(std::slice::from_raw_parts(ptr as *const u8, len)).into_owned())
     }
 }
 
-impl Drop for Context {
-    fn drop(&mut self) {
-        unsafe {
-            LLVMContextDispose(self.ctx);
+thread_local! {
+    static DECODE_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Record an error encountered while decoding something whose decoder can't
+// return one directly (e.g., a value printed inside a `Constant`). Parsing a
+// `Module` or `Function` fails with the first such error on the thread; see
+// `take_decode_error()`.
+pub fn record_decode_error(error: String) {
+    DECODE_ERROR.with(|slot| {
+        slot.borrow_mut().get_or_insert(error);
+    });
+}
+
+// Get (and clear) the first error recorded with `record_decode_error()` on
+// this thread since the last call
+pub fn take_decode_error() -> Option<String> {
+    DECODE_ERROR.with(|slot| slot.borrow_mut().take())
+}
+
+macro_rules! debug {
+    ($($arg:expr),+) => {
+        if log::log_enabled!(log::Level::Debug) {
+            log::debug!($($arg),+)
         }
-    }
+    };
 }
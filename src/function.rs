@@ -1,13 +1,23 @@
 use crate::basicblock::BasicBlock;
+use crate::cfg::Cfg;
 use crate::constant::Constant;
+use crate::datalayout::DataLayout;
+use crate::debuginfo::{DIType, DbgVariableRef, MetadataNodeID};
 use crate::debugloc::{DebugLoc, HasDebugLoc};
-use crate::module::{Comdat, DLLStorageClass, Linkage, Visibility};
+use crate::instruction::{Call, Instruction};
+use crate::module::{Comdat, DLLStorageClass, InstructionRef, Linkage, Module, Visibility};
 use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::{Br, Terminator};
 use crate::types::{Type, Typed};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::num;
 
 /// See [LLVM 9 docs on Functions](https://releases.llvm.org/9.0.0/docs/LangRef.html#functions)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function {
     pub name: String,
     pub parameters: Vec<Parameter>,
@@ -25,7 +35,12 @@ pub struct Function {
     pub alignment: u32,
     /// See [LLVM 9 docs on Garbage Collector Strategy Names](https://releases.llvm.org/9.0.0/docs/LangRef.html#gc)
     pub garbage_collector_name: Option<String>,
-    // pub prefix: Option<Constant>,  // appears to not be exposed in the LLVM C API, only the C++ API
+    /// Data placed immediately before the function's entry point.
+    /// See [LLVM 9 docs on Prefix Data](https://releases.llvm.org/9.0.0/docs/LangRef.html#prefixdata)
+    pub prefix_data: Option<Constant>,
+    /// Data (typically code) placed at the start of the function body.
+    /// See [LLVM 9 docs on Prologue Data](https://releases.llvm.org/9.0.0/docs/LangRef.html#prologuedata)
+    pub prologue_data: Option<Constant>,
     /// Personalities are used for exception handling. See [LLVM 9 docs on Personality Function](https://releases.llvm.org/9.0.0/docs/LangRef.html#personalityfn)
     pub personality_function: Option<Constant>,
     pub debugloc: Option<DebugLoc>,
@@ -59,6 +74,526 @@ impl Function {
         None
     }
 
+    /// Get the source-level local variables described by the
+    /// `llvm.dbg.declare` and `llvm.dbg.value` calls in the `Function`, in
+    /// order.
+    /// `module` must be the `Module` containing this `Function`.
+    /// See [LLVM 9 docs on Source Level Debugging](https://releases.llvm.org/9.0.0/docs/SourceLevelDebugging.html#debugger-intrinsic-functions)
+    pub fn local_variables(&self, module: &Module) -> Vec<LocalVariable> {
+        self.basic_blocks
+            .iter()
+            .flat_map(|bb| &bb.instrs)
+            .filter_map(|inst| {
+                let call = match inst {
+                    Instruction::Call(call) => call,
+                    _ => return None,
+                };
+                let dbg = call.debug_variable.as_ref()?;
+                let var = module.debug_local_variables.get(dbg.variable)?;
+                Some(LocalVariable {
+                    name: var.name.clone(),
+                    line: var.line,
+                    type_name: var.ty.and_then(|ty| module.resolve_ditype(ty)).and_then(DIType::name).map(str::to_owned),
+                    value: dbg.value.clone(),
+                    is_declare: call.called_function_name() == Some("llvm.dbg.declare"),
+                    ty: var.ty,
+                })
+            })
+            .collect()
+    }
+
+    /// A hash of the `Function`'s structure (its signature, attributes, and
+    /// body), ignoring debug information: `DebugLoc`s and local variable
+    /// information. `Function`s with different structural hashes differ in
+    /// more than just debug information.
+    /// See also [`Module::diff()`](../module/struct.Module.html#method.diff).
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.without_debug_info().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// A copy of the `Function` with its `DebugLoc`s and local variable
+    /// information removed
+    pub(crate) fn without_debug_info(&self) -> Self {
+        let mut func = self.clone();
+        func.debugloc = None;
+        for bb in &mut func.basic_blocks {
+            for inst in &mut bb.instrs {
+                *inst.debugloc_mut() = None;
+                if let Instruction::Call(call) = inst {
+                    call.debug_variable = None;
+                }
+            }
+            *bb.term.debugloc_mut() = None;
+        }
+        func
+    }
+
+    /// Get the `Name`s of the blocks which may transfer control to the given
+    /// block, in the order they appear in the `Function`.
+    ///
+    /// This scans every terminator in the `Function`; to query the
+    /// predecessors of many blocks, build a [`Cfg`](../cfg/struct.Cfg.html)
+    /// instead.
+    pub fn predecessors_of(&self, block: &Name) -> Vec<&Name> {
+        self.basic_blocks
+            .iter()
+            .filter(|bb| bb.term.successors().contains(&block))
+            .map(|bb| &bb.name)
+            .collect()
+    }
+
+    /// Get the entry block of the `Function` (i.e., its first `BasicBlock`).
+    /// Returns `None` only for a `Function` with no `BasicBlock`s, which does
+    /// not occur for `Function`s parsed from a `Module`.
+    pub fn entry_block(&self) -> Option<&BasicBlock> {
+        self.basic_blocks.first()
+    }
+
+    /// Get all of the `Ret` terminators in the `Function`, along with the
+    /// `BasicBlock`s they terminate
+    pub fn returns(&self) -> Vec<(&BasicBlock, &Terminator)> {
+        self.basic_blocks
+            .iter()
+            .filter(|bb| matches!(bb.term, Terminator::Ret(_)))
+            .map(|bb| (bb, &bb.term))
+            .collect()
+    }
+
+    /// Get the operands returned by each of the `Function`'s `Ret`
+    /// terminators, in the same order as [`returns()`](struct.Function.html#method.returns).
+    /// `ret void` gives `None`.
+    pub fn return_values(&self) -> Vec<Option<&Operand>> {
+        self.basic_blocks
+            .iter()
+            .filter_map(|bb| match &bb.term {
+                Terminator::Ret(ret) => Some(ret.return_operand.as_ref()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the number of parameters the `Function` declares. For a variadic
+    /// `Function` (see `is_var_arg`), this is the number of fixed parameters,
+    /// not counting any variadic arguments passed by a particular call.
+    pub fn num_fixed_params(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Get the type of the `i`th declared parameter, or `None` if the
+    /// `Function` has `i` or fewer parameters
+    pub fn param_type(&self, i: usize) -> Option<&Type> {
+        self.parameters.get(i).map(|param| &param.ty)
+    }
+
+    /// Does the `Function` have the enum attribute with the given name (e.g.,
+    /// `"uwtable"`)?
+    pub fn has_enum_attribute(&self, name: &str) -> bool {
+        self.function_attributes.iter().any(|attr| attr.is_enum_attribute_named(name))
+    }
+
+    /// Get the value of the `Function`'s string attribute with the given kind
+    /// (e.g., `"frame-pointer"`), if it has one
+    pub fn get_string_attribute(&self, kind: &str) -> Option<&str> {
+        self.function_attributes.iter().find_map(|attr| match attr {
+            Attribute::StringAttribute { kind: k, value } if k == kind => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Get the target features the `Function` was compiled for (its
+    /// `"target-features"` attribute), e.g., `"+sse,+avx"`, if specified
+    pub fn target_features(&self) -> Option<&str> {
+        self.get_string_attribute("target-features")
+    }
+
+    /// Get the CPU the `Function` was compiled for (its `"target-cpu"`
+    /// attribute), e.g., `"x86-64"`, if specified
+    pub fn target_cpu(&self) -> Option<&str> {
+        self.get_string_attribute("target-cpu")
+    }
+
+    /// Is this an LLVM intrinsic function, i.e., does its name begin with
+    /// `llvm.`? Intrinsics are only ever declared, never defined.
+    /// See [LLVM 9 docs on Intrinsic Functions](https://releases.llvm.org/9.0.0/docs/LangRef.html#intrinsic-functions)
+    pub fn is_intrinsic(&self) -> bool {
+        self.name.starts_with("llvm.")
+    }
+
+    /// Get LLVM's ID for the intrinsic this `Function` is (or is an overload
+    /// of), or `None` if it isn't an intrinsic known to LLVM.
+    ///
+    /// IDs are assigned by the version of LLVM this crate is linked against,
+    /// and differ between LLVM versions.
+    pub fn intrinsic_id(&self) -> Option<u32> {
+        if !self.is_intrinsic() {
+            return None;
+        }
+        let id = unsafe { llvm_sys::core::LLVMLookupIntrinsicID(self.name.as_ptr() as *const _, self.name.len()) };
+        if id == 0 {
+            None
+        } else {
+            Some(id)
+        }
+    }
+
+    /// Get the total number of bytes allocated by the `Function`'s `alloca`s,
+    /// each contributing its allocated type's size times its element count.
+    /// Padding between allocations is not included.
+    ///
+    /// Returns `None` if any `alloca` has a non-constant element count, or
+    /// allocates an unsized type.
+    pub fn static_stack_size(&self, dl: &DataLayout) -> Option<u64> {
+        let mut total: u64 = 0;
+        for inst in self.basic_blocks.iter().flat_map(|bb| &bb.instrs) {
+            if let Instruction::Alloca(alloca) = inst {
+                let count = match &alloca.num_elements {
+                    Operand::ConstantOperand(Constant::Int { value, .. }) => *value,
+                    _ => return None,
+                };
+                let size = dl.alloc_size(&alloca.allocated_type)?.checked_mul(count)?;
+                total = total.checked_add(size)?;
+            }
+        }
+        Some(total)
+    }
+
+    /// Get the `Name`s of the blocks which are not reachable from the entry
+    /// block, in the order they appear in the `Function`.
+    /// See [`Cfg::reachable_blocks()`](../cfg/struct.Cfg.html#method.reachable_blocks)
+    pub fn unreachable_blocks(&self) -> Vec<&Name> {
+        let reachable = Cfg::new(self).reachable_blocks();
+        self.basic_blocks
+            .iter()
+            .map(|bb| &bb.name)
+            .filter(|name| !reachable.contains(name))
+            .collect()
+    }
+
+    /// Remove all of the blocks which are not reachable from the entry block
+    /// (see [`unreachable_blocks()`](struct.Function.html#method.unreachable_blocks)).
+    ///
+    /// Incoming values of `Phi`s in the remaining blocks which come from a
+    /// removed block are also removed.
+    pub fn remove_unreachable_blocks(&mut self) {
+        let reachable = Cfg::new(self).reachable_blocks();
+        self.basic_blocks.retain(|bb| reachable.contains(&bb.name));
+        for bb in &mut self.basic_blocks {
+            for inst in &mut bb.instrs {
+                if let Instruction::Phi(phi) = inst {
+                    phi.incoming_values.retain(|(_, block)| reachable.contains(block));
+                }
+            }
+        }
+    }
+
+    /// Split the block with the given `Name` before its `at`th instruction:
+    /// instructions `at..` and the terminator are moved into a new block named
+    /// `new_name`, inserted just after the original block, and the original
+    /// block instead ends with a `Br` to the new block. `Phi`s in the
+    /// successors of the moved terminator are updated to refer to the new
+    /// block.
+    ///
+    /// `at` may be the number of instructions in the block, in which case
+    /// the new block contains only the terminator.
+    pub fn split_block(&mut self, block: &Name, at: usize, new_name: Name) -> Result<(), SplitError> {
+        let index = self
+            .basic_blocks
+            .iter()
+            .position(|bb| &bb.name == block)
+            .ok_or_else(|| SplitError::NoSuchBlock(block.clone()))?;
+        let len = self.basic_blocks[index].instrs.len();
+        if at > len {
+            return Err(SplitError::IndexOutOfRange { block: block.clone(), at, len });
+        }
+        let name_in_use = self.parameters.iter().any(|param| param.name == new_name)
+            || self.basic_blocks.iter().any(|bb| {
+                bb.name == new_name
+                    || bb.instrs.iter().any(|inst| inst.try_get_result() == Some(&new_name))
+                    || bb.term.try_get_result() == Some(&new_name)
+            });
+        if name_in_use {
+            return Err(SplitError::NameInUse(new_name));
+        }
+        if self.basic_blocks[index].instrs[at..].iter().any(|inst| matches!(inst, Instruction::Phi(_))) {
+            return Err(SplitError::SplitsPhis(block.clone()));
+        }
+
+        let bb = &mut self.basic_blocks[index];
+        let br = Terminator::Br(Br { dest: new_name.clone(), debugloc: None });
+        let new_bb = BasicBlock {
+            name: new_name.clone(),
+            instrs: bb.instrs.split_off(at),
+            term: std::mem::replace(&mut bb.term, br),
+        };
+        let succs: Vec<Name> = new_bb.term.successors().into_iter().cloned().collect();
+        self.basic_blocks.insert(index + 1, new_bb);
+        for bb in self.basic_blocks.iter_mut().filter(|bb| succs.contains(&bb.name)) {
+            for inst in &mut bb.instrs {
+                if let Instruction::Phi(phi) = inst {
+                    for (_, incoming) in &mut phi.incoming_values {
+                        if incoming == block {
+                            *incoming = new_name.clone();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge the block with the given `Name` into its predecessor: the
+    /// block's instructions are appended to the predecessor's, the
+    /// predecessor adopts the block's terminator, and the block is removed.
+    /// `Phi`s in the successors of the terminator are updated to refer to the
+    /// predecessor. This is the inverse of
+    /// [`split_block()`](struct.Function.html#method.split_block).
+    ///
+    /// The block must have exactly one predecessor (other than itself), which
+    /// must end in an unconditional `Br` to it, and the block must not
+    /// contain any `Phi`s.
+    pub fn merge_into_predecessor(&mut self, block: &Name) -> Result<(), MergeError> {
+        let index = self
+            .basic_blocks
+            .iter()
+            .position(|bb| &bb.name == block)
+            .ok_or_else(|| MergeError::NoSuchBlock(block.clone()))?;
+        let preds = self.predecessors_of(block);
+        if preds.len() != 1 || preds[0] == block {
+            return Err(MergeError::NotSinglePredecessor(block.clone()));
+        }
+        let pred = preds[0].clone();
+        let pred_index = self
+            .basic_blocks
+            .iter()
+            .position(|bb| bb.name == pred)
+            .expect("Predecessor should be in the function");
+        if !matches!(self.basic_blocks[pred_index].term, Terminator::Br(_)) {
+            return Err(MergeError::PredecessorNotBr(pred));
+        }
+        if self.basic_blocks[index].instrs.iter().any(|inst| matches!(inst, Instruction::Phi(_))) {
+            return Err(MergeError::HasPhis(block.clone()));
+        }
+
+        let bb = self.basic_blocks.remove(index);
+        let succs: Vec<Name> = bb.term.successors().into_iter().cloned().collect();
+        let pred_index = if pred_index > index { pred_index - 1 } else { pred_index };
+        let pred_bb = &mut self.basic_blocks[pred_index];
+        pred_bb.instrs.extend(bb.instrs);
+        pred_bb.term = bb.term;
+        for bb in self.basic_blocks.iter_mut().filter(|bb| succs.contains(&bb.name)) {
+            for inst in &mut bb.instrs {
+                if let Instruction::Phi(phi) = inst {
+                    for (_, incoming) in &mut phi.incoming_values {
+                        if incoming == block {
+                            *incoming = pred.clone();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Determine whether the pointers `a` and `b` may refer to the same
+    /// memory, using only simple, local facts:
+    ///   - pointers with the same base, apart from casts and all-zero `GetElementPtr`s, `MustAlias`;
+    ///   - pointers into distinct `alloca`s or distinct global variables of `module` are `NoAlias`;
+    ///   - anything else (including a `GetElementPtr` and its base) is `MayAlias`.
+    ///
+    /// This is conservative: `NoAlias` and `MustAlias` are always correct, but
+    /// many pairs of pointers which could be proven not to alias are
+    /// reported as `MayAlias`. `Name`s which are not parameters or
+    /// instruction results in this `Function` are looked up as global
+    /// variables of `module`; global aliases, and names which are not found,
+    /// may alias anything.
+    ///
+    /// `module` must be the `Module` containing this `Function`. It is needed
+    /// because a reference to a global doesn't say what kind of global it
+    /// refers to: two distinct global variables never alias, but a
+    /// `GlobalAlias` may refer to the same memory as another global.
+    pub fn may_alias(&self, module: &Module, a: &Name, b: &Name) -> AliasResult {
+        let defs: HashMap<&Name, &Instruction> = self
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| &bb.instrs)
+            .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+            .collect();
+        let (stripped_a, stripped_b) = (self.strip_pointer(&defs, a, false), self.strip_pointer(&defs, b, false));
+        if a == b || (stripped_a == stripped_b && stripped_a != PointerBase::Unknown) {
+            return AliasResult::MustAlias;
+        }
+        let (base_a, base_b) = (self.strip_pointer(&defs, a, true), self.strip_pointer(&defs, b, true));
+        let is_distinct = |base: &PointerBase| match base {
+            PointerBase::Global(name) => module.get_global_var_by_name(name).is_some(),
+            PointerBase::Local(name) => matches!(defs.get(name), Some(Instruction::Alloca(_))),
+            PointerBase::Unknown => false,
+        };
+        if base_a != base_b && is_distinct(&base_a) && is_distinct(&base_b) {
+            AliasResult::NoAlias
+        } else {
+            AliasResult::MayAlias
+        }
+    }
+
+    /// Follow the pointer `name` back through casts and all-zero
+    /// `GetElementPtr`s (or, if `through_geps`, all `GetElementPtr`s) to the
+    /// pointer it is derived from. `defs` maps each instruction result in the
+    /// `Function` to the instruction defining it.
+    fn strip_pointer<'a>(&'a self, defs: &HashMap<&'a Name, &'a Instruction>, name: &'a Name, through_geps: bool) -> PointerBase<'a> {
+        let is_zero = |op: &Operand| matches!(op, Operand::ConstantOperand(Constant::Int { value: 0, .. }));
+        let mut cur = name;
+        // bound the walk, since unreachable code may contain cycles such as `%a = bitcast %a`
+        for _ in 0..=defs.len() {
+            let operand = match defs.get(cur) {
+                Some(Instruction::BitCast(bc)) => &bc.operand,
+                Some(Instruction::AddrSpaceCast(asc)) => &asc.operand,
+                Some(Instruction::GetElementPtr(gep)) if through_geps || gep.indices.iter().all(is_zero) => &gep.address,
+                Some(_) => return PointerBase::Local(cur),
+                None if self.parameters.iter().any(|param| &param.name == cur) => return PointerBase::Local(cur),
+                None => return PointerBase::Global(cur),
+            };
+            match operand {
+                Operand::LocalOperand { name, .. } => cur = name,
+                Operand::ConstantOperand(c) => {
+                    let mut c = c.strip_pointer_casts();
+                    while let (true, Constant::GetElementPtr(gep)) = (through_geps, c) {
+                        c = gep.address.strip_pointer_casts();
+                    }
+                    return match c {
+                        Constant::GlobalReference { name, .. } => PointerBase::Global(name),
+                        _ => PointerBase::Unknown,
+                    };
+                },
+                Operand::MetadataOperand => return PointerBase::Unknown,
+            }
+        }
+        PointerBase::Unknown
+    }
+
+    /// Find the single `Store` whose address operand is `alloca` (typically
+    /// the result of an `Alloca`), or `None` if there are no such stores or
+    /// more than one.
+    ///
+    /// Only stores directly to `alloca` are considered: stores through casts
+    /// of it or `GetElementPtr`s into it are not, so callers reasoning about
+    /// the memory (as in `mem2reg`) should also check that `alloca` has no
+    /// other uses.
+    pub fn sole_store_to(&self, alloca: &Name) -> Option<InstructionRef> {
+        let mut stores = self.instructions_using_address(alloca, |inst| matches!(inst, Instruction::Store(_)));
+        if stores.len() == 1 {
+            stores.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Find the `Load`s whose address operand is `alloca`, in order.
+    /// As with [`sole_store_to()`](struct.Function.html#method.sole_store_to),
+    /// loads through casts or `GetElementPtr`s are not included.
+    pub fn loads_from(&self, alloca: &Name) -> Vec<InstructionRef> {
+        self.instructions_using_address(alloca, |inst| matches!(inst, Instruction::Load(_)))
+    }
+
+    /// The locations of the instructions satisfying `filter` whose address
+    /// operand is the local `address`
+    fn instructions_using_address(&self, address: &Name, filter: impl Fn(&Instruction) -> bool) -> Vec<InstructionRef> {
+        let mut refs = vec![];
+        for bb in &self.basic_blocks {
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                let addr = match inst {
+                    Instruction::Load(load) => &load.address,
+                    Instruction::Store(store) => &store.address,
+                    _ => continue,
+                };
+                if matches!(addr, Operand::LocalOperand { name, .. } if name == address) && filter(inst) {
+                    refs.push(InstructionRef {
+                        function: self.name.clone(),
+                        block: bb.name.clone(),
+                        index,
+                    });
+                }
+            }
+        }
+        refs
+    }
+
+    /// Reassign all of the `Name::Number`s in the `Function` so that they are
+    /// dense and in definition order, and rewrite all uses accordingly.
+    /// `Name::Name`s are left untouched.
+    ///
+    /// Definition order is the order in which LLVM itself numbers unnamed
+    /// values: parameters first, then each basic block's label followed by
+    /// the results of its instructions (and terminator).
+    pub fn renumber(&mut self) {
+        let mut ctr = 0;
+        let mut renumbering: HashMap<usize, usize> = HashMap::new();
+        let mut assign = |name: &Name| {
+            if let Name::Number(n) = name {
+                renumbering.insert(*n, ctr);
+                ctr += 1;
+            }
+        };
+        for param in &self.parameters {
+            assign(&param.name);
+        }
+        for bb in &mut self.basic_blocks {
+            assign(&bb.name);
+            for inst in &mut bb.instrs {
+                if let Some(dest) = inst.try_get_result_mut() {
+                    assign(dest);
+                }
+            }
+            if let Some(result) = bb.term.try_get_result_mut() {
+                assign(result);
+            }
+        }
+
+        let rename = |name: &mut Name| {
+            if let Name::Number(n) = name {
+                if let Some(&new) = renumbering.get(n) {
+                    *n = new;
+                }
+            }
+        };
+        for param in &mut self.parameters {
+            rename(&mut param.name);
+        }
+        for bb in &mut self.basic_blocks {
+            rename(&mut bb.name);
+            for inst in &mut bb.instrs {
+                if let Some(dest) = inst.try_get_result_mut() {
+                    rename(dest);
+                }
+                if let Instruction::Phi(phi) = inst {
+                    for (_, block) in &mut phi.incoming_values {
+                        rename(block);
+                    }
+                }
+                for op in inst.operands_mut() {
+                    if let Operand::LocalOperand { name, .. } = op {
+                        rename(name);
+                    }
+                }
+                if let Instruction::Call(Call { debug_variable: Some(DbgVariableRef { value: Some(value), .. }), .. }) = inst {
+                    rename(value);
+                }
+            }
+            if let Some(result) = bb.term.try_get_result_mut() {
+                rename(result);
+            }
+            for succ in bb.term.successors_mut() {
+                rename(succ);
+            }
+            for op in bb.term.operands_mut() {
+                if let Operand::LocalOperand { name, .. } = op {
+                    rename(name);
+                }
+            }
+        }
+    }
+
     /// A Function instance as empty as possible, using defaults
     pub fn new(name: impl Into<String>) -> Self {
         Self {
@@ -77,13 +612,117 @@ impl Function {
             comdat: None,
             alignment: 4,
             garbage_collector_name: None,
+            prefix_data: None,
+            prologue_data: None,
             personality_function: None,
             debugloc: None,
         }
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// The result of [`Function::may_alias()`](struct.Function.html#method.may_alias)
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum AliasResult {
+    /// The pointers definitely do not refer to overlapping memory
+    NoAlias,
+    /// The pointers may or may not refer to overlapping memory
+    MayAlias,
+    /// The pointers definitely refer to the same address
+    MustAlias,
+}
+
+/// What a pointer is derived from, for `Function::may_alias()`
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum PointerBase<'a> {
+    /// A parameter or instruction result
+    Local(&'a Name),
+    /// Any other name, presumably a global
+    Global(&'a Name),
+    /// Something else, e.g., a constant `inttoptr`
+    Unknown,
+}
+
+/// Error returned by [`Function::split_block()`](struct.Function.html#method.split_block)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SplitError {
+    /// There is no block with the given name
+    NoSuchBlock(Name),
+    /// The split index is past the end of the block's instructions
+    IndexOutOfRange { block: Name, at: usize, len: usize },
+    /// The new block's name is already used by a block or value
+    NameInUse(Name),
+    /// The split would move `Phi`s out of the beginning of the given block
+    SplitsPhis(Name),
+}
+
+impl std::fmt::Display for SplitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SplitError::NoSuchBlock(name) => write!(f, "No block named {}", name),
+            SplitError::IndexOutOfRange { block, at, len } => {
+                write!(f, "Cannot split block {} with {} instructions at index {}", block, len, at)
+            },
+            SplitError::NameInUse(name) => write!(f, "The name {} is already in use", name),
+            SplitError::SplitsPhis(name) => write!(f, "Splitting block {} there would separate its phis", name),
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// Error returned by [`Function::merge_into_predecessor()`](struct.Function.html#method.merge_into_predecessor)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum MergeError {
+    /// There is no block with the given name
+    NoSuchBlock(Name),
+    /// The given block does not have exactly one predecessor (other than itself)
+    NotSinglePredecessor(Name),
+    /// The given predecessor does not end in an unconditional `Br`
+    PredecessorNotBr(Name),
+    /// The given block contains `Phi`s
+    HasPhis(Name),
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MergeError::NoSuchBlock(name) => write!(f, "No block named {}", name),
+            MergeError::NotSinglePredecessor(name) => write!(f, "Block {} does not have exactly one predecessor", name),
+            MergeError::PredecessorNotBr(name) => write!(f, "Predecessor block {} does not end in an unconditional br", name),
+            MergeError::HasPhis(name) => write!(f, "Block {} contains phis", name),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// A source-level local variable, as described by a call to
+/// `llvm.dbg.declare` or `llvm.dbg.value`.
+/// See [`Function::local_variables()`](struct.Function.html#method.local_variables).
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalVariable {
+    /// Name of the variable in the source
+    pub name: String,
+    /// Line number of the variable's declaration in the source
+    pub line: u32,
+    /// Name of the variable's source-level type, if it has one (e.g., `int`;
+    /// but pointer types, for instance, are typically unnamed)
+    pub type_name: Option<String>,
+    /// The local value the intrinsic refers to: for `llvm.dbg.declare`, the
+    /// address of the variable (typically an `alloca`); for `llvm.dbg.value`,
+    /// the variable's new value. `None` if this is not a local value (e.g.,
+    /// it is a constant or `undef`).
+    pub value: Option<Name>,
+    /// `true` for `llvm.dbg.declare`, `false` for `llvm.dbg.value`
+    pub is_declare: bool,
+    /// The variable's source-level type, which can be resolved with
+    /// [`Module::resolve_ditype()`](../struct.Module.html#method.resolve_ditype)
+    pub ty: Option<MetadataNodeID>,
+}
+
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameter {
     pub name: Name,
     pub ty: Type,
@@ -96,8 +735,30 @@ impl Typed for Parameter {
     }
 }
 
+impl Parameter {
+    /// If the parameter is `byval`, get the type of the value which is passed
+    /// by value, i.e., the type it points to.
+    /// See [LLVM 9 docs on Parameter Attributes](https://releases.llvm.org/9.0.0/docs/LangRef.html#paramattrs)
+    pub fn byval_type(&self) -> Option<&Type> {
+        self.attributes.iter().find_map(|attr| match attr {
+            Attribute::ByVal(ty) => Some(ty),
+            _ => None,
+        })
+    }
+
+    /// If the parameter is `sret`, get the type of the returned structure,
+    /// i.e., the type it points to
+    pub fn sret_type(&self) -> Option<&Type> {
+        self.attributes.iter().find_map(|attr| match attr {
+            Attribute::StructRet(ty) => Some(ty),
+            _ => None,
+        })
+    }
+}
+
 /// See [LLVM 9 docs on Calling Conventions](https://releases.llvm.org/9.0.0/docs/LangRef.html#callingconv)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum CallingConvention {
     C,
@@ -147,7 +808,8 @@ pub enum CallingConvention {
     Numbered(u32),
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Attribute {
     EnumAttribute {
         kind: u32,
@@ -157,6 +819,30 @@ pub enum Attribute {
         kind: String,
         value: String, // for no value, use ""
     },
+    /// `byval`, with the type of the value which is passed by value (the type
+    /// the parameter points to)
+    ByVal(Type),
+    /// `sret`, with the type of the returned structure (the type the
+    /// parameter points to)
+    StructRet(Type),
+}
+
+impl Attribute {
+    /// Is this the enum attribute with the given name (e.g., `"noreturn"`)?
+    /// Enum attributes are stored with only a numeric `kind`, and the mapping
+    /// from names to kinds is specific to the LLVM version.
+    ///
+    /// `ByVal` and `StructRet` are the `"byval"` and `"sret"` enum attributes.
+    pub fn is_enum_attribute_named(&self, name: &str) -> bool {
+        match self {
+            Attribute::EnumAttribute { kind, .. } => {
+                *kind != 0 && *kind == crate::from_llvm::enum_attribute_kind_for_name(name)
+            },
+            Attribute::StringAttribute { .. } => false,
+            Attribute::ByVal(_) => name == "byval",
+            Attribute::StructRet(_) => name == "sret",
+        }
+    }
 }
 
 /// See [LLVM 9 docs on Function Attributes](https://releases.llvm.org/9.0.0/docs/LangRef.html#fnattrs)
@@ -244,6 +930,7 @@ pub type GroupID = usize;
 
 use crate::basicblock::BBMap;
 use crate::constant::GlobalNameMap;
+use crate::debuginfo::DITypeMap;
 use crate::from_llvm::*;
 use crate::operand::ValToNameMap;
 use crate::types::TyNameMap;
@@ -255,6 +942,7 @@ impl Function {
         func: LLVMValueRef,
         gnmap: &GlobalNameMap,
         tnmap: &mut TyNameMap,
+        ditypemap: &mut DITypeMap,
     ) -> Self {
         let func = unsafe { LLVMIsAFunction(func) };
         assert!(!func.is_null());
@@ -264,22 +952,24 @@ impl Function {
         let parameters: Vec<Parameter> = {
             get_parameters(func)
                 .enumerate()
-                .map(|(i, p)| Parameter {
-                    name: Name::name_or_num(unsafe { get_value_name(p) }, &mut local_ctr),
-                    ty: Type::from_llvm_ref(unsafe { LLVMTypeOf(p) }, tnmap),
-                    attributes: {
-                        let num_attrs = unsafe { LLVMGetAttributeCountAtIndex(func, i as u32) };
+                .map(|(i, p)| {
+                    let name = Name::name_or_num(unsafe { get_value_name(p) }, &mut local_ctr);
+                    let ty = Type::from_llvm_ref(unsafe { LLVMTypeOf(p) }, tnmap);
+                    let attributes = {
+                        // attribute index 0 is the return value, so parameters start at 1
+                        let num_attrs = unsafe { LLVMGetAttributeCountAtIndex(func, (i + 1) as u32) };
                         let mut attrs: Vec<LLVMAttributeRef> =
                             Vec::with_capacity(num_attrs as usize);
                         unsafe {
-                            LLVMGetAttributesAtIndex(func, i as u32, attrs.as_mut_ptr());
+                            LLVMGetAttributesAtIndex(func, (i + 1) as u32, attrs.as_mut_ptr());
                             attrs.set_len(num_attrs as usize);
                         };
                         attrs
                             .into_iter()
-                            .filter_map(ParameterAttribute::from_llvm_ref)
+                            .filter_map(|a| ParameterAttribute::param_from_llvm_ref(a, &ty))
                             .collect()
-                    },
+                    };
+                    Parameter { name, ty, attributes }
                 })
                 .collect()
         };
@@ -318,7 +1008,7 @@ impl Function {
             basic_blocks: {
                 get_basic_blocks(func)
                     .map(|bb| {
-                        BasicBlock::from_llvm_ref(bb, &mut local_ctr, &vnmap, &bbmap, gnmap, tnmap)
+                        BasicBlock::from_llvm_ref(bb, &mut local_ctr, &vnmap, &bbmap, gnmap, tnmap, ditypemap)
                     })
                     .collect()
             },
@@ -381,6 +1071,8 @@ impl Function {
             },
             alignment: unsafe { LLVMGetAlignment(func) },
             garbage_collector_name: unsafe { get_gc(func) },
+            prefix_data: hung_off_constant(func, 1, gnmap, tnmap),
+            prologue_data: hung_off_constant(func, 2, gnmap, tnmap),
             personality_function: {
                 if unsafe { LLVMHasPersonalityFn(func) } != 0 {
                     Some(Constant::from_llvm_ref(
@@ -398,6 +1090,25 @@ impl Function {
     }
 }
 
+/// The LLVM 9 C API has no way to get a function's prefix or prologue data.
+/// But LLVM stores them (along with the personality function) as "hung-off"
+/// operands of the function: index `1` for prefix data and `2` for prologue
+/// data. Unset ones hold a null `i1*` placeholder (in address space 1 in
+/// some LLVM versions).
+fn hung_off_constant(func: LLVMValueRef, index: u32, gnmap: &GlobalNameMap, tnmap: &mut TyNameMap) -> Option<Constant> {
+    if unsafe { LLVMGetNumOperands(func) } <= index as i32 {
+        return None;
+    }
+    let operand = unsafe { LLVMGetOperand(func, index) };
+    if operand.is_null() {
+        return None;
+    }
+    match Constant::from_llvm_ref(operand, gnmap, tnmap) {
+        Constant::Null(Type::PointerType { pointee_type, .. }) if *pointee_type == Type::bool() => None,
+        constant => Some(constant),
+    }
+}
+
 impl CallingConvention {
     #[allow(clippy::cognitive_complexity)]
     pub(crate) fn from_u32(u: u32) -> Self {
@@ -465,8 +1176,36 @@ impl Attribute {
                 value: unsafe { get_string_attribute_value(a) },
             })
         } else {
-            debug!("Encountered an unknown attribute: neither enum nor string");
-            None
+            // Newer versions of LLVM have type attributes (e.g., `byval(%T)`),
+            // which the LLVM 9 C API can't decode; we keep their kind
+            debug!("Encountered an attribute which is neither enum nor string; assuming it is a type attribute");
+            Some(Attribute::EnumAttribute {
+                kind: unsafe { LLVMGetEnumAttributeKind(a) },
+                value: None,
+            })
+        }
+    }
+
+    /// Like `from_llvm_ref()`, but for an attribute of a parameter (or call
+    /// argument) of type `ty`, so that `byval` and `sret` can be given their
+    /// types.
+    ///
+    /// LLVM 9 infers these types from the pointee type of the parameter.
+    /// Newer versions of LLVM instead have an explicit type attribute (e.g.,
+    /// `byval(%T)`), whose type the LLVM 9 C API can't read; but with typed
+    /// pointers, the explicit type is always the pointee type too.
+    pub(crate) fn param_from_llvm_ref(a: LLVMAttributeRef, ty: &Type) -> Option<Self> {
+        let attr = Self::from_llvm_ref(a)?;
+        let pointee_type = match ty {
+            Type::PointerType { pointee_type, .. } => (**pointee_type).clone(),
+            _ => return Some(attr),
+        };
+        if attr.is_enum_attribute_named("byval") {
+            Some(Attribute::ByVal(pointee_type))
+        } else if attr.is_enum_attribute_named("sret") {
+            Some(Attribute::StructRet(pointee_type))
+        } else {
+            Some(attr)
         }
     }
 }
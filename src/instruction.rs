@@ -1,17 +1,22 @@
 use crate::constant::Constant;
+use crate::datalayout::DataLayout;
+use crate::debuginfo::DbgVariableRef;
 use crate::debugloc::{DebugLoc, HasDebugLoc};
 use crate::function::{CallingConvention, FunctionAttribute, ParameterAttribute};
+use crate::intrinsic::Intrinsic;
+use crate::module::Module;
 use crate::name::Name;
-use crate::operand::Operand;
+use crate::operand::{Operand, OperandVec};
 use crate::predicates::*;
-use crate::types::{Type, Typed};
+use crate::types::{extractvalue_type_in, Type, Typed, TypedInModule};
 use either::Either;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::ops::Deref;
 
 /// Non-terminator instructions.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Instruction {
     // Integer binary ops
     Add(Add),
@@ -144,6 +149,17 @@ impl Typed for Instruction {
     }
 }
 
+impl TypedInModule for Instruction {
+    fn get_type_in(&self, module: &Module) -> Type {
+        match self {
+            Instruction::ExtractValue(i) => i.get_type_in(module),
+            Instruction::Load(i) => i.get_type_in(module),
+            Instruction::GetElementPtr(i) => i.get_type_in(module),
+            _ => self.get_type(),
+        }
+    }
+}
+
 impl HasDebugLoc for Instruction {
     fn get_debug_loc(&self) -> &Option<DebugLoc> {
         match self {
@@ -205,6 +221,74 @@ impl HasDebugLoc for Instruction {
 }
 
 impl Instruction {
+    /// Get a mutable reference to the `DebugLoc` of the `Instruction`
+    pub(crate) fn debugloc_mut(&mut self) -> &mut Option<DebugLoc> {
+        match self {
+            Instruction::Add(i) => &mut i.debugloc,
+            Instruction::Sub(i) => &mut i.debugloc,
+            Instruction::Mul(i) => &mut i.debugloc,
+            Instruction::UDiv(i) => &mut i.debugloc,
+            Instruction::SDiv(i) => &mut i.debugloc,
+            Instruction::URem(i) => &mut i.debugloc,
+            Instruction::SRem(i) => &mut i.debugloc,
+            Instruction::And(i) => &mut i.debugloc,
+            Instruction::Or(i) => &mut i.debugloc,
+            Instruction::Xor(i) => &mut i.debugloc,
+            Instruction::Shl(i) => &mut i.debugloc,
+            Instruction::LShr(i) => &mut i.debugloc,
+            Instruction::AShr(i) => &mut i.debugloc,
+            Instruction::FAdd(i) => &mut i.debugloc,
+            Instruction::FSub(i) => &mut i.debugloc,
+            Instruction::FMul(i) => &mut i.debugloc,
+            Instruction::FDiv(i) => &mut i.debugloc,
+            Instruction::FRem(i) => &mut i.debugloc,
+            Instruction::FNeg(i) => &mut i.debugloc,
+            Instruction::ExtractElement(i) => &mut i.debugloc,
+            Instruction::InsertElement(i) => &mut i.debugloc,
+            Instruction::ShuffleVector(i) => &mut i.debugloc,
+            Instruction::ExtractValue(i) => &mut i.debugloc,
+            Instruction::InsertValue(i) => &mut i.debugloc,
+            Instruction::Alloca(i) => &mut i.debugloc,
+            Instruction::Load(i) => &mut i.debugloc,
+            Instruction::Store(i) => &mut i.debugloc,
+            Instruction::Fence(i) => &mut i.debugloc,
+            Instruction::CmpXchg(i) => &mut i.debugloc,
+            Instruction::AtomicRMW(i) => &mut i.debugloc,
+            Instruction::GetElementPtr(i) => &mut i.debugloc,
+            Instruction::Trunc(i) => &mut i.debugloc,
+            Instruction::ZExt(i) => &mut i.debugloc,
+            Instruction::SExt(i) => &mut i.debugloc,
+            Instruction::FPTrunc(i) => &mut i.debugloc,
+            Instruction::FPExt(i) => &mut i.debugloc,
+            Instruction::FPToUI(i) => &mut i.debugloc,
+            Instruction::FPToSI(i) => &mut i.debugloc,
+            Instruction::UIToFP(i) => &mut i.debugloc,
+            Instruction::SIToFP(i) => &mut i.debugloc,
+            Instruction::PtrToInt(i) => &mut i.debugloc,
+            Instruction::IntToPtr(i) => &mut i.debugloc,
+            Instruction::BitCast(i) => &mut i.debugloc,
+            Instruction::AddrSpaceCast(i) => &mut i.debugloc,
+            Instruction::ICmp(i) => &mut i.debugloc,
+            Instruction::FCmp(i) => &mut i.debugloc,
+            Instruction::Phi(i) => &mut i.debugloc,
+            Instruction::Select(i) => &mut i.debugloc,
+            Instruction::Call(i) => &mut i.debugloc,
+            Instruction::VAArg(i) => &mut i.debugloc,
+            Instruction::LandingPad(i) => &mut i.debugloc,
+            Instruction::CatchPad(i) => &mut i.debugloc,
+            Instruction::CleanupPad(i) => &mut i.debugloc,
+        }
+    }
+
+    /// If this is a direct `Call`, get the name of the called function; see
+    /// [`Call::called_function_name()`](struct.Call.html#method.called_function_name)
+    pub fn called_function_name(&self) -> Option<&str> {
+        match self {
+            Instruction::Call(call) => call.called_function_name(),
+            _ => None,
+        }
+    }
+
     /// Get the result (destination) of the `Instruction`, or `None` if the
     /// `Instruction` doesn't have a result (has void type).
     pub fn try_get_result(&self) -> Option<&Name> {
@@ -265,6 +349,87 @@ impl Instruction {
         }
     }
 
+    /// Like `try_get_result()`, but get a mutable reference
+    pub(crate) fn try_get_result_mut(&mut self) -> Option<&mut Name> {
+        match self {
+            Instruction::Add(i) => Some(&mut i.dest),
+            Instruction::Sub(i) => Some(&mut i.dest),
+            Instruction::Mul(i) => Some(&mut i.dest),
+            Instruction::UDiv(i) => Some(&mut i.dest),
+            Instruction::SDiv(i) => Some(&mut i.dest),
+            Instruction::URem(i) => Some(&mut i.dest),
+            Instruction::SRem(i) => Some(&mut i.dest),
+            Instruction::And(i) => Some(&mut i.dest),
+            Instruction::Or(i) => Some(&mut i.dest),
+            Instruction::Xor(i) => Some(&mut i.dest),
+            Instruction::Shl(i) => Some(&mut i.dest),
+            Instruction::LShr(i) => Some(&mut i.dest),
+            Instruction::AShr(i) => Some(&mut i.dest),
+            Instruction::FAdd(i) => Some(&mut i.dest),
+            Instruction::FSub(i) => Some(&mut i.dest),
+            Instruction::FMul(i) => Some(&mut i.dest),
+            Instruction::FDiv(i) => Some(&mut i.dest),
+            Instruction::FRem(i) => Some(&mut i.dest),
+            Instruction::FNeg(i) => Some(&mut i.dest),
+            Instruction::ExtractElement(i) => Some(&mut i.dest),
+            Instruction::InsertElement(i) => Some(&mut i.dest),
+            Instruction::ShuffleVector(i) => Some(&mut i.dest),
+            Instruction::ExtractValue(i) => Some(&mut i.dest),
+            Instruction::InsertValue(i) => Some(&mut i.dest),
+            Instruction::Alloca(i) => Some(&mut i.dest),
+            Instruction::Load(i) => Some(&mut i.dest),
+            Instruction::Store(_) => None,
+            Instruction::Fence(_) => None,
+            Instruction::CmpXchg(i) => Some(&mut i.dest),
+            Instruction::AtomicRMW(i) => Some(&mut i.dest),
+            Instruction::GetElementPtr(i) => Some(&mut i.dest),
+            Instruction::Trunc(i) => Some(&mut i.dest),
+            Instruction::ZExt(i) => Some(&mut i.dest),
+            Instruction::SExt(i) => Some(&mut i.dest),
+            Instruction::FPTrunc(i) => Some(&mut i.dest),
+            Instruction::FPExt(i) => Some(&mut i.dest),
+            Instruction::FPToUI(i) => Some(&mut i.dest),
+            Instruction::FPToSI(i) => Some(&mut i.dest),
+            Instruction::UIToFP(i) => Some(&mut i.dest),
+            Instruction::SIToFP(i) => Some(&mut i.dest),
+            Instruction::PtrToInt(i) => Some(&mut i.dest),
+            Instruction::IntToPtr(i) => Some(&mut i.dest),
+            Instruction::BitCast(i) => Some(&mut i.dest),
+            Instruction::AddrSpaceCast(i) => Some(&mut i.dest),
+            Instruction::ICmp(i) => Some(&mut i.dest),
+            Instruction::FCmp(i) => Some(&mut i.dest),
+            Instruction::Phi(i) => Some(&mut i.dest),
+            Instruction::Select(i) => Some(&mut i.dest),
+            Instruction::Call(i) => i.dest.as_mut(),
+            Instruction::VAArg(i) => Some(&mut i.dest),
+            Instruction::LandingPad(i) => Some(&mut i.dest),
+            Instruction::CatchPad(i) => Some(&mut i.dest),
+            Instruction::CleanupPad(i) => Some(&mut i.dest),
+        }
+    }
+
+    /// Get a clone of the `Instruction`, but with its result (destination)
+    /// renamed to `name`. Everything else, including operands, is unchanged.
+    ///
+    /// If the `Instruction` doesn't have a result (has void type), this just
+    /// returns an unmodified clone.
+    pub fn with_dest(&self, name: Name) -> Instruction {
+        let mut inst = self.clone();
+        if let Some(dest) = inst.try_get_result_mut() {
+            *dest = name;
+        }
+        inst
+    }
+
+    /// If the `Instruction` is a call to an LLVM intrinsic, decode it.
+    /// See [`Intrinsic`](../intrinsic/enum.Intrinsic.html).
+    pub fn as_intrinsic(&self) -> Option<Intrinsic> {
+        match self {
+            Instruction::Call(call) => Intrinsic::from_call(call),
+            _ => None,
+        }
+    }
+
     /// Whether the `Instruction` is atomic
     pub fn is_atomic(&self) -> bool {
         match self {
@@ -325,6 +490,172 @@ impl Instruction {
     }
 }
 
+impl Instruction {
+    /// Get mutable references to the `Type`s stored directly in the
+    /// `Instruction` (e.g., the `to_type` of a cast), not including the types
+    /// of its operands
+    pub(crate) fn types_mut(&mut self) -> Vec<&mut Type> {
+        match self {
+            Instruction::Alloca(i) => vec![&mut i.allocated_type],
+            Instruction::Trunc(i) => vec![&mut i.to_type],
+            Instruction::ZExt(i) => vec![&mut i.to_type],
+            Instruction::SExt(i) => vec![&mut i.to_type],
+            Instruction::FPTrunc(i) => vec![&mut i.to_type],
+            Instruction::FPExt(i) => vec![&mut i.to_type],
+            Instruction::FPToUI(i) => vec![&mut i.to_type],
+            Instruction::FPToSI(i) => vec![&mut i.to_type],
+            Instruction::UIToFP(i) => vec![&mut i.to_type],
+            Instruction::SIToFP(i) => vec![&mut i.to_type],
+            Instruction::PtrToInt(i) => vec![&mut i.to_type],
+            Instruction::IntToPtr(i) => vec![&mut i.to_type],
+            Instruction::BitCast(i) => vec![&mut i.to_type],
+            Instruction::AddrSpaceCast(i) => vec![&mut i.to_type],
+            Instruction::Phi(i) => vec![&mut i.to_type],
+            Instruction::Call(i) => i.function.as_mut().left().map(|asm| &mut asm.ty).into_iter().collect(),
+            Instruction::VAArg(i) => vec![&mut i.cur_type],
+            Instruction::LandingPad(i) => vec![&mut i.result_type],
+            _ => vec![],
+        }
+    }
+
+    /// Get all of the `Operand`s of the `Instruction`.
+    ///
+    /// For a `Phi`, these are the incoming values (not the incoming blocks).
+    /// For a `Call`, these are the called function (unless it is inline
+    /// assembly), followed by the arguments, followed by the operands of any
+    /// operand bundles.
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Instruction::Add(i) => vec![&i.operand0, &i.operand1],
+            Instruction::Sub(i) => vec![&i.operand0, &i.operand1],
+            Instruction::Mul(i) => vec![&i.operand0, &i.operand1],
+            Instruction::UDiv(i) => vec![&i.operand0, &i.operand1],
+            Instruction::SDiv(i) => vec![&i.operand0, &i.operand1],
+            Instruction::URem(i) => vec![&i.operand0, &i.operand1],
+            Instruction::SRem(i) => vec![&i.operand0, &i.operand1],
+            Instruction::And(i) => vec![&i.operand0, &i.operand1],
+            Instruction::Or(i) => vec![&i.operand0, &i.operand1],
+            Instruction::Xor(i) => vec![&i.operand0, &i.operand1],
+            Instruction::Shl(i) => vec![&i.operand0, &i.operand1],
+            Instruction::LShr(i) => vec![&i.operand0, &i.operand1],
+            Instruction::AShr(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FAdd(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FSub(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FMul(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FDiv(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FRem(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FNeg(i) => vec![&i.operand],
+            Instruction::ExtractElement(i) => vec![&i.vector, &i.index],
+            Instruction::InsertElement(i) => vec![&i.vector, &i.element, &i.index],
+            Instruction::ShuffleVector(i) => vec![&i.operand0, &i.operand1],
+            Instruction::ExtractValue(i) => vec![&i.aggregate],
+            Instruction::InsertValue(i) => vec![&i.aggregate, &i.element],
+            Instruction::Alloca(i) => vec![&i.num_elements],
+            Instruction::Load(i) => vec![&i.address],
+            Instruction::Store(i) => vec![&i.address, &i.value],
+            Instruction::Fence(_) => vec![],
+            Instruction::CmpXchg(i) => vec![&i.address, &i.expected, &i.replacement],
+            Instruction::AtomicRMW(i) => vec![&i.address, &i.value],
+            Instruction::GetElementPtr(i) => std::iter::once(&i.address).chain(i.indices.iter()).collect(),
+            Instruction::Trunc(i) => vec![&i.operand],
+            Instruction::ZExt(i) => vec![&i.operand],
+            Instruction::SExt(i) => vec![&i.operand],
+            Instruction::FPTrunc(i) => vec![&i.operand],
+            Instruction::FPExt(i) => vec![&i.operand],
+            Instruction::FPToUI(i) => vec![&i.operand],
+            Instruction::FPToSI(i) => vec![&i.operand],
+            Instruction::UIToFP(i) => vec![&i.operand],
+            Instruction::SIToFP(i) => vec![&i.operand],
+            Instruction::PtrToInt(i) => vec![&i.operand],
+            Instruction::IntToPtr(i) => vec![&i.operand],
+            Instruction::BitCast(i) => vec![&i.operand],
+            Instruction::AddrSpaceCast(i) => vec![&i.operand],
+            Instruction::ICmp(i) => vec![&i.operand0, &i.operand1],
+            Instruction::FCmp(i) => vec![&i.operand0, &i.operand1],
+            Instruction::Phi(i) => i.incoming_values.iter().map(|(op, _)| op).collect(),
+            Instruction::Select(i) => vec![&i.condition, &i.true_value, &i.false_value],
+            Instruction::Call(i) => i
+                .function
+                .as_ref()
+                .right()
+                .into_iter()
+                .chain(i.arguments.iter().map(|(op, _)| op))
+                .chain(i.operand_bundles.iter().flat_map(|bundle| bundle.operands.iter()))
+                .collect(),
+            Instruction::VAArg(i) => vec![&i.arg_list],
+            Instruction::LandingPad(_) => vec![],
+            Instruction::CatchPad(i) => std::iter::once(&i.catch_switch).chain(i.args.iter()).collect(),
+            Instruction::CleanupPad(i) => std::iter::once(&i.parent_pad).chain(i.args.iter()).collect(),
+        }
+    }
+
+    /// Like [`operands()`](enum.Instruction.html#method.operands), but get mutable references
+    pub fn operands_mut(&mut self) -> Vec<&mut Operand> {
+        match self {
+            Instruction::Add(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::Sub(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::Mul(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::UDiv(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::SDiv(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::URem(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::SRem(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::And(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::Or(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::Xor(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::Shl(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::LShr(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::AShr(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FAdd(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FSub(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FMul(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FDiv(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FRem(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FNeg(i) => vec![&mut i.operand],
+            Instruction::ExtractElement(i) => vec![&mut i.vector, &mut i.index],
+            Instruction::InsertElement(i) => vec![&mut i.vector, &mut i.element, &mut i.index],
+            Instruction::ShuffleVector(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::ExtractValue(i) => vec![&mut i.aggregate],
+            Instruction::InsertValue(i) => vec![&mut i.aggregate, &mut i.element],
+            Instruction::Alloca(i) => vec![&mut i.num_elements],
+            Instruction::Load(i) => vec![&mut i.address],
+            Instruction::Store(i) => vec![&mut i.address, &mut i.value],
+            Instruction::Fence(_) => vec![],
+            Instruction::CmpXchg(i) => vec![&mut i.address, &mut i.expected, &mut i.replacement],
+            Instruction::AtomicRMW(i) => vec![&mut i.address, &mut i.value],
+            Instruction::GetElementPtr(i) => std::iter::once(&mut i.address).chain(i.indices.iter_mut()).collect(),
+            Instruction::Trunc(i) => vec![&mut i.operand],
+            Instruction::ZExt(i) => vec![&mut i.operand],
+            Instruction::SExt(i) => vec![&mut i.operand],
+            Instruction::FPTrunc(i) => vec![&mut i.operand],
+            Instruction::FPExt(i) => vec![&mut i.operand],
+            Instruction::FPToUI(i) => vec![&mut i.operand],
+            Instruction::FPToSI(i) => vec![&mut i.operand],
+            Instruction::UIToFP(i) => vec![&mut i.operand],
+            Instruction::SIToFP(i) => vec![&mut i.operand],
+            Instruction::PtrToInt(i) => vec![&mut i.operand],
+            Instruction::IntToPtr(i) => vec![&mut i.operand],
+            Instruction::BitCast(i) => vec![&mut i.operand],
+            Instruction::AddrSpaceCast(i) => vec![&mut i.operand],
+            Instruction::ICmp(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::FCmp(i) => vec![&mut i.operand0, &mut i.operand1],
+            Instruction::Phi(i) => i.incoming_values.iter_mut().map(|(op, _)| op).collect(),
+            Instruction::Select(i) => vec![&mut i.condition, &mut i.true_value, &mut i.false_value],
+            Instruction::Call(i) => i
+                .function
+                .as_mut()
+                .right()
+                .into_iter()
+                .chain(i.arguments.iter_mut().map(|(op, _)| op))
+                .chain(i.operand_bundles.iter_mut().flat_map(|bundle| bundle.operands.iter_mut()))
+                .collect(),
+            Instruction::VAArg(i) => vec![&mut i.arg_list],
+            Instruction::LandingPad(_) => vec![],
+            Instruction::CatchPad(i) => std::iter::once(&mut i.catch_switch).chain(i.args.iter_mut()).collect(),
+            Instruction::CleanupPad(i) => std::iter::once(&mut i.parent_pad).chain(i.args.iter_mut()).collect(),
+        }
+    }
+}
+
 /* --TODO not yet implemented: metadata
 pub trait HasMetadata {
     fn get_metadata(&self) -> &InstructionMetadata;
@@ -602,7 +933,8 @@ macro_rules! void_typed {
 }
 
 /// See [LLVM 9 docs on the 'add' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#add-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Add {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -618,7 +950,8 @@ impl_binop!(Add, Add);
 binop_same_type!(Add);
 
 /// See [LLVM 9 docs on the 'sub' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#sub-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sub {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -634,7 +967,8 @@ impl_binop!(Sub, Sub);
 binop_same_type!(Sub);
 
 /// See [LLVM 9 docs on the 'mul' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#mul-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mul {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -650,7 +984,8 @@ impl_binop!(Mul, Mul);
 binop_same_type!(Mul);
 
 /// See [LLVM 9 docs on the 'udiv' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#udiv-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UDiv {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -665,7 +1000,8 @@ impl_binop!(UDiv, UDiv);
 binop_same_type!(UDiv);
 
 /// See [LLVM 9 docs on the 'sdiv' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#sdiv-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SDiv {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -680,7 +1016,8 @@ impl_binop!(SDiv, SDiv);
 binop_same_type!(SDiv);
 
 /// See [LLVM 9 docs on the 'urem' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#urem-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct URem {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -694,7 +1031,8 @@ impl_binop!(URem, URem);
 binop_same_type!(URem);
 
 /// See [LLVM 9 docs on the 'srem' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#srem-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SRem {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -709,7 +1047,8 @@ binop_same_type!(SRem);
 
 /// Bitwise logical and.
 /// See [LLVM 9 docs on the 'and' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#and-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct And {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -724,7 +1063,8 @@ binop_same_type!(And);
 
 /// Bitwise logical inclusive or.
 /// See [LLVM 9 docs on the 'or' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#or-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Or {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -739,7 +1079,8 @@ binop_same_type!(Or);
 
 /// Bitwise logical exclusive or.
 /// See [LLVM 9 docs on the 'xor' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#xor-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Xor {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -754,7 +1095,8 @@ binop_same_type!(Xor);
 
 /// Shift left.
 /// See [LLVM 9 docs on the 'shl' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#shl-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shl {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -771,7 +1113,8 @@ binop_left_type!(Shl);
 
 /// Logical shift right.
 /// See [LLVM 9 docs on the 'lshr' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#lshr-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LShr {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -787,7 +1130,8 @@ binop_left_type!(LShr);
 
 /// Arithmetic shift right.
 /// See [LLVM 9 docs on the 'ashr' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#ashr-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AShr {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -803,7 +1147,8 @@ binop_left_type!(AShr);
 
 /// Floating-point add.
 /// See [LLVM 9 docs on the 'fadd' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fadd-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FAdd {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -819,7 +1164,8 @@ binop_same_type!(FAdd);
 
 /// Floating-point sub.
 /// See [LLVM 9 docs on the 'fsub' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fsub-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FSub {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -835,7 +1181,8 @@ binop_same_type!(FSub);
 
 /// Floating-point multiply.
 /// See [LLVM 9 docs on the 'fmul' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fmul-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FMul {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -851,7 +1198,8 @@ binop_same_type!(FMul);
 
 /// Floating-point divide.
 /// See [LLVM 9 docs on the 'fdiv' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fdiv-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FDiv {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -867,7 +1215,8 @@ binop_same_type!(FDiv);
 
 /// Floating-point remainder.
 /// See [LLVM 9 docs on the 'frem' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#frem-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FRem {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -883,7 +1232,8 @@ binop_same_type!(FRem);
 
 /// Floating-point unary negation.
 /// See [LLVM 9 docs on the 'fneg' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fneg-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FNeg {
     pub operand: Operand,
     pub dest: Name,
@@ -898,7 +1248,8 @@ unop_same_type!(FNeg);
 
 /// Get an element from a vector at a specified index.
 /// See [LLVM 9 docs on the 'extractelement' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#extractelement-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtractElement {
     pub vector: Operand,
     pub index: Operand,
@@ -921,7 +1272,8 @@ impl Typed for ExtractElement {
 
 /// Insert an element into a vector at a specified index.
 /// See [LLVM 9 docs on the 'insertelement' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#insertelement-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InsertElement {
     pub vector: Operand,
     pub element: Operand,
@@ -941,7 +1293,8 @@ impl Typed for InsertElement {
 }
 
 /// See [LLVM 9 docs on the 'shufflevector' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#shufflevector-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShuffleVector {
     pub operand0: Operand,
     pub operand1: Operand,
@@ -954,6 +1307,29 @@ pub struct ShuffleVector {
 impl_inst!(ShuffleVector, ShuffleVector);
 impl_hasresult!(ShuffleVector);
 
+impl ShuffleVector {
+    /// Get the mask as a list of indices into the concatenation of
+    /// `operand0` and `operand1`, with `-1` for `undef` elements.
+    ///
+    /// The mask of a `ShuffleVector` in a parsed `Module` is always a
+    /// constant vector of `Int`s and `undef`s (parsing fails if it can't be
+    /// decoded); this panics if `self.mask` has been replaced with anything
+    /// else.
+    pub fn mask(&self) -> Vec<i32> {
+        let element = |c: &Constant| match c {
+            Constant::Int { value, .. } => *value as i32,
+            Constant::Undef(_) => -1,
+            c => panic!("Expected a ShuffleVector mask element to be an Int or Undef, got {:?}", c),
+        };
+        match &self.mask {
+            Constant::Vector(elements) => elements.iter().map(element).collect(),
+            Constant::AggregateZero(Type::VectorType { num_elements, .. }) => vec![0; *num_elements],
+            Constant::Undef(Type::VectorType { num_elements, .. }) => vec![-1; *num_elements],
+            mask => panic!("Expected a ShuffleVector mask to be a constant vector, got {:?}", mask),
+        }
+    }
+}
+
 impl Typed for ShuffleVector {
     fn get_type(&self) -> Type {
         let ty = self.operand0.get_type();
@@ -970,7 +1346,8 @@ impl Typed for ShuffleVector {
 
 /// Extract the value of a member field from an aggregate (struct or array) type.
 /// See [LLVM 9 docs on the 'extractvalue' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#extractvalue-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtractValue {
     pub aggregate: Operand,
     pub indices: Vec<u32>,
@@ -988,6 +1365,26 @@ impl Typed for ExtractValue {
     }
 }
 
+impl ExtractValue {
+    /// Get the type of the value at the indexed position in the aggregate,
+    /// resolving any named struct types using the given `Module`.
+    ///
+    /// Unlike `get_type()`, this works when the aggregate is (or contains) a
+    /// named struct. Returns `None` if an index is out of range, or if an
+    /// indexed type is not an aggregate or is an opaque struct.
+    pub fn result_type(&self, module: &Module) -> Option<Type> {
+        extractvalue_type_in(module, self.aggregate.get_type_in(module), &self.indices)
+    }
+}
+
+impl TypedInModule for ExtractValue {
+    fn get_type_in(&self, module: &Module) -> Type {
+        self.result_type(module).unwrap_or_else(|| {
+            panic!("ExtractValue index out of range, or into an opaque struct; aggregate is {:?}", self.aggregate)
+        })
+    }
+}
+
 fn ev_type(cur_type: Type, mut indices: impl Iterator<Item = u32>) -> Type {
     match indices.next() {
         None => cur_type,
@@ -1007,7 +1404,8 @@ fn ev_type(cur_type: Type, mut indices: impl Iterator<Item = u32>) -> Type {
 
 /// Insert a value into a member field of an aggregate (struct or array) type.
 /// See [LLVM 9 docs on the 'insertvalue' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#insertvalue-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InsertValue {
     pub aggregate: Operand,
     pub element: Operand,
@@ -1028,7 +1426,8 @@ impl Typed for InsertValue {
 
 /// Allocate memory on the stack.
 /// See [LLVM 9 docs on the 'alloca' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#alloca-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Alloca {
     pub allocated_type: Type,
     pub num_elements: Operand, // llvm-hs-pure has Option<Operand>
@@ -1049,7 +1448,8 @@ impl Typed for Alloca {
 
 /// Load a value from memory.
 /// See [LLVM 9 docs on the 'load' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#load-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Load {
     pub address: Operand,
     pub dest: Name,
@@ -1072,9 +1472,20 @@ impl Typed for Load {
     }
 }
 
+impl TypedInModule for Load {
+    fn get_type_in(&self, module: &Module) -> Type {
+        // the address may be a constant GEP
+        match self.address.get_type_in(module) {
+            Type::PointerType { pointee_type, .. } => *pointee_type,
+            ty => panic!("Expected a load address to be PointerType, got {:?}", ty),
+        }
+    }
+}
+
 /// Store a value to memory.
 /// See [LLVM 9 docs on the 'store' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#store-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Store {
     pub address: Operand,
     pub value: Operand,
@@ -1089,7 +1500,8 @@ impl_inst!(Store, Store);
 void_typed!(Store);
 
 /// See [LLVM 9 docs on the 'fence' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fence-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fence {
     pub atomicity: Atomicity,
     pub debugloc: Option<DebugLoc>,
@@ -1101,7 +1513,8 @@ void_typed!(Fence);
 
 /// Atomic compare and exchange.
 /// See [LLVM 9 docs on the 'cmpxchg' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#cmpxchg-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CmpXchg {
     pub address: Operand,
     pub expected: Operand,
@@ -1132,9 +1545,10 @@ impl Typed for CmpXchg {
 
 /// Atomic read-modify-write.
 /// See [LLVM 9 docs on the 'atomicrmw' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#atomicrmw-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AtomicRMW {
-    // pub operation: RMWOperation,  // this seems to not be exposed in the LLVM C API, only the C++ API
+    pub operation: RMWOperation,
     pub address: Operand,
     pub value: Operand,
     pub dest: Name,
@@ -1159,10 +1573,11 @@ impl Typed for AtomicRMW {
 /// Get the address of a subelement of an aggregate data structure.
 /// Only performs address calculation, does not actually access memory.
 /// See [LLVM 9 docs on the 'getelementptr' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#getelementptr-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetElementPtr {
     pub address: Operand,
-    pub indices: Vec<Operand>,
+    pub indices: OperandVec,
     pub dest: Name,
     pub in_bounds: bool,
     pub debugloc: Option<DebugLoc>,
@@ -1174,48 +1589,147 @@ impl_hasresult!(GetElementPtr);
 
 impl Typed for GetElementPtr {
     fn get_type(&self) -> Type {
-        gep_type(&self.address.get_type(), self.indices.iter())
+        self.get_type_with(None)
     }
 }
 
-fn gep_type<'a, 'b>(cur_type: &'a Type, mut indices: impl Iterator<Item = &'b Operand>) -> Type {
+impl TypedInModule for GetElementPtr {
+    fn get_type_in(&self, module: &Module) -> Type {
+        self.get_type_with(Some(module))
+    }
+}
+
+impl GetElementPtr {
+    /// If `module` is `None`, named struct types are resolved through their
+    /// weak references instead
+    fn get_type_with(&self, module: Option<&Module>) -> Type {
+        // a GEP whose base is a vector of pointers, or which has any vector
+        // indices, produces a vector of pointers
+        let (address_type, base_width) = match self.address.get_type() {
+            Type::VectorType { element_type, num_elements } => (*element_type, Some(num_elements)),
+            ty => (ty, None),
+        };
+        let width = base_width.or_else(|| {
+            self.indices.iter().find_map(|index| match index.get_type() {
+                Type::VectorType { num_elements, .. } => Some(num_elements),
+                _ => None,
+            })
+        });
+        let ty = gep_type(module, &address_type, self.indices.iter());
+        match width {
+            Some(num_elements) => Type::VectorType { element_type: Box::new(ty), num_elements },
+            None => ty,
+        }
+    }
+}
+
+impl GetElementPtr {
+    /// For a GEP whose indices are all constants, determine whether the
+    /// resulting pointer points to a value which lies entirely within the
+    /// object pointed to by `address` (taking that object to be a single
+    /// value of `address`'s pointee type). Offsets are computed in bytes, so
+    /// e.g. an out-of-range index into an inner array may still be in bounds
+    /// of the outer object.
+    ///
+    /// Returns `None` if this can't be determined statically, e.g. due to a
+    /// non-constant index or an opaque struct type.
+    ///
+    /// This is independent of the `in_bounds` field, which only records
+    /// whether the GEP has the `inbounds` keyword.
+    pub fn is_in_bounds_statically(&self, dl: &DataLayout, module: &Module) -> Option<bool> {
+        let mut cur_type = match self.address.get_type() {
+            Type::PointerType { pointee_type, .. } => *pointee_type,
+            _ => return None, // vector of pointers
+        };
+        let object_size = i128::from(dl.alloc_size(&cur_type)?);
+        let mut offset = match self.indices.first() {
+            Some(index) => constant_index(index)? * object_size,
+            None => 0,
+        };
+        for index in self.indices.iter().skip(1) {
+            let index = constant_index(index)?;
+            if let Type::NamedStructType { name, .. } = &cur_type {
+                let arc = module.named_struct_types.get(name)?.as_ref()?.clone(); // `None` if opaque
+                let inner = arc.read().unwrap().clone();
+                cur_type = inner;
+            }
+            cur_type = match cur_type {
+                Type::ArrayType { element_type, .. } | Type::VectorType { element_type, .. } => {
+                    offset += index * i128::from(dl.alloc_size(&element_type)?);
+                    *element_type
+                },
+                Type::StructType { mut element_types, is_packed } => {
+                    let field = usize::try_from(index).ok().filter(|&field| field < element_types.len())?;
+                    offset += i128::from(dl.struct_field_offsets(&element_types, is_packed)?[field]);
+                    element_types.swap_remove(field)
+                },
+                _ => return None,
+            };
+        }
+        let access_size = i128::from(dl.store_size(&cur_type)?);
+        Some(offset >= 0 && offset + access_size <= object_size)
+    }
+}
+
+/// If the `Operand` is a constant integer, get its value, treated as signed
+fn constant_index(index: &Operand) -> Option<i128> {
+    match index {
+        Operand::ConstantOperand(Constant::Int { bits, value }) if *bits < 64 => {
+            let shift = 64 - bits;
+            Some(i128::from(((value << shift) as i64) >> shift))
+        },
+        Operand::ConstantOperand(Constant::Int { value, .. }) => Some(i128::from(*value as i64)),
+        _ => None,
+    }
+}
+
+fn gep_type<'a, 'b>(module: Option<&Module>, cur_type: &'a Type, mut indices: impl Iterator<Item = &'b Operand>) -> Type {
     match indices.next() {
         None => Type::pointer_to(cur_type.clone()),  // iterator is done
         Some(index) => match cur_type {
-            Type::PointerType { pointee_type, .. } => gep_type(pointee_type, indices),
-            Type::VectorType { element_type, .. } => gep_type(element_type, indices),
-            Type::ArrayType { element_type, .. } => gep_type(element_type, indices),
-            Type::StructType { element_types, .. } => {
-                if let Operand::ConstantOperand(Constant::Int { value, .. }) = index {
-                    gep_type(
-                        element_types.get(*value as usize).expect("GEP index out of range"),
-                        indices,
-                    )
-                } else {
-                    panic!("Expected GEP index on a struct to be a Operand::ConstantOperand(Constant::Int); got {:?}", index)
-                }
-            },
-            Type::NamedStructType { ty, .. } => match ty {
-                None => panic!("GEP on an opaque struct type"),
-                Some(weak) => match weak.upgrade().expect("Weak reference disappeared").read().unwrap().deref() {
+            Type::PointerType { pointee_type, .. } => gep_type(module, pointee_type, indices),
+            Type::VectorType { element_type, .. } => gep_type(module, element_type, indices),
+            Type::ArrayType { element_type, .. } => gep_type(module, element_type, indices),
+            Type::StructType { element_types, .. } => gep_type(
+                module,
+                element_types.get(gep_struct_index(index)).expect("GEP index out of range"),
+                indices,
+            ),
+            Type::NamedStructType { name, ty } => {
+                let arc = match module {
+                    Some(module) => module.named_struct_types.get(name).cloned().flatten(),
+                    None => ty.as_ref().map(|weak| weak.upgrade().expect("Weak reference disappeared")),
+                };
+                match arc.expect("GEP on an opaque struct type").read().unwrap().deref() {
                     Type::StructType { element_types, .. } => {
-                        if let Operand::ConstantOperand(Constant::Int { value, .. }) = index {
-                            gep_type(element_types.get(*value as usize).expect("GEP index out of range"), indices)
-                        } else {
-                            panic!("Expected GEP index on a struct to be a Operand::ConstantOperand(Constant::Int); got {:?}", index)
-                        }
+                        gep_type(module, element_types.get(gep_struct_index(index)).expect("GEP index out of range"), indices)
                     },
                     ty => panic!("Expected NamedStructType inner type to be a StructType; got {:?}", ty),
-                },
-            }
+                }
+            },
             _ => panic!("Expected GEP base type to be a PointerType, VectorType, ArrayType, StructType, or NamedStructType; got {:?}", cur_type),
         }
     }
 }
 
+/// Get the value of a GEP index into a struct, which must be a constant
+/// integer (or, in a vector GEP, a splat of one)
+fn gep_struct_index(index: &Operand) -> usize {
+    match index {
+        Operand::ConstantOperand(Constant::Int { value, .. }) => *value as usize,
+        Operand::ConstantOperand(Constant::Vector(elements)) => match elements.first() {
+            Some(Constant::Int { value, .. }) => *value as usize,
+            _ => panic!("Expected vector GEP index on a struct to be a splat of a Constant::Int; got {:?}", index),
+        },
+        Operand::ConstantOperand(Constant::AggregateZero(_)) => 0,
+        _ => panic!("Expected GEP index on a struct to be a Operand::ConstantOperand(Constant::Int); got {:?}", index),
+    }
+}
+
 /// Truncate.
 /// See [LLVM 9 docs on the 'trunc' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#trunc-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trunc {
     pub operand: Operand,
     pub to_type: Type,
@@ -1230,7 +1744,8 @@ explicitly_typed!(Trunc);
 
 /// Zero-extend.
 /// See [LLVM 9 docs on the 'zext' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#zext-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExt {
     pub operand: Operand,
     pub to_type: Type,
@@ -1245,7 +1760,8 @@ explicitly_typed!(ZExt);
 
 /// Sign-extend.
 /// See [LLVM 9 docs on the 'sext' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#sext-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SExt {
     pub operand: Operand,
     pub to_type: Type,
@@ -1260,7 +1776,8 @@ explicitly_typed!(SExt);
 
 /// Truncate a floating-point value.
 /// See [LLVM 9 docs on the 'fptrunc' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fptrunc-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPTrunc {
     pub operand: Operand,
     pub to_type: Type,
@@ -1275,7 +1792,8 @@ explicitly_typed!(FPTrunc);
 
 /// Extend a floating-point value.
 /// See [LLVM 9 docs on the 'fpext' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fpext-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPExt {
     pub operand: Operand,
     pub to_type: Type,
@@ -1290,7 +1808,8 @@ explicitly_typed!(FPExt);
 
 /// Convert floating-point to unsigned integer.
 /// See [LLVM 9 docs on the 'fptoui' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fptoui-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPToUI {
     pub operand: Operand,
     pub to_type: Type,
@@ -1305,7 +1824,8 @@ explicitly_typed!(FPToUI);
 
 /// Convert floating-point to signed integer.
 /// See [LLVM 9 docs on the 'fptosi' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fptosi-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPToSI {
     pub operand: Operand,
     pub to_type: Type,
@@ -1320,7 +1840,8 @@ explicitly_typed!(FPToSI);
 
 /// Convert unsigned integer to floating-point.
 /// See [LLVM 9 docs on the 'uitofp' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#uitofp-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UIToFP {
     pub operand: Operand,
     pub to_type: Type,
@@ -1335,7 +1856,8 @@ explicitly_typed!(UIToFP);
 
 /// Convert signed integer to floating-point.
 /// See [LLVM 9 docs on the 'sitofp' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#sitofp-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SIToFP {
     pub operand: Operand,
     pub to_type: Type,
@@ -1350,7 +1872,8 @@ explicitly_typed!(SIToFP);
 
 /// Convert pointer to integer.
 /// See [LLVM 9 docs on the 'ptrtoint' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#ptrtoint-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PtrToInt {
     pub operand: Operand,
     pub to_type: Type,
@@ -1365,7 +1888,8 @@ explicitly_typed!(PtrToInt);
 
 /// Convert integer to pointer.
 /// See [LLVM 9 docs on the 'inttoptr' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#inttoptr-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntToPtr {
     pub operand: Operand,
     pub to_type: Type,
@@ -1380,7 +1904,8 @@ explicitly_typed!(IntToPtr);
 
 /// Convert between types without changing any bits.
 /// See [LLVM 9 docs on the 'bitcast' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#bitcast-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitCast {
     pub operand: Operand,
     pub to_type: Type,
@@ -1394,7 +1919,8 @@ impl_unop!(BitCast);
 explicitly_typed!(BitCast);
 
 /// See [LLVM 9 docs on the 'addrspacecast' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#addrspacecast-to-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddrSpaceCast {
     pub operand: Operand,
     pub to_type: Type,
@@ -1409,7 +1935,8 @@ explicitly_typed!(AddrSpaceCast);
 
 /// Compare integers, pointers, or vectors of integers or pointers.
 /// See [LLVM 9 docs on the 'icmp' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#icmp-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ICmp {
     pub predicate: IntPredicate,
     pub operand0: Operand,
@@ -1438,7 +1965,8 @@ impl Typed for ICmp {
 
 /// Compare floating-point values or vectors of floating-point values.
 /// See [LLVM 9 docs on the 'fcmp' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#fcmp-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FCmp {
     pub predicate: FPPredicate,
     pub operand0: Operand,
@@ -1466,7 +1994,8 @@ impl Typed for FCmp {
 }
 
 /// See [LLVM 9 docs on the 'phi' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#phi-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Phi {
     pub incoming_values: Vec<(Operand, Name)>,
     pub dest: Name,
@@ -1480,7 +2009,8 @@ impl_hasresult!(Phi);
 explicitly_typed!(Phi);
 
 /// See [LLVM 9 docs on the 'select' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#select-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Select {
     pub condition: Operand,
     pub true_value: Operand,
@@ -1503,21 +2033,68 @@ impl Typed for Select {
 
 /// Function call.
 /// See [LLVM 9 docs on the 'call' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#call-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Call {
     pub function: Either<InlineAssembly, Operand>,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
+    /// The attributes of each argument at this call site (one list per
+    /// argument), e.g., `nocapture` for `call void @f(i8* nocapture %p)`.
+    /// These are the attributes on the call itself, not those declared on
+    /// the callee's parameters; they are the same as the attributes in
+    /// `arguments`.
+    pub argument_attributes: Vec<Vec<ParameterAttribute>>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub dest: Option<Name>, // will be None if the `function` returns void
     pub function_attributes: Vec<FunctionAttribute>, // llvm-hs has the equivalent of Vec<Either<GroupID, FunctionAttribute>>, but I'm not sure how the GroupID option comes up
-    pub is_tail_call: bool, // llvm-hs has the more sophisticated structure Option<TailCallKind>, but the LLVM C API just gives us true/false
+    pub tail: TailCallKind,
     pub calling_convention: CallingConvention,
+    pub operand_bundles: Vec<OperandBundle>,
     pub debugloc: Option<DebugLoc>,
+    /// For calls to `llvm.dbg.declare` and `llvm.dbg.value`, the decoded
+    /// (metadata) arguments; `None` for other calls.
+    /// See [`Function::local_variables()`](../function/struct.Function.html#method.local_variables).
+    pub debug_variable: Option<DbgVariableRef>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
 
 impl_inst!(Call, Call);
 
+impl Call {
+    /// For a direct call, get the name of the called function (or other
+    /// global), looking through any pointer casts. Returns `None` for
+    /// indirect calls, calls to inline assembly, and calls to numbered
+    /// (unnamed) globals.
+    ///
+    /// If the callee is a `GlobalAlias`, this is the name of the alias; use
+    /// [`Module::ultimate_target()`](../module/struct.Module.html#method.ultimate_target)
+    /// to find the aliased function.
+    pub fn called_function_name(&self) -> Option<&str> {
+        called_function_name(&self.function)
+    }
+}
+
+/// Get the name of the global called by a `Call` or `Invoke` with the given
+/// `function`, if it is a direct call
+pub(crate) fn called_function_name(function: &Either<InlineAssembly, Operand>) -> Option<&str> {
+    match function {
+        Either::Right(Operand::ConstantOperand(c)) => match c.strip_pointer_casts() {
+            Constant::GlobalReference { name, .. } => name.as_str(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// An operand bundle attached to a `Call` or `Invoke`, e.g., `"deopt"(i32 %x)`.
+/// See [LLVM 9 docs on Operand Bundles](https://releases.llvm.org/9.0.0/docs/LangRef.html#operand-bundles)
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperandBundle {
+    pub tag: String,
+    pub operands: OperandVec,
+}
+
 impl Typed for Call {
     fn get_type(&self) -> Type {
         match self.function.get_type() {
@@ -1531,7 +2108,8 @@ impl Typed for Call {
 }
 
 /// See [LLVM 9 docs on the 'va_arg' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#va-arg-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VAArg {
     pub arg_list: Operand,
     pub cur_type: Type,
@@ -1551,7 +2129,8 @@ impl Typed for VAArg {
 
 /// Used for exception handling.
 /// See [LLVM 9 docs on the 'landingpad' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#landingpad-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LandingPad {
     pub result_type: Type,
     pub clauses: Vec<LandingPadClause>,
@@ -1572,10 +2151,11 @@ impl Typed for LandingPad {
 
 /// Used for exception handling.
 /// See [LLVM 9 docs on the 'catchpad' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#catchpad-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CatchPad {
     pub catch_switch: Operand,
-    pub args: Vec<Operand>,
+    pub args: OperandVec,
     pub dest: Name,
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
@@ -1592,10 +2172,11 @@ impl Typed for CatchPad {
 
 /// Used for exception handling.
 /// See [LLVM 9 docs on the 'cleanuppad' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#cleanuppad-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CleanupPad {
     pub parent_pad: Operand,
-    pub args: Vec<Operand>,
+    pub args: OperandVec,
     pub dest: Name,
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
@@ -1610,10 +2191,18 @@ impl Typed for CleanupPad {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+/// The tail-call marker on a `Call`, if any.
+/// See [LLVM 9 docs on the 'call' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#call-instruction)
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TailCallKind {
+    /// No marker
+    None,
+    /// `tail`: a hint that the call may be a tail call
     Tail,
+    /// `musttail`: the call must be a tail call
     MustTail,
+    /// `notail`: the call must not be a tail call
     NoTail,
 }
 
@@ -1631,21 +2220,24 @@ pub struct FastMathFlags {
 }
 
 /// See [LLVM 9 docs on Atomic Memory Ordering Constraints](https://releases.llvm.org/9.0.0/docs/LangRef.html#ordering)
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Atomicity {
     pub synch_scope: SynchronizationScope,
     pub mem_ordering: MemoryOrdering,
 }
 
 /// See [LLVM 9 docs on Atomic Memory Ordering Constraints](https://releases.llvm.org/9.0.0/docs/LangRef.html#ordering)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SynchronizationScope {
     SingleThread,
     System,
 }
 
 /// See [LLVM 9 docs on Atomic Memory Ordering Constraints](https://releases.llvm.org/9.0.0/docs/LangRef.html#ordering)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemoryOrdering {
     Unordered,
     Monotonic,
@@ -1660,7 +2252,8 @@ pub enum MemoryOrdering {
 // --TODO this seems to be the data structure we want. But see notes on
 // InlineAssembly::from_llvm_ref()
 /*
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InlineAssembly {
     pub assembly: String,
     pub ty: Type,
@@ -1678,7 +2271,8 @@ pub enum AssemblyDialect {
 */
 // Instead we have this for now
 /// `InlineAssembly` needs more fields, but the necessary getter functions are apparently not exposed in the LLVM C API (only the C++ API)
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InlineAssembly {
     pub ty: Type,
 }
@@ -1690,7 +2284,8 @@ impl Typed for InlineAssembly {
 }
 
 /// See [LLVM 9 docs on the 'atomicrmw' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#i-atomicrmw)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RMWOperation {
     Xchg,
     Add,
@@ -1703,21 +2298,27 @@ pub enum RMWOperation {
     Min,
     UMax,
     UMin,
-}
-
-// --TODO this seems to be the data structure we want. But see notes on
-// LandingPadClause::from_llvm_ref()
-/*
-#[derive(PartialEq, Clone, Debug)]
+    FAdd,
+    FSub,
+    /// Requires LLVM 15 or later, so never produced when parsing with LLVM 9
+    FMax,
+    /// Requires LLVM 15 or later, so never produced when parsing with LLVM 9
+    FMin,
+    /// An operation we didn't recognize, e.g. one added in an LLVM version
+    /// newer than this crate knows about
+    Unknown,
+}
+
+/// A clause of a `LandingPad`.
+/// See [LLVM 9 docs on the 'landingpad' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#landingpad-instruction)
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LandingPadClause {
+    /// Catch exceptions of the type identified by the given type info
     Catch(Constant),
+    /// Filter exceptions using the given array of type infos
     Filter(Constant),
 }
-*/
-// Instead we have this for now
-/// `LandingPadClause` needs more fields, but the necessary getter functions are apparently not exposed in the LLVM C API (only the C++ API)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub struct LandingPadClause {}
 
 // ********* //
 // from_llvm //
@@ -1795,7 +2396,7 @@ impl Instruction {
             LLVMOpcode::LLVMSelect => Instruction::Select(Select::from_llvm_ref(inst, ctr, vnmap, gnmap, tnmap)),
             LLVMOpcode::LLVMCall => Instruction::Call(Call::from_llvm_ref(inst, ctr, vnmap, gnmap, tnmap)),
             LLVMOpcode::LLVMVAArg => Instruction::VAArg(VAArg::from_llvm_ref(inst, ctr, vnmap, gnmap, tnmap)),
-            LLVMOpcode::LLVMLandingPad => Instruction::LandingPad(LandingPad::from_llvm_ref(inst, ctr, tnmap)),
+            LLVMOpcode::LLVMLandingPad => Instruction::LandingPad(LandingPad::from_llvm_ref(inst, ctr, gnmap, tnmap)),
             LLVMOpcode::LLVMCatchPad => Instruction::CatchPad(CatchPad::from_llvm_ref(inst, ctr, vnmap, gnmap, tnmap)),
             LLVMOpcode::LLVMCleanupPad => Instruction::CleanupPad(CleanupPad::from_llvm_ref(inst, ctr, vnmap, gnmap, tnmap)),
             opcode => panic!("Instruction::from_llvm_ref called with a terminator instruction (opcode {:?})", opcode),
@@ -1935,7 +2536,17 @@ impl ShuffleVector {
         gnmap: &GlobalNameMap,
         tnmap: &mut TyNameMap,
     ) -> Self {
-        assert_eq!(unsafe { LLVMGetNumOperands(inst) }, 3);
+        // Before LLVM 11, the mask is the third operand. Since LLVM 11, it is
+        // stored as an array of ints rather than as an operand, and the LLVM 9
+        // C API we use has no accessor for it, so we decode it from the
+        // instruction's textual representation instead.
+        let num_operands = unsafe { LLVMGetNumOperands(inst) };
+        assert!(num_operands == 2 || num_operands == 3);
+        let mask = if num_operands == 3 {
+            Constant::from_llvm_ref(unsafe { LLVMGetOperand(inst, 2) }, gnmap, tnmap)
+        } else {
+            Self::mask_from_llvm_ref(inst)
+        };
         Self {
             operand0: Operand::from_llvm_ref(
                 unsafe { LLVMGetOperand(inst, 0) },
@@ -1949,12 +2560,69 @@ impl ShuffleVector {
                 gnmap,
                 tnmap,
             ),
-            mask: Constant::from_llvm_ref(unsafe { LLVMGetOperand(inst, 2) }, gnmap, tnmap),
+            mask,
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, ctr),
             debugloc: DebugLoc::from_llvm_with_col(inst),
             // metadata: InstructionMetadata::from_llvm_inst(inst),
         }
     }
+
+    /// Decode the mask of a `shufflevector` instruction or constant
+    /// expression which doesn't have it as an operand (see notes on
+    /// `from_llvm_ref()`).
+    ///
+    /// If the mask can't be decoded, the error is recorded with
+    /// `record_decode_error()`, so that parsing fails; in the meantime, we
+    /// return a (never observed) `undef` mask.
+    pub(crate) fn mask_from_llvm_ref(value: LLVMValueRef) -> Constant {
+        let num_elements = unsafe { LLVMGetVectorSize(LLVMTypeOf(value)) } as usize;
+        Self::mask_from_text(&unsafe { print_to_string(value) }, num_elements).unwrap_or_else(|e| {
+            record_decode_error(e);
+            Constant::Undef(Type::VectorType { element_type: Box::new(Type::i32()), num_elements })
+        })
+    }
+
+    /// Decode the mask from the textual representation of a `shufflevector`
+    /// instruction, e.g.
+    /// `%3 = shufflevector <4 x i32> %1, <4 x i32> %2, <2 x i32> <i32 0, i32 undef>, !dbg !7`.
+    /// Also works for `shufflevector` constant expressions.
+    ///
+    /// `num_elements` is the number of elements in the result (and thus the
+    /// mask).
+    pub fn mask_from_text(text: &str, num_elements: usize) -> Result<Constant, String> {
+        let parse_error = || format!("Failed to parse the mask of shufflevector {:?}", text);
+        let mask = match text.rfind("x i32> ") {
+            Some(idx) => &text[idx + "x i32> ".len() ..],
+            None => return Err(format!("Failed to find the mask of shufflevector {:?}", text)),
+        };
+        let elements: Vec<Constant> = if mask.starts_with("zeroinitializer") {
+            vec![Constant::Int { bits: 32, value: 0 }; num_elements]
+        } else if mask.starts_with("undef") || mask.starts_with("poison") {
+            vec![Constant::Undef(Type::i32()); num_elements]
+        } else {
+            let end = mask.find('>').ok_or_else(parse_error)?;
+            mask.get(1 .. end)
+                .ok_or_else(parse_error)?
+                .split(',')
+                .map(|element| match element.trim().trim_start_matches("i32 ") {
+                    "undef" | "poison" => Ok(Constant::Undef(Type::i32())),
+                    value => Ok(Constant::Int {
+                        bits: 32,
+                        value: value.parse().map_err(|_| parse_error())?,
+                    }),
+                })
+                .collect::<Result<_, String>>()?
+        };
+        if elements.len() != num_elements {
+            return Err(format!(
+                "Expected {} elements in the mask of shufflevector {:?}, found {}",
+                num_elements,
+                text,
+                elements.len()
+            ));
+        }
+        Ok(Constant::Vector(elements))
+    }
 }
 
 impl ExtractValue {
@@ -2184,6 +2852,7 @@ impl AtomicRMW {
     ) -> Self {
         assert_eq!(unsafe { LLVMGetNumOperands(inst) }, 2);
         Self {
+            operation: RMWOperation::from_llvm_ref(inst),
             address: Operand::from_llvm_ref(
                 unsafe { LLVMGetOperand(inst, 0) },
                 vnmap,
@@ -2203,6 +2872,46 @@ impl AtomicRMW {
     }
 }
 
+impl RMWOperation {
+    /// The LLVM 9 C API has no way to get the operation of an `atomicrmw`
+    /// (`LLVMGetAtomicRMWBinOp` was added in LLVM 10), so we recover it from
+    /// the instruction's textual representation
+    pub(crate) fn from_llvm_ref(inst: LLVMValueRef) -> Self {
+        Self::from_text(&unsafe { print_to_string(inst) })
+    }
+
+    /// Get the operation from the textual representation of an `atomicrmw`,
+    /// e.g. `%old = atomicrmw volatile fadd float* %p, float 1.0 seq_cst`.
+    ///
+    /// Returns `RMWOperation::Unknown` if the operation isn't one we
+    /// recognize, or if `text` isn't the text of an `atomicrmw` at all.
+    pub fn from_text(text: &str) -> Self {
+        let rest = match text.find("= atomicrmw ") {
+            Some(idx) => &text[idx + "= atomicrmw ".len() ..],
+            None => return RMWOperation::Unknown,
+        };
+        let rest = rest.trim_start_matches("volatile ");
+        match rest.split(' ').next() {
+            Some("xchg") => RMWOperation::Xchg,
+            Some("add") => RMWOperation::Add,
+            Some("sub") => RMWOperation::Sub,
+            Some("and") => RMWOperation::And,
+            Some("nand") => RMWOperation::Nand,
+            Some("or") => RMWOperation::Or,
+            Some("xor") => RMWOperation::Xor,
+            Some("max") => RMWOperation::Max,
+            Some("min") => RMWOperation::Min,
+            Some("umax") => RMWOperation::UMax,
+            Some("umin") => RMWOperation::UMin,
+            Some("fadd") => RMWOperation::FAdd,
+            Some("fsub") => RMWOperation::FSub,
+            Some("fmax") => RMWOperation::FMax,
+            Some("fmin") => RMWOperation::FMin,
+            _ => RMWOperation::Unknown,
+        }
+    }
+}
+
 impl GetElementPtr {
     pub(crate) fn from_llvm_ref(
         inst: LLVMValueRef,
@@ -2421,6 +3130,7 @@ pub(crate) struct CallInfo {
     pub return_attributes: Vec<ParameterAttribute>,
     pub function_attributes: Vec<FunctionAttribute>,
     pub calling_convention: CallingConvention,
+    pub operand_bundles: Vec<OperandBundle>,
 }
 
 impl CallInfo {
@@ -2461,9 +3171,10 @@ impl CallInfo {
                                 LLVMGetCallSiteAttributes(inst, (i + 1) as u32, attrs.as_mut_ptr());
                                 attrs.set_len(num_attrs as usize);
                             };
+                            let ty = operand.get_type();
                             attrs
                                 .into_iter()
-                                .filter_map(ParameterAttribute::from_llvm_ref)
+                                .filter_map(|a| ParameterAttribute::param_from_llvm_ref(a, &ty))
                                 .collect()
                         };
                         (operand, attrs)
@@ -2499,7 +3210,92 @@ impl CallInfo {
             calling_convention: CallingConvention::from_u32(unsafe {
                 LLVMGetInstructionCallConv(inst)
             }),
+            operand_bundles: OperandBundle::all_from_llvm_ref(inst, vnmap, gnmap, tnmap),
+        }
+    }
+}
+
+impl OperandBundle {
+    // Call this function only on a Call instruction or an Invoke or CallBr terminator
+    fn all_from_llvm_ref(
+        inst: LLVMValueRef,
+        vnmap: &ValToNameMap,
+        gnmap: &GlobalNameMap,
+        tnmap: &mut TyNameMap,
+    ) -> Vec<Self> {
+        // the operands are the arguments, then the operands of all the
+        // bundles, then the successor blocks (if any), then the called function
+        let num_args = unsafe { LLVMGetNumArgOperands(inst) } as u32;
+        let num_successors = if unsafe { LLVMIsATerminatorInst(inst) }.is_null() {
+            0
+        } else {
+            unsafe { LLVMGetNumSuccessors(inst) }
+        };
+        let num_operands = unsafe { LLVMGetNumOperands(inst) } as u32;
+        if num_operands == num_args + num_successors + 1 {
+            // The LLVM 9 C API has no way to get operand bundles, so we
+            // recover their tags from the textual representation of the
+            // instruction. To save that expense in the common case, we assume
+            // there are no bundles if there are no bundle operands. (This
+            // misses bundles which all have zero operands.)
+            return vec![];
+        }
+        let mut next_operand = num_args;
+        Self::tags_and_sizes_from_text(&unsafe { print_to_string(inst) })
+            .into_iter()
+            .map(|(tag, size)| {
+                let operands = (next_operand .. next_operand + size as u32)
+                    .map(|i| Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, i) }, vnmap, gnmap, tnmap))
+                    .collect();
+                next_operand += size as u32;
+                Self { tag, operands }
+            })
+            .collect()
+    }
+
+    /// Get the tag and number of operands of each operand bundle in the
+    /// textual representation of a call, e.g.
+    /// `call void @f(i32 %x) [ "deopt"(i32 %x, i32 1), "gc-live"() ], !dbg !7`
+    fn tags_and_sizes_from_text(text: &str) -> Vec<(String, usize)> {
+        let mut bundles = vec![];
+        let mut rest = match text.find(" [ \"") {
+            Some(idx) => &text[idx + " [ ".len() ..],
+            None => return bundles,
+        };
+        while rest.starts_with('"') {
+            let tag_end = match rest[1 ..].find('"') {
+                Some(idx) => idx + 1,
+                None => break,
+            };
+            let tag = rest[1 .. tag_end].to_owned();
+            rest = &rest[tag_end + 1 ..];
+            // count the top-level commas between the parens, ignoring any
+            // inside nested types, constants, or strings
+            let mut depth = 0;
+            let mut in_string = false;
+            let mut commas = 0;
+            let mut end = rest.len();
+            for (i, c) in rest.char_indices() {
+                match c {
+                    '"' => in_string = !in_string,
+                    _ if in_string => {},
+                    '(' | '[' | '{' | '<' => depth += 1,
+                    ')' | ']' | '}' | '>' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = i;
+                            break;
+                        }
+                    },
+                    ',' if depth == 1 => commas += 1,
+                    _ => {},
+                }
+            }
+            let size = if rest[1 .. end].trim().is_empty() { 0 } else { commas + 1 };
+            bundles.push((tag, size));
+            rest = rest[end ..].trim_start_matches(')').trim_start_matches(", ");
         }
+        bundles
     }
 }
 
@@ -2514,6 +3310,7 @@ impl Call {
         let callinfo = CallInfo::from_llvm_ref(inst, vnmap, gnmap, tnmap);
         Self {
             function: callinfo.function,
+            argument_attributes: callinfo.arguments.iter().map(|(_, attrs)| attrs.clone()).collect(),
             arguments: callinfo.arguments,
             return_attributes: callinfo.return_attributes,
             dest: if unsafe {
@@ -2525,14 +3322,53 @@ impl Call {
                 Some(Name::name_or_num(unsafe { get_value_name(inst) }, ctr))
             },
             function_attributes: callinfo.function_attributes,
-            is_tail_call: unsafe { LLVMIsTailCall(inst) } != 0,
+            tail: TailCallKind::from_llvm_ref(inst),
             calling_convention: callinfo.calling_convention,
+            operand_bundles: callinfo.operand_bundles,
             debugloc: DebugLoc::from_llvm_with_col(inst),
+            debug_variable: None, // filled in by `BasicBlock::from_llvm_ref()`
             // metadata: InstructionMetadata::from_llvm_inst(inst),
         }
     }
 }
 
+impl TailCallKind {
+    pub(crate) fn from_llvm_ref(call: LLVMValueRef) -> Self {
+        // The LLVM 9 C API only tells us whether the call is `tail` or
+        // `musttail` (and reports `notail` calls the same as unmarked ones),
+        // so we get the marker from its textual representation
+        Self::from_text(&unsafe { print_to_string(call) }).unwrap_or(TailCallKind::None)
+    }
+
+    /// Get the marker from the textual representation of a call, e.g.
+    /// `%5 = musttail call i32 @f(i32 %x)`.
+    ///
+    /// Returns `None` if `text` doesn't look like the text of a call.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let text = text.trim_start();
+        let text = if text.starts_with('%') {
+            // skip the dest, which may be quoted
+            let name_end = match text.strip_prefix("%\"") {
+                Some(quoted) => quoted.find('"').map_or(0, |idx| idx + 3),
+                None => 0,
+            };
+            match text.get(name_end ..).and_then(|rest| rest.find(" = ")) {
+                Some(idx) => &text[name_end + idx + " = ".len() ..],
+                None => text,
+            }
+        } else {
+            text
+        };
+        match text.split(' ').next() {
+            Some("tail") => Some(TailCallKind::Tail),
+            Some("musttail") => Some(TailCallKind::MustTail),
+            Some("notail") => Some(TailCallKind::NoTail),
+            Some("call") => Some(TailCallKind::None),
+            _ => None,
+        }
+    }
+}
+
 impl VAArg {
     pub(crate) fn from_llvm_ref(
         inst: LLVMValueRef,
@@ -2561,6 +3397,7 @@ impl LandingPad {
     pub(crate) fn from_llvm_ref(
         inst: LLVMValueRef,
         ctr: &mut usize,
+        gnmap: &GlobalNameMap,
         tnmap: &mut TyNameMap,
     ) -> Self {
         Self {
@@ -2568,7 +3405,7 @@ impl LandingPad {
             clauses: {
                 let num_clauses = unsafe { LLVMGetNumClauses(inst) };
                 (0..num_clauses)
-                    .map(|i| LandingPadClause::from_llvm_ref(unsafe { LLVMGetClause(inst, i) }))
+                    .map(|i| LandingPadClause::from_llvm_ref(unsafe { LLVMGetClause(inst, i) }, gnmap, tnmap))
                     .collect()
             },
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, ctr),
@@ -2686,10 +3523,14 @@ impl InlineAssembly {
 }
 
 impl LandingPadClause {
-    pub(crate) fn from_llvm_ref(_lpc: LLVMValueRef) -> Self {
-        // The LLVM C API has an enum `LLVMLandingPadClauseTy`, but appears not
-        // to reference it. In particular, it's unclear how to tell whether a
-        // given clause is a `Catch` or a `Filter`.
-        Self {}
+    pub(crate) fn from_llvm_ref(lpc: LLVMValueRef, gnmap: &GlobalNameMap, tnmap: &mut TyNameMap) -> Self {
+        // The LLVM C API has no getter for the kind of a clause, but (as in
+        // LLVM's own `LandingPadInst::isFilter()`) filter clauses are exactly
+        // those of array type
+        let constant = Constant::from_llvm_ref(lpc, gnmap, tnmap);
+        match unsafe { LLVMGetTypeKind(LLVMTypeOf(lpc)) } {
+            llvm_sys::LLVMTypeKind::LLVMArrayTypeKind => LandingPadClause::Filter(constant),
+            _ => LandingPadClause::Catch(constant),
+        }
     }
 }
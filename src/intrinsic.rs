@@ -0,0 +1,147 @@
+use crate::constant::Constant;
+use crate::function::ParameterAttribute;
+use crate::instruction::Call;
+use crate::operand::Operand;
+use either::Either;
+
+/// A call to one of the commonly-used LLVM intrinsic functions, with its
+/// relevant arguments decoded.
+/// See [LLVM 9 docs on Intrinsic Functions](https://releases.llvm.org/9.0.0/docs/LangRef.html#intrinsic-functions)
+#[derive(PartialEq, Clone, Debug)]
+pub enum Intrinsic {
+    /// See [LLVM 9 docs on `llvm.memcpy`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-memcpy-intrinsic)
+    Memcpy {
+        dest: Operand,
+        src: Operand,
+        len: Operand,
+        /// Alignment of `dest`, if it has an `align` attribute
+        dest_alignment: Option<u64>,
+        /// Alignment of `src`, if it has an `align` attribute
+        src_alignment: Option<u64>,
+        is_volatile: bool,
+    },
+    /// See [LLVM 9 docs on `llvm.memmove`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-memmove-intrinsic)
+    Memmove {
+        dest: Operand,
+        src: Operand,
+        len: Operand,
+        /// Alignment of `dest`, if it has an `align` attribute
+        dest_alignment: Option<u64>,
+        /// Alignment of `src`, if it has an `align` attribute
+        src_alignment: Option<u64>,
+        is_volatile: bool,
+    },
+    /// See [LLVM 9 docs on `llvm.memset`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-memset-intrinsics)
+    Memset {
+        dest: Operand,
+        /// The byte value to fill with
+        val: Operand,
+        len: Operand,
+        /// Alignment of `dest`, if it has an `align` attribute
+        dest_alignment: Option<u64>,
+        is_volatile: bool,
+    },
+    /// See [LLVM 9 docs on `llvm.lifetime.start`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-lifetime-start-intrinsic)
+    LifetimeStart {
+        /// Size of the object in bytes, or `None` if the size is variable
+        size: Option<u64>,
+        ptr: Operand,
+    },
+    /// See [LLVM 9 docs on `llvm.lifetime.end`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-lifetime-end-intrinsic)
+    LifetimeEnd {
+        /// Size of the object in bytes, or `None` if the size is variable
+        size: Option<u64>,
+        ptr: Operand,
+    },
+    /// `llvm.dbg.declare`. The arguments are all metadata; see
+    /// [`Function::local_variables()`](../function/struct.Function.html#method.local_variables)
+    /// for the decoded information.
+    DbgDeclare,
+    /// `llvm.dbg.value`. The arguments are all metadata; see
+    /// [`Function::local_variables()`](../function/struct.Function.html#method.local_variables)
+    /// for the decoded information.
+    DbgValue,
+    /// `llvm.expect.*`: `value` is expected to be equal to `expected`.
+    /// See [LLVM 9 docs on `llvm.expect`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-expect-intrinsic)
+    Expect { value: Operand, expected: Operand },
+    /// See [LLVM 9 docs on `llvm.assume`](https://releases.llvm.org/9.0.0/docs/LangRef.html#llvm-assume-intrinsic)
+    Assume { condition: Operand },
+    /// Any other intrinsic, with its full name (e.g., `"llvm.ctpop.i32"`)
+    Other(String),
+}
+
+impl Intrinsic {
+    /// If the `Call` is a direct call to an intrinsic (a function whose name
+    /// begins with `llvm.`), decode it
+    pub fn from_call(call: &Call) -> Option<Self> {
+        let name = match &call.function {
+            Either::Right(Operand::ConstantOperand(Constant::GlobalReference { name, .. })) => name.as_str()?,
+            _ => return None,
+        };
+        if !name.starts_with("llvm.") {
+            return None;
+        }
+        Some(Self::decode(name, call).unwrap_or_else(|| Intrinsic::Other(name.to_string())))
+    }
+
+    /// Returns `None` if the intrinsic's arguments aren't as expected
+    fn decode(name: &str, call: &Call) -> Option<Self> {
+        let arg = |i: usize| call.arguments.get(i).map(|(op, _)| op.clone());
+        let alignment = |i: usize| {
+            call.arguments.get(i).and_then(|(_, attrs)| {
+                attrs.iter().find_map(|attr| match attr {
+                    ParameterAttribute::EnumAttribute { value: Some(align), .. } if attr.is_enum_attribute_named("align") => Some(align.get()),
+                    _ => None,
+                })
+            })
+        };
+        let const_int = |i: usize| match call.arguments.get(i) {
+            Some((Operand::ConstantOperand(Constant::Int { value, .. }), _)) => Some(*value),
+            _ => None,
+        };
+        // an object size of -1 indicates a variable size
+        let object_size = |i: usize| const_int(i).filter(|&size| size as i64 != -1);
+
+        if name.starts_with("llvm.memcpy.p") {
+            Some(Intrinsic::Memcpy {
+                dest: arg(0)?,
+                src: arg(1)?,
+                len: arg(2)?,
+                dest_alignment: alignment(0),
+                src_alignment: alignment(1),
+                is_volatile: const_int(3)? != 0,
+            })
+        } else if name.starts_with("llvm.memmove.p") {
+            Some(Intrinsic::Memmove {
+                dest: arg(0)?,
+                src: arg(1)?,
+                len: arg(2)?,
+                dest_alignment: alignment(0),
+                src_alignment: alignment(1),
+                is_volatile: const_int(3)? != 0,
+            })
+        } else if name.starts_with("llvm.memset.p") {
+            Some(Intrinsic::Memset {
+                dest: arg(0)?,
+                val: arg(1)?,
+                len: arg(2)?,
+                dest_alignment: alignment(0),
+                is_volatile: const_int(3)? != 0,
+            })
+        } else if name == "llvm.lifetime.start" || name.starts_with("llvm.lifetime.start.p") {
+            Some(Intrinsic::LifetimeStart { size: object_size(0), ptr: arg(1)? })
+        } else if name == "llvm.lifetime.end" || name.starts_with("llvm.lifetime.end.p") {
+            Some(Intrinsic::LifetimeEnd { size: object_size(0), ptr: arg(1)? })
+        } else if name == "llvm.dbg.declare" {
+            Some(Intrinsic::DbgDeclare)
+        } else if name == "llvm.dbg.value" {
+            Some(Intrinsic::DbgValue)
+        } else if name.starts_with("llvm.expect.i") {
+            Some(Intrinsic::Expect { value: arg(0)?, expected: arg(1)? })
+        } else if name == "llvm.assume" {
+            Some(Intrinsic::Assume { condition: arg(0)? })
+        } else {
+            None
+        }
+    }
+}
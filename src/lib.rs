@@ -2,22 +2,43 @@
 // properly get links to the public docs for llvm-ir's types
 #![doc(html_root_url = "https://cdisselkoen.github.io/llvm-ir")]
 
+mod archive;
 #[macro_use]
 mod from_llvm;
 mod iterators;
+#[cfg(feature = "serde")]
+mod serialize;
 
 pub mod basicblock;
 pub use basicblock::BasicBlock;
+pub mod callgraph;
+pub use callgraph::CallGraph;
+pub mod cfg;
+pub use cfg::Cfg;
 pub mod constant;
 pub use constant::Constant;
+pub mod context;
+pub use context::Context;
+pub mod datalayout;
+pub use datalayout::DataLayout;
+pub mod debuginfo;
+pub use debuginfo::DIType;
 pub mod debugloc;
 pub use debugloc::{DebugLoc, HasDebugLoc};
+pub mod diff;
+pub use diff::ModuleDiff;
+pub mod dominators;
+pub use dominators::DominatorTree;
 pub mod function;
 pub use function::Function;
 pub mod instruction;
 pub use instruction::Instruction;
+pub mod intrinsic;
+pub use intrinsic::Intrinsic;
 // pub mod metadata;
 // pub use metadata::Metadata;
+pub mod lower;
+pub use lower::{TacInstr, TacOperand};
 pub mod module;
 pub use module::Module;
 pub mod name;
@@ -29,4 +50,8 @@ pub use predicates::{FPPredicate, IntPredicate};
 pub mod terminator;
 pub use terminator::Terminator;
 pub mod types;
-pub use types::{Type, Typed};
+pub use types::{Type, TypeCategory, TypeError, Typed, TypedInModule};
+pub mod verify;
+pub use verify::VerifyError;
+pub mod warnings;
+pub use warnings::ParseWarning;
@@ -0,0 +1,302 @@
+//! A lossy lowering of LLVM IR into a simple three-address code, e.g., for
+//! visualization or for writing a simple interpreter.
+//!
+//! Types, attributes, flags (`nsw`, `volatile`, etc), metadata, and debug
+//! locations are all dropped; each instruction is reduced to its opcode, its
+//! result (if any), and its operands.
+
+use crate::constant::Constant;
+use crate::function::Function;
+use crate::instruction::{Instruction, InlineAssembly};
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::Terminator;
+use either::Either;
+use std::fmt;
+
+/// An operand of a [`TacInstr`](enum.TacInstr.html)
+#[derive(PartialEq, Clone, Debug)]
+pub enum TacOperand {
+    /// A local variable (a function parameter or the result of an instruction)
+    Temp(Name),
+    /// A constant, other than a reference to a global
+    Const(Constant),
+    /// A global variable or function
+    Global(Name),
+}
+
+/// A single three-address-code instruction, as produced by
+/// [`Function::to_three_address()`](../struct.Function.html#method.to_three_address)
+#[derive(PartialEq, Clone, Debug)]
+pub enum TacInstr {
+    /// Marks the start of the basic block with the given name
+    Label(Name),
+    /// `dest = op args`. `op` is the LLVM opcode (e.g., `add` or `load`),
+    /// followed by the predicate for comparisons (e.g., `icmp slt`).
+    /// `dest` is `None` for instructions without a result, such as `store`.
+    Op {
+        dest: Option<Name>,
+        op: String,
+        args: Vec<TacOperand>,
+    },
+    /// `dest = call function(args)`. `function` is `None` for inline assembly.
+    Call {
+        dest: Option<Name>,
+        function: Option<TacOperand>,
+        args: Vec<TacOperand>,
+    },
+    /// Unconditional branch to the given block
+    Br(Name),
+    /// Branch to `true_dest` if `condition` is true, else to `false_dest`
+    CondBr {
+        condition: TacOperand,
+        true_dest: Name,
+        false_dest: Name,
+    },
+    /// Return, with the returned value if the function is non-void
+    Ret(Option<TacOperand>),
+    /// Any other terminator (e.g., `switch` or `unreachable`): its opcode, its
+    /// operands, and the blocks it may transfer control to
+    Jump {
+        op: String,
+        args: Vec<TacOperand>,
+        targets: Vec<Name>,
+    },
+}
+
+impl TacOperand {
+    /// Returns `None` for `Operand::MetadataOperand`, which has no
+    /// three-address-code equivalent
+    fn from_operand(op: &Operand) -> Option<Self> {
+        match op {
+            Operand::LocalOperand { name, .. } => Some(TacOperand::Temp(name.clone())),
+            Operand::ConstantOperand(Constant::GlobalReference { name, .. }) => Some(TacOperand::Global(name.clone())),
+            Operand::ConstantOperand(c) => Some(TacOperand::Const(c.clone())),
+            Operand::MetadataOperand => None,
+        }
+    }
+
+    fn from_operands<'a>(ops: impl IntoIterator<Item = &'a Operand>) -> Vec<Self> {
+        ops.into_iter().filter_map(Self::from_operand).collect()
+    }
+
+    fn from_callee(function: &Either<InlineAssembly, Operand>) -> Option<Self> {
+        function.as_ref().right().and_then(Self::from_operand)
+    }
+}
+
+impl Function {
+    /// Lower the `Function` to three-address code: for each basic block, a
+    /// `TacInstr::Label` followed by the block's instructions and terminator.
+    ///
+    /// `invoke` and `callbr` are lowered to a `TacInstr::Call` followed by a
+    /// `TacInstr::Jump` to their successors.
+    pub fn to_three_address(&self) -> Vec<TacInstr> {
+        let mut tac = vec![];
+        for bb in &self.basic_blocks {
+            tac.push(TacInstr::Label(bb.name.clone()));
+            for inst in &bb.instrs {
+                tac.push(match inst {
+                    Instruction::Call(call) => TacInstr::Call {
+                        dest: call.dest.clone(),
+                        function: TacOperand::from_callee(&call.function),
+                        args: TacOperand::from_operands(call.arguments.iter().map(|(op, _)| op)),
+                    },
+                    _ => TacInstr::Op {
+                        dest: inst.try_get_result().cloned(),
+                        op: opcode(inst),
+                        args: TacOperand::from_operands(inst.operands()),
+                    },
+                });
+            }
+            match &bb.term {
+                Terminator::Ret(ret) => {
+                    tac.push(TacInstr::Ret(ret.return_operand.as_ref().and_then(TacOperand::from_operand)))
+                },
+                Terminator::Br(br) => tac.push(TacInstr::Br(br.dest.clone())),
+                Terminator::CondBr(condbr) => {
+                    if let Some(condition) = TacOperand::from_operand(&condbr.condition) {
+                        tac.push(TacInstr::CondBr {
+                            condition,
+                            true_dest: condbr.true_dest.clone(),
+                            false_dest: condbr.false_dest.clone(),
+                        })
+                    }
+                },
+                Terminator::Invoke(invoke) => {
+                    tac.push(TacInstr::Call {
+                        dest: Some(invoke.result.clone()),
+                        function: TacOperand::from_callee(&invoke.function),
+                        args: TacOperand::from_operands(invoke.arguments.iter().map(|(op, _)| op)),
+                    });
+                    tac.push(jump("invoke", vec![], &bb.term));
+                },
+                Terminator::CallBr(callbr) => {
+                    tac.push(TacInstr::Call {
+                        dest: Some(callbr.result.clone()),
+                        function: TacOperand::from_callee(&callbr.function),
+                        args: TacOperand::from_operands(callbr.arguments.iter().map(|(op, _)| op)),
+                    });
+                    tac.push(jump("callbr", vec![], &bb.term));
+                },
+                term => tac.push(jump(term_opcode(term), TacOperand::from_operands(term.operands()), term)),
+            }
+        }
+        tac
+    }
+}
+
+fn jump(op: &str, args: Vec<TacOperand>, term: &Terminator) -> TacInstr {
+    TacInstr::Jump {
+        op: op.to_owned(),
+        args,
+        targets: term.successors().into_iter().cloned().collect(),
+    }
+}
+
+/// The LLVM opcode of a `Terminator` other than `ret`, `br`, `invoke`, and `callbr`
+fn term_opcode(term: &Terminator) -> &'static str {
+    match term {
+        Terminator::Switch(_) => "switch",
+        Terminator::IndirectBr(_) => "indirectbr",
+        Terminator::Resume(_) => "resume",
+        Terminator::Unreachable(_) => "unreachable",
+        Terminator::CleanupRet(_) => "cleanupret",
+        Terminator::CatchRet(_) => "catchret",
+        Terminator::CatchSwitch(_) => "catchswitch",
+        Terminator::Ret(_) => "ret",
+        Terminator::Br(_) | Terminator::CondBr(_) => "br",
+        Terminator::Invoke(_) => "invoke",
+        Terminator::CallBr(_) => "callbr",
+    }
+}
+
+/// The LLVM opcode of an `Instruction`, with the predicate for comparisons
+fn opcode(inst: &Instruction) -> String {
+    let op = match inst {
+        Instruction::Add(_) => "add",
+        Instruction::Sub(_) => "sub",
+        Instruction::Mul(_) => "mul",
+        Instruction::UDiv(_) => "udiv",
+        Instruction::SDiv(_) => "sdiv",
+        Instruction::URem(_) => "urem",
+        Instruction::SRem(_) => "srem",
+        Instruction::And(_) => "and",
+        Instruction::Or(_) => "or",
+        Instruction::Xor(_) => "xor",
+        Instruction::Shl(_) => "shl",
+        Instruction::LShr(_) => "lshr",
+        Instruction::AShr(_) => "ashr",
+        Instruction::FAdd(_) => "fadd",
+        Instruction::FSub(_) => "fsub",
+        Instruction::FMul(_) => "fmul",
+        Instruction::FDiv(_) => "fdiv",
+        Instruction::FRem(_) => "frem",
+        Instruction::FNeg(_) => "fneg",
+        Instruction::ExtractElement(_) => "extractelement",
+        Instruction::InsertElement(_) => "insertelement",
+        Instruction::ShuffleVector(_) => "shufflevector",
+        Instruction::ExtractValue(_) => "extractvalue",
+        Instruction::InsertValue(_) => "insertvalue",
+        Instruction::Alloca(_) => "alloca",
+        Instruction::Load(_) => "load",
+        Instruction::Store(_) => "store",
+        Instruction::Fence(_) => "fence",
+        Instruction::CmpXchg(_) => "cmpxchg",
+        Instruction::AtomicRMW(_) => "atomicrmw",
+        Instruction::GetElementPtr(_) => "getelementptr",
+        Instruction::Trunc(_) => "trunc",
+        Instruction::ZExt(_) => "zext",
+        Instruction::SExt(_) => "sext",
+        Instruction::FPTrunc(_) => "fptrunc",
+        Instruction::FPExt(_) => "fpext",
+        Instruction::FPToUI(_) => "fptoui",
+        Instruction::FPToSI(_) => "fptosi",
+        Instruction::UIToFP(_) => "uitofp",
+        Instruction::SIToFP(_) => "sitofp",
+        Instruction::PtrToInt(_) => "ptrtoint",
+        Instruction::IntToPtr(_) => "inttoptr",
+        Instruction::BitCast(_) => "bitcast",
+        Instruction::AddrSpaceCast(_) => "addrspacecast",
+        // the `Debug` names of the predicates are their LLVM names, uppercased
+        Instruction::ICmp(icmp) => return format!("icmp {}", format!("{:?}", icmp.predicate).to_lowercase()),
+        Instruction::FCmp(fcmp) => return format!("fcmp {}", format!("{:?}", fcmp.predicate).to_lowercase()),
+        Instruction::Phi(_) => "phi",
+        Instruction::Select(_) => "select",
+        Instruction::Call(_) => "call",
+        Instruction::VAArg(_) => "va_arg",
+        Instruction::LandingPad(_) => "landingpad",
+        Instruction::CatchPad(_) => "catchpad",
+        Instruction::CleanupPad(_) => "cleanuppad",
+    };
+    op.to_owned()
+}
+
+impl fmt::Display for TacOperand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TacOperand::Temp(name) => write!(f, "%{}", bare(name)),
+            TacOperand::Const(c) => write!(f, "{}", c),
+            TacOperand::Global(name) => write!(f, "@{}", bare(name)),
+        }
+    }
+}
+
+/// Prints, e.g., `%sum = add %a, %b`, `store i32 0, %p`, or `br %cmp, %then, %else`
+impl fmt::Display for TacInstr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TacInstr::Label(name) => write!(f, "{}:", bare(name)),
+            TacInstr::Op { dest, op, args } => {
+                if let Some(dest) = dest {
+                    write!(f, "%{} = ", bare(dest))?;
+                }
+                write!(f, "{}", op)?;
+                fmt_list(f, " ", args)
+            },
+            TacInstr::Call { dest, function, args } => {
+                if let Some(dest) = dest {
+                    write!(f, "%{} = ", bare(dest))?;
+                }
+                match function {
+                    Some(function) => write!(f, "call {}(", function)?,
+                    None => write!(f, "call asm(")?,
+                }
+                fmt_list(f, "", args)?;
+                write!(f, ")")
+            },
+            TacInstr::Br(dest) => write!(f, "br %{}", bare(dest)),
+            TacInstr::CondBr { condition, true_dest, false_dest } => {
+                write!(f, "br {}, %{}, %{}", condition, bare(true_dest), bare(false_dest))
+            },
+            TacInstr::Ret(None) => write!(f, "ret"),
+            TacInstr::Ret(Some(op)) => write!(f, "ret {}", op),
+            TacInstr::Jump { op, args, targets } => {
+                write!(f, "{}", op)?;
+                fmt_list(f, " ", args)?;
+                for (i, target) in targets.iter().enumerate() {
+                    let sep = if i == 0 && args.is_empty() { " " } else { ", " };
+                    write!(f, "{}%{}", sep, bare(target))?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// A `Name` without `Name`'s `Display` quoting, e.g., `sum` or `3`
+fn bare(name: &Name) -> String {
+    match name {
+        Name::Name(s) => s.to_string(),
+        Name::Interned(s) => s.to_string(),
+        Name::Number(n) => n.to_string(),
+    }
+}
+
+/// Writes the items separated by commas, preceded by `first` if there are any
+fn fmt_list(f: &mut fmt::Formatter, first: &str, items: &[TacOperand]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        write!(f, "{}{}", if i == 0 { first } else { ", " }, item)?;
+    }
+    Ok(())
+}
@@ -1,14 +1,41 @@
+use crate::basicblock::BasicBlock;
+use crate::callgraph::CallGraph;
+use crate::constant;
 use crate::constant::Constant;
+use crate::datalayout::DataLayout;
+use crate::debuginfo::{DIGlobalVariable, DILocalVariable, DIScope, DIType, DbgVariableRef, MetadataNodeID};
 use crate::debugloc::*;
+use crate::diff::ModuleDiff;
 use crate::function::{Function, FunctionAttribute, GroupID};
+use crate::instruction::{Call, Instruction};
+use crate::intrinsic::Intrinsic;
 use crate::name::Name;
-use crate::types::{Type, Typed};
-use std::collections::HashMap;
-use std::path::Path;
+use crate::operand::Operand;
+use crate::terminator::{Invoke, Terminator};
+use crate::types::{Type, TypeError, Typed};
+use crate::verify::{verify_function, verify_module, verify_module_types, VerifyError};
+use crate::warnings::{collect_warnings, ParseWarning};
+use either::Either;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "bincode")]
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Written at the start of every cache by `Module::to_cache()`, as caches
+/// written by one version of `llvm-ir` can't be read by another
+#[cfg(feature = "bincode")]
+const CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// See [LLVM 9 docs on Module Structure](https://releases.llvm.org/9.0.0/docs/LangRef.html#module-structure)
+///
+/// With the `serde` feature, `Module` implements `Serialize` and
+/// `Deserialize`; see also [`to_cache()`](struct.Module.html#method.to_cache).
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Module {
     /// The name of the module
     pub name: String,
@@ -37,6 +64,30 @@ pub struct Module {
     // --TODO not yet implemented-- pub function_attribute_groups: Vec<FunctionAttributeGroup>,
     /// See [LLVM 9 docs on Module-Level Inline Assembly](https://releases.llvm.org/9.0.0/docs/LangRef.html#moduleasm)
     pub inline_assembly: String,
+    /// Map from metadata kind IDs to their names (e.g., `0` to `"dbg"`).
+    /// This includes all of the metadata kinds built into LLVM; the LLVM C
+    /// API provides no way to enumerate custom metadata kinds.
+    pub metadata_kind_names: HashMap<u32, String>,
+    /// Source-level types from the debug metadata of this `Module`, indexed
+    /// by `MetadataNodeID`. Currently these are the types of
+    /// [`DILocalVariable`](../debuginfo/struct.DILocalVariable.html)s and of
+    /// [`DIGlobalVariable`](../debuginfo/struct.DIGlobalVariable.html)s, and
+    /// the types they (transitively) refer to.
+    /// See [`resolve_ditype()`](struct.Module.html#method.resolve_ditype).
+    pub debug_types: Vec<DIType>,
+    /// Source-level scopes from the debug metadata of this `Module`, indexed
+    /// by `MetadataNodeID`: those containing the `DebugLoc`s of instructions,
+    /// and their (transitive) parent scopes.
+    /// See [`resolve_discope()`](struct.Module.html#method.resolve_discope).
+    pub debug_scopes: Vec<DIScope>,
+    /// Source-level descriptions of the `GlobalVariable`s in this `Module`,
+    /// indexed by `MetadataNodeID`.
+    /// See [`GlobalVariable::debug_info()`](struct.GlobalVariable.html#method.debug_info).
+    pub debug_global_variables: Vec<DIGlobalVariable>,
+    /// Source-level local variables referred to by the `llvm.dbg.declare` and
+    /// `llvm.dbg.value` calls in this `Module`, indexed by `MetadataNodeID`.
+    /// See [`Function::local_variables()`](../function/struct.Function.html#method.local_variables).
+    pub debug_local_variables: Vec<DILocalVariable>,
     // --TODO not yet implemented-- pub metadata_nodes: Vec<(MetadataNodeID, MetadataNode)>,
     // --TODO not yet implemented-- pub named_metadatas: Vec<NamedMetadata>,
     // --TODO not yet implemented-- pub comdats: Vec<Comdat>,
@@ -49,63 +100,1583 @@ impl Module {
         self.functions.iter().find(|func| func.name == name)
     }
 
+    /// Get the name of the metadata kind with the given ID (if known).
+    /// See [`metadata_kind_names`](struct.Module.html#structfield.metadata_kind_names).
+    pub fn metadata_kind_name(&self, id: u32) -> Option<&str> {
+        self.metadata_kind_names.get(&id).map(|s| s.as_str())
+    }
+
+    /// Get the source-level type with the given `MetadataNodeID` (if any).
+    /// See [`debug_types`](struct.Module.html#structfield.debug_types).
+    pub fn resolve_ditype(&self, id: MetadataNodeID) -> Option<&DIType> {
+        self.debug_types.get(id)
+    }
+
+    /// Get the source-level scope with the given `MetadataNodeID` (if any).
+    /// See [`debug_scopes`](struct.Module.html#structfield.debug_scopes).
+    pub fn resolve_discope(&self, id: MetadataNodeID) -> Option<&DIScope> {
+        self.debug_scopes.get(id)
+    }
+
+    /// Get the size of a pointer in the given address space, in bits,
+    /// according to the `Module`'s `data_layout`. Address spaces without an
+    /// explicit layout use address space 0's, which defaults to 64 bits.
+    ///
+    /// Returns `None` if the `data_layout` can't be parsed.
+    pub fn pointer_size_bits(&self, addr_space: AddrSpace) -> Option<u32> {
+        DataLayout::parse(&self.data_layout)
+            .ok()
+            .map(|dl| dl.pointer_size_bits(addr_space))
+    }
+
+    /// Get the `GlobalVariable` having the given `Name` (if any).
+    pub fn get_global_var_by_name(&self, name: &Name) -> Option<&GlobalVariable> {
+        self.global_vars.iter().find(|var| &var.name == name)
+    }
+
+    /// Iterate over the `GlobalVariable`s which are defined in this `Module`,
+    /// i.e., which have an initializer
+    pub fn defined_globals(&self) -> impl Iterator<Item = &GlobalVariable> {
+        self.global_vars.iter().filter(|var| var.initializer.is_some())
+    }
+
+    /// Iterate over the `GlobalVariable`s which are only declared in this
+    /// `Module` (e.g., `external global i32`), i.e., which have no initializer
+    pub fn external_globals(&self) -> impl Iterator<Item = &GlobalVariable> {
+        self.global_vars.iter().filter(|var| var.initializer.is_none())
+    }
+
+    /// Get the `GlobalAlias` having the given `Name` (if any).
+    pub fn get_global_alias_by_name(&self, name: &Name) -> Option<&GlobalAlias> {
+        self.global_aliases.iter().find(|alias| &alias.name == name)
+    }
+
+    /// Get every distinct `Type` used anywhere in the `Module`: in globals,
+    /// function signatures, instruction results and operands, and named
+    /// struct definitions, including the types those types are built from
+    /// (e.g., the element type of an array type).
+    pub fn all_types(&self) -> HashSet<Type> {
+        let mut types = HashSet::new();
+        for var in &self.global_vars {
+            add_type(&mut types, &var.ty);
+            if let Some(initializer) = &var.initializer {
+                add_type(&mut types, &initializer.get_type());
+            }
+        }
+        for alias in &self.global_aliases {
+            add_type(&mut types, &alias.ty);
+        }
+        for (name, ty) in &self.named_struct_types {
+            add_type(&mut types, &Type::NamedStructType {
+                name: name.clone(),
+                ty: ty.as_ref().map(Arc::downgrade),
+            });
+            if let Some(Type::StructType { element_types, .. }) = ty.as_ref().map(|arc| arc.read().unwrap().clone()) {
+                // the body itself isn't a distinct type, but its elements are
+                for element_type in &element_types {
+                    add_type(&mut types, element_type);
+                }
+            }
+        }
+        for func in &self.functions {
+            add_type(&mut types, &func.get_type());
+            for bb in &func.basic_blocks {
+                for inst in &bb.instrs {
+                    add_type(&mut types, &inst.get_type());
+                    for operand in inst.operands() {
+                        add_type(&mut types, &operand.get_type());
+                    }
+                }
+                add_type(&mut types, &bb.term.get_type());
+                for operand in bb.term.operands() {
+                    add_type(&mut types, &operand.get_type());
+                }
+            }
+        }
+        types
+    }
+
+    /// Get the address spaces of all of the pointer types used anywhere in
+    /// the `Module` (see [`all_types()`](struct.Module.html#method.all_types)),
+    /// e.g., the global and shared address spaces of a GPU target.
+    pub fn address_spaces_used(&self) -> HashSet<AddrSpace> {
+        self.all_types()
+            .iter()
+            .filter_map(|ty| match ty {
+                Type::PointerType { addr_space, .. } => Some(*addr_space),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get every distinct `Constant` used anywhere in the `Module`: in global
+    /// initializers and aliasees, in functions' personality functions and
+    /// prefix and prologue data, and as instruction and terminator operands,
+    /// including the `Constant`s those are built from (e.g., the elements of
+    /// an array or the operands of a constant expression). Constants inside
+    /// metadata are not included, as metadata operands aren't decoded.
+    ///
+    /// `Constant`s are listed in the order they are first encountered.
+    pub fn all_constants(&self) -> Vec<Constant> {
+        let mut constants = vec![];
+        for var in &self.global_vars {
+            if let Some(initializer) = &var.initializer {
+                add_constant(&mut constants, initializer);
+            }
+        }
+        for alias in &self.global_aliases {
+            add_constant(&mut constants, &alias.aliasee);
+        }
+        for func in &self.functions {
+            for c in func.personality_function.iter().chain(&func.prefix_data).chain(&func.prologue_data) {
+                add_constant(&mut constants, c);
+            }
+            for bb in &func.basic_blocks {
+                let operands = bb.instrs.iter().flat_map(|inst| inst.operands()).chain(bb.term.operands());
+                for operand in operands {
+                    if let Operand::ConstantOperand(c) = operand {
+                        add_constant(&mut constants, c);
+                    }
+                }
+            }
+        }
+        constants
+    }
+
+    /// Get the names of the named struct types which are recursive, i.e.,
+    /// which contain a reference to themselves (necessarily through a
+    /// pointer), either directly or via other named struct types.
+    ///
+    /// Code which walks into the bodies of named struct types can use this to
+    /// avoid recursing forever.
+    pub fn recursive_struct_types(&self) -> HashSet<String> {
+        // for each named struct, the named structs which its body refers to
+        let refs: HashMap<&str, HashSet<String>> = self
+            .named_struct_types
+            .iter()
+            .map(|(name, ty)| {
+                let mut names = HashSet::new();
+                if let Some(arc) = ty {
+                    add_struct_names(&mut names, &arc.read().unwrap());
+                }
+                (name.as_str(), names)
+            })
+            .collect();
+        refs.keys()
+            .filter(|&&name| {
+                let mut worklist: Vec<&str> = refs[name].iter().map(String::as_str).collect();
+                let mut seen = HashSet::new();
+                while let Some(cur) = worklist.pop() {
+                    if cur == name {
+                        return true;
+                    }
+                    if seen.insert(cur) {
+                        worklist.extend(refs.get(cur).into_iter().flatten().map(String::as_str));
+                    }
+                }
+                false
+            })
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// A hash of the `Module`'s structure, ignoring debug information (as
+    /// for [`Function::structural_hash()`](../function/struct.Function.html#method.structural_hash)):
+    /// its data layout, target triple, named struct types, global variables
+    /// and aliases, inline assembly, and functions. The `name` and
+    /// `source_file_name` of the `Module` aren't included.
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.data_layout.hash(&mut hasher);
+        self.target_triple.hash(&mut hasher);
+        let mut named_struct_types: Vec<_> = self.named_struct_types.iter().collect();
+        named_struct_types.sort_by_key(|(name, _)| *name);
+        for (name, ty) in named_struct_types {
+            name.hash(&mut hasher);
+            ty.as_ref().map(|ty| ty.read().unwrap().clone()).hash(&mut hasher);
+        }
+        for var in &self.global_vars {
+            var.without_debug_info().hash(&mut hasher);
+        }
+        self.global_aliases.hash(&mut hasher);
+        self.inline_assembly.hash(&mut hasher);
+        for func in &self.functions {
+            func.structural_hash().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Compare this `Module` (the "old" version) against `other` (the "new"
+    /// version), reporting which functions and global variables were added,
+    /// removed, or changed. Differences in debug information alone (e.g., in
+    /// `DebugLoc`s) aren't reported.
+    ///
+    /// Functions with the same
+    /// [`structural_hash()`](../function/struct.Function.html#method.structural_hash)
+    /// are considered unchanged without comparing them further, as are whole
+    /// `Module`s with the same
+    /// [`structural_hash()`](struct.Module.html#method.structural_hash).
+    pub fn diff(&self, other: &Module) -> ModuleDiff {
+        ModuleDiff::between(self, other)
+    }
+
+    /// Follow the chain of `GlobalAlias`es (if any) starting at the given
+    /// `Name`, and return the `Name` of the global which is ultimately aliased.
+    /// If the given `Name` is not the name of a `GlobalAlias`, it is returned
+    /// unchanged.
+    ///
+    /// Returns `None` if the chain of aliases is cyclic, or if some aliasee is
+    /// not a (possibly casted) reference to a global.
+    pub fn ultimate_target<'m>(&'m self, name: &'m Name) -> Option<&'m Name> {
+        let mut cur = name;
+        let mut seen = vec![];
+        while let Some(alias) = self.get_global_alias_by_name(cur) {
+            if seen.contains(&cur) {
+                return None;
+            }
+            seen.push(cur);
+            cur = referenced_global(&alias.aliasee)?;
+        }
+        Some(cur)
+    }
+
+    /// Rewrite every reference to a `GlobalAlias` (in global initializers,
+    /// instruction and terminator operands, personality functions, and
+    /// prefix and prologue data) into a
+    /// reference to the alias's [`ultimate_target()`](struct.Module.html#method.ultimate_target),
+    /// inserting a `BitCast` or `AddrSpaceCast` where the types differ.
+    ///
+    /// The `GlobalAlias` definitions themselves are left in place. References
+    /// to aliases whose ultimate target can't be determined are left alone.
+    pub fn resolve_all_aliases(&mut self) {
+        let replacements: HashMap<Name, Constant> = self
+            .global_aliases
+            .iter()
+            .filter_map(|alias| {
+                let target = self.ultimate_target(&alias.name)?;
+                let func = target.as_str().and_then(|name| self.get_func_by_name(name));
+                let target_ty = if let Some(func) = func {
+                    func.get_type()
+                } else {
+                    match &self.get_global_var_by_name(target)?.ty {
+                        Type::PointerType { pointee_type, .. } => (**pointee_type).clone(),
+                        _ => return None,
+                    }
+                };
+                let reference = Constant::GlobalReference { name: target.clone(), ty: target_ty };
+                let replacement = match (reference.get_type(), &alias.ty) {
+                    (ref_ty, alias_ty) if &ref_ty == alias_ty => reference,
+                    (Type::PointerType { addr_space: a, .. }, Type::PointerType { addr_space: b, .. }) if a != *b => {
+                        Constant::AddrSpaceCast(Box::new(constant::AddrSpaceCast {
+                            operand: reference,
+                            to_type: alias.ty.clone(),
+                        }))
+                    },
+                    _ => Constant::BitCast(Box::new(constant::BitCast {
+                        operand: reference,
+                        to_type: alias.ty.clone(),
+                    })),
+                };
+                Some((alias.name.clone(), replacement))
+            })
+            .collect();
+        if replacements.is_empty() {
+            return;
+        }
+
+        for var in &mut self.global_vars {
+            if let Some(initializer) = &mut var.initializer {
+                resolve_aliases_in_constant(initializer, &replacements);
+            }
+        }
+        for func in &mut self.functions {
+            for c in func.personality_function.iter_mut().chain(&mut func.prefix_data).chain(&mut func.prologue_data) {
+                resolve_aliases_in_constant(c, &replacements);
+            }
+            for bb in &mut func.basic_blocks {
+                let operands = bb
+                    .instrs
+                    .iter_mut()
+                    .flat_map(|inst| inst.operands_mut())
+                    .chain(bb.term.operands_mut());
+                for operand in operands {
+                    if let Operand::ConstantOperand(c) = operand {
+                        resolve_aliases_in_constant(c, &replacements);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rewrite every `Type` in the `Module` through `f`: the types of global
+    /// variables and aliases, function signatures, instructions, operands
+    /// and constants, and the bodies of named structs.
+    ///
+    /// Types are rewritten bottom-up: `f` is applied to each component of a
+    /// type (e.g., the pointee of a pointer type) before the type itself. The
+    /// bodies of named structs are rewritten in place, so all
+    /// `NamedStructType` references to them remain valid; `f` is also applied
+    /// to the `NamedStructType` references themselves, but not recursively to
+    /// their bodies.
+    ///
+    /// Integer constants are given the width of their mapped type (their
+    /// values are zero-extended or truncated accordingly). Other
+    /// constants whose type is implied by their value (e.g., floating-point
+    /// constants) are left alone.
+    pub fn map_types<F: FnMut(&Type) -> Type>(&mut self, mut f: F) {
+        let mut map = |ty: &mut Type| *ty = ty.map_recursive(&mut f);
+        for ty in self.named_struct_types.values().flatten() {
+            map(&mut ty.write().unwrap());
+        }
+        for var in &mut self.global_vars {
+            map(&mut var.ty);
+            if let Some(initializer) = &mut var.initializer {
+                map_constant_types(initializer, &mut map);
+            }
+        }
+        for alias in &mut self.global_aliases {
+            map(&mut alias.ty);
+            map_constant_types(&mut alias.aliasee, &mut map);
+        }
+        for func in &mut self.functions {
+            map(&mut func.return_type);
+            for param in &mut func.parameters {
+                map(&mut param.ty);
+            }
+            for c in func.personality_function.iter_mut().chain(&mut func.prefix_data).chain(&mut func.prologue_data) {
+                map_constant_types(c, &mut map);
+            }
+            for bb in &mut func.basic_blocks {
+                for inst in &mut bb.instrs {
+                    inst.types_mut().into_iter().for_each(&mut map);
+                    for operand in inst.operands_mut() {
+                        map_operand_types(operand, &mut map);
+                    }
+                }
+                bb.term.types_mut().into_iter().for_each(&mut map);
+                for operand in bb.term.operands_mut() {
+                    map_operand_types(operand, &mut map);
+                }
+                if let Terminator::Switch(switch) = &mut bb.term {
+                    for (value, _) in &mut switch.dests {
+                        map_constant_types(value, &mut map);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove all calls to the `llvm.dbg.*` intrinsics (e.g.,
+    /// `llvm.dbg.declare`, `llvm.dbg.value`, and `llvm.dbg.label`) from every
+    /// function. These calls have no results, so nothing else needs updating.
+    ///
+    /// Unlike [`strip_debug_info()`](struct.Module.html#method.strip_debug_info),
+    /// this leaves `DebugLoc`s and debug types in place. Afterwards,
+    /// [`Function::local_variables()`](../function/struct.Function.html#method.local_variables),
+    /// which is computed from these calls, is empty.
+    pub fn strip_debug_intrinsics(&mut self) {
+        for func in &mut self.functions {
+            for bb in &mut func.basic_blocks {
+                bb.instrs.retain(|inst| match inst.as_intrinsic() {
+                    Some(Intrinsic::DbgDeclare) | Some(Intrinsic::DbgValue) => false,
+                    Some(Intrinsic::Other(name)) => !name.starts_with("llvm.dbg."),
+                    _ => true,
+                });
+            }
+        }
+    }
+
+    /// Remove all debug information from the `Module`: `DebugLoc`s, local
+    /// variable information, debug types, and calls to the `llvm.dbg.*`
+    /// intrinsics
+    pub fn strip_debug_info(&mut self) {
+        self.strip_debug_intrinsics();
+        self.debug_types.clear();
+        self.debug_scopes.clear();
+        self.debug_global_variables.clear();
+        self.debug_local_variables.clear();
+        for var in &mut self.global_vars {
+            var.debugloc = None;
+            var.debug_variable = None;
+        }
+        for func in &mut self.functions {
+            func.debugloc = None;
+            for bb in &mut func.basic_blocks {
+                for inst in &mut bb.instrs {
+                    *inst.debugloc_mut() = None;
+                }
+                *bb.term.debugloc_mut() = None;
+            }
+        }
+    }
+
+    /// Remove all metadata from the `Module`.
+    ///
+    /// Currently the only metadata which `llvm-ir` decodes is debug
+    /// information, so this is equivalent to
+    /// [`strip_debug_info()`](struct.Module.html#method.strip_debug_info).
+    /// `MetadataOperand`s to calls other than the `llvm.dbg.*` intrinsics are
+    /// kept, as removing them would change the meaning of the code.
+    pub fn strip_metadata(&mut self) {
+        self.strip_debug_info();
+    }
+
+    /// Make all of the equal string `Name`s in the `Module` share storage, to
+    /// reduce memory usage, by converting them from `Name::Name` to
+    /// `Name::Interned`. This affects the `Name`s of globals, parameters,
+    /// basic blocks, and instruction results, and all references to them
+    /// (including in prefix and prologue data). It does not affect other
+    /// strings, such as `Function::name` or `section`s, which are plain
+    /// `String`s.
+    ///
+    /// `Name::Interned` compares, hashes, and prints the same as `Name::Name`
+    /// with the same string. Code which matches on `Name::Name` directly
+    /// won't see interned names, though; use `Name::as_str()` instead.
+    pub fn intern_strings(&mut self) {
+        let mut interned: HashSet<Arc<str>> = HashSet::new();
+        let mut intern = |name: &mut Name| {
+            if let Name::Name(s) = name {
+                let shared = match interned.get(s.as_str()) {
+                    Some(existing) => existing.clone(),
+                    None => {
+                        let new: Arc<str> = s.as_str().into();
+                        interned.insert(new.clone());
+                        new
+                    },
+                };
+                *name = Name::Interned(shared);
+            }
+        };
+        for var in &mut self.global_vars {
+            intern(&mut var.name);
+            if let Some(initializer) = &mut var.initializer {
+                visit_constant_names(initializer, &mut intern);
+            }
+        }
+        for alias in &mut self.global_aliases {
+            intern(&mut alias.name);
+            visit_constant_names(&mut alias.aliasee, &mut intern);
+        }
+        for func in &mut self.functions {
+            for c in func.personality_function.iter_mut().chain(&mut func.prefix_data).chain(&mut func.prologue_data) {
+                visit_constant_names(c, &mut intern);
+            }
+            for param in &mut func.parameters {
+                intern(&mut param.name);
+            }
+            for bb in &mut func.basic_blocks {
+                intern(&mut bb.name);
+                for inst in &mut bb.instrs {
+                    if let Some(dest) = inst.try_get_result_mut() {
+                        intern(dest);
+                    }
+                    if let Instruction::Phi(phi) = inst {
+                        for (_, block) in &mut phi.incoming_values {
+                            intern(block);
+                        }
+                    }
+                    for op in inst.operands_mut() {
+                        visit_operand_names(op, &mut intern);
+                    }
+                    if let Instruction::Call(Call { debug_variable: Some(DbgVariableRef { value: Some(value), .. }), .. }) = inst {
+                        intern(value);
+                    }
+                }
+                if let Some(result) = bb.term.try_get_result_mut() {
+                    intern(result);
+                }
+                for succ in bb.term.successors_mut() {
+                    intern(succ);
+                }
+                for op in bb.term.operands_mut() {
+                    visit_operand_names(op, &mut intern);
+                }
+            }
+        }
+    }
+
+    /// Replace the body of the function named `name` with `blocks`, keeping
+    /// its signature, attributes, and other properties. The first of `blocks`
+    /// becomes the entry block.
+    ///
+    /// The new body may only refer to the function's existing parameters and
+    /// to values it defines itself; it is checked as by
+    /// [`verify()`](struct.Module.html#method.verify), and if any problems
+    /// are found the `Module` is left unchanged.
+    pub fn replace_function_body(&mut self, name: &str, blocks: Vec<BasicBlock>) -> Result<(), ReplaceError> {
+        let func = self
+            .functions
+            .iter_mut()
+            .find(|func| func.name == name)
+            .ok_or_else(|| ReplaceError::NotFound(name.to_owned()))?;
+        if blocks.is_empty() {
+            return Err(ReplaceError::NoBlocks);
+        }
+        let old_blocks = std::mem::replace(&mut func.basic_blocks, blocks);
+        let errors = verify_function(func);
+        if !errors.is_empty() {
+            func.basic_blocks = old_blocks;
+            return Err(ReplaceError::Invalid(errors));
+        }
+        Ok(())
+    }
+
+    /// Rename the global (function, global variable, or global alias) named
+    /// `old` to `new`, updating every reference to it throughout the `Module`:
+    /// in instructions, global initializers (including `llvm.used` and
+    /// `llvm.global_ctors`), aliasees, personality functions, and prefix and
+    /// prologue data.
+    ///
+    /// `old` may also be a function which is only declared (not defined) in
+    /// this `Module`, in which case only the references to it are renamed.
+    /// Fails if there is no global named `old`, or if there already is one
+    /// named `new`.
+    pub fn rename_symbol(&mut self, old: &str, new: &str) -> Result<(), RenameError> {
+        if !self.has_symbol(old) {
+            return Err(RenameError::NotFound(old.to_owned()));
+        }
+        if old == new {
+            return Ok(());
+        }
+        if self.has_symbol(new) {
+            return Err(RenameError::AlreadyExists(new.to_owned()));
+        }
+
+        for func in &mut self.functions {
+            if func.name == old {
+                func.name = new.to_owned();
+            }
+        }
+        let (old, new) = (Name::from(old), Name::from(new));
+        let mut rename = |name: &mut Name| {
+            if *name == old {
+                *name = new.clone();
+            }
+        };
+        for var in &mut self.global_vars {
+            rename(&mut var.name);
+            if let Some(initializer) = &mut var.initializer {
+                visit_constant_names(initializer, &mut rename);
+            }
+        }
+        for alias in &mut self.global_aliases {
+            rename(&mut alias.name);
+            visit_constant_names(&mut alias.aliasee, &mut rename);
+        }
+        for func in &mut self.functions {
+            for c in func.personality_function.iter_mut().chain(&mut func.prefix_data).chain(&mut func.prologue_data) {
+                visit_constant_names(c, &mut rename);
+            }
+            for bb in &mut func.basic_blocks {
+                let operands = bb.instrs.iter_mut().flat_map(|inst| inst.operands_mut()).chain(bb.term.operands_mut());
+                for operand in operands {
+                    if let Operand::ConstantOperand(c) = operand {
+                        visit_constant_names(c, &mut rename);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Is there a global with the given name, either defined in the `Module`
+    /// or referenced from it (e.g., a declared function)?
+    fn has_symbol(&self, name: &str) -> bool {
+        let defined = self.functions.iter().any(|func| func.name == name);
+        let name = Name::from(name);
+        let defined = defined
+            || self.global_vars.iter().any(|var| var.name == name)
+            || self.global_aliases.iter().any(|alias| alias.name == name);
+        defined || {
+            let constants = self
+                .global_vars
+                .iter()
+                .filter_map(|var| var.initializer.as_ref())
+                .chain(self.global_aliases.iter().map(|alias| &alias.aliasee))
+                .chain(self.functions.iter().flat_map(|func| func.personality_function.iter().chain(&func.prefix_data).chain(&func.prologue_data)));
+            let operand_constants = self.functions.iter().flat_map(|func| {
+                func.basic_blocks.iter().flat_map(|bb| {
+                    bb.instrs
+                        .iter()
+                        .flat_map(|inst| inst.operands())
+                        .chain(bb.term.operands())
+                        .filter_map(|operand| match operand {
+                            Operand::ConstantOperand(c) => Some(c),
+                            _ => None,
+                        })
+                })
+            });
+            constants.chain(operand_constants).any(|c| constant_references(c, &name))
+        }
+    }
+
+    /// Put the `Module` in a canonical form, so that two `Module`s which
+    /// differ only in the order of their functions and globals, the numbering
+    /// of their unnamed values, or their debug information become equal.
+    ///
+    /// This strips debug information (see
+    /// [`strip_debug_info()`](struct.Module.html#method.strip_debug_info)),
+    /// sorts the functions, global variables, and global aliases by name, and
+    /// [`renumber()`](../function/struct.Function.html#method.renumber)s each
+    /// function.
+    pub fn canonicalize(&mut self) {
+        self.strip_debug_info();
+        self.functions.sort_by(|a, b| a.name.cmp(&b.name));
+        self.global_vars.sort_by(|a, b| a.name.cmp(&b.name));
+        self.global_aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        for func in &mut self.functions {
+            func.renumber();
+        }
+    }
+
+    /// Get the `Instruction` at the given location, or `None` if there is no
+    /// such function, block, or instruction (or if the location is of a
+    /// terminator; see
+    /// [`terminator_at()`](struct.Module.html#method.terminator_at))
+    pub fn instruction_at(&self, r: &InstructionRef) -> Option<&Instruction> {
+        self.get_func_by_name(&r.function)?.get_bb_by_name(&r.block)?.instrs.get(r.index)
+    }
+
+    /// Get the `Terminator` at the given location, or `None` if there is no
+    /// such function or block, or if the location is not of a terminator
+    pub fn terminator_at(&self, r: &InstructionRef) -> Option<&Terminator> {
+        let bb = self.get_func_by_name(&r.function)?.get_bb_by_name(&r.block)?;
+        if r.index == bb.instrs.len() {
+            Some(&bb.term)
+        } else {
+            None
+        }
+    }
+
+    /// Get the location of the instruction or terminator which defines the
+    /// local with the given `Name` in the given function, or `None` if it is
+    /// a parameter or is not defined
+    pub fn definition_of(&self, function: &str, name: &Name) -> Option<InstructionRef> {
+        self.locations_in(function)
+            .find(|(instr, _)| match instr {
+                Either::Left(instr) => instr.try_get_result() == Some(name),
+                Either::Right(term) => term.try_get_result() == Some(name),
+            })
+            .map(|(_, r)| r)
+    }
+
+    /// Get the locations of all of the instructions and terminators in the
+    /// given function which use the local with the given `Name` as an operand
+    /// (including as a `Phi` incoming value)
+    pub fn uses_of(&self, function: &str, name: &Name) -> Vec<InstructionRef> {
+        let is_use = |op: &&Operand| match op {
+            Operand::LocalOperand { name: n, .. } => n == name,
+            _ => false,
+        };
+        self.locations_in(function)
+            .filter(|(instr, _)| match instr {
+                Either::Left(instr) => instr.operands().iter().any(is_use),
+                Either::Right(term) => term.operands().iter().any(is_use),
+            })
+            .map(|(_, r)| r)
+            .collect()
+    }
+
+    /// Iterate over all of the instructions and terminators in the given
+    /// function, with their locations
+    pub(crate) fn locations_in<'m>(
+        &'m self,
+        function: &'m str,
+    ) -> impl Iterator<Item = (Either<&'m Instruction, &'m Terminator>, InstructionRef)> {
+        self.get_func_by_name(function).into_iter().flat_map(move |func| {
+            func.basic_blocks.iter().flat_map(move |bb| {
+                let r = move |index| InstructionRef { function: function.to_owned(), block: bb.name.clone(), index };
+                bb.instrs
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, instr)| (Either::Left(instr), r(index)))
+                    .chain(std::iter::once((Either::Right(&bb.term), r(bb.instrs.len()))))
+            })
+        })
+    }
+
+    /// Get the source-level frames at the given `DebugLoc`, from innermost
+    /// to outermost, by following its chain of
+    /// [`inlined_at`](../debugloc/struct.DebugLoc.html#structfield.inlined_at)
+    /// locations.
+    ///
+    /// The first frame is always the `DebugLoc` itself; if it is not in
+    /// inlined code, it is the only frame.
+    pub fn inline_chain(&self, loc: &DebugLoc) -> Vec<Frame> {
+        let mut frames = vec![];
+        let mut cur = Some(loc);
+        while let Some(loc) = cur {
+            frames.push(Frame { function: loc.function.clone(), line: loc.line, col: loc.col });
+            cur = loc.inlined_at.as_deref();
+        }
+        frames
+    }
+
+    /// Check the `Module` for structural problems, such as branches to blocks
+    /// which don't exist or uses of values which are never defined.
+    /// See [`VerifyError`](../verify/enum.VerifyError.html) for the full list
+    /// of checks.
+    pub fn verify(&self) -> Result<(), Vec<VerifyError>> {
+        verify_module(self)
+    }
+
+    /// Check that the operands of each instruction and terminator in the
+    /// `Module` have consistent types: e.g., that both operands of a binary
+    /// operation or comparison have the same type, that the value stored by
+    /// a `Store` has the type its address points to, that each `Ret` returns
+    /// the function's return type, and that the arguments of each call have
+    /// the types of the callee's parameters.
+    ///
+    /// Like [`verify()`](struct.Module.html#method.verify), this is a much
+    /// smaller set of checks than LLVM's own verifier.
+    pub fn verify_types(&self) -> Result<(), Vec<TypeError>> {
+        verify_module_types(self)
+    }
+
+    /// Apply the transform `f` to the `Module`, then
+    /// [`verify()`](struct.Module.html#method.verify) the result.
+    ///
+    /// The changes made by `f` are kept even if verification fails; see
+    /// [`transform_strict()`](struct.Module.html#method.transform_strict) to
+    /// roll them back instead.
+    pub fn transform<F: FnOnce(&mut Module)>(&mut self, f: F) -> Result<(), Vec<VerifyError>> {
+        f(self);
+        self.verify()
+    }
+
+    /// Like [`transform()`](struct.Module.html#method.transform), but if
+    /// verification fails, the `Module` is restored to its state before `f`
+    /// was applied.
+    ///
+    /// This clones the `Module` before applying `f`.
+    pub fn transform_strict<F: FnOnce(&mut Module)>(&mut self, f: F) -> Result<(), Vec<VerifyError>> {
+        let original = self.clone();
+        let result = self.transform(f);
+        if result.is_err() {
+            *self = original;
+        }
+        result
+    }
+
+    /// Heuristically identify the `Function`s which are likely to be entry
+    /// points into the `Module`. These are:
+    ///   - `main`;
+    ///   - functions with external linkage which are not called (other than
+    ///     recursively) from anywhere in the `Module`;
+    ///   - functions listed in `llvm.used` or `llvm.compiler.used`;
+    ///   - constructors and destructors listed in `llvm.global_ctors` or
+    ///     `llvm.global_dtors`.
+    pub fn likely_entry_points(&self) -> Vec<&Function> {
+        let callgraph = CallGraph::new(self);
+        let used: Vec<Name> = self.used_symbols().into_iter().chain(self.compiler_used_symbols()).collect();
+        let ctors_and_dtors: Vec<CtorEntry> = self.global_ctors().into_iter().chain(self.global_dtors()).collect();
+        let listed: Vec<&Name> = used
+            .iter()
+            .chain(ctors_and_dtors.iter().map(|entry| &entry.function))
+            .filter_map(|name| self.ultimate_target(name))
+            .collect();
+        self.functions
+            .iter()
+            .filter(|func| {
+                func.name == "main"
+                    || (func.linkage == Linkage::External
+                        && callgraph.callers(&func.name).iter().all(|&caller| caller == func.name))
+                    || listed.iter().any(|name| name.as_str() == Some(&func.name))
+            })
+            .collect()
+    }
+
+    /// Get the `Function`s which don't directly call any other function
+    /// defined in the `Module`; see
+    /// [`CallGraph::is_leaf()`](struct.CallGraph.html#method.is_leaf)
+    pub fn leaf_functions(&self) -> Vec<&Function> {
+        let callgraph = CallGraph::new(self);
+        self.functions
+            .iter()
+            .filter(|func| callgraph.is_leaf(&func.name))
+            .collect()
+    }
+
+    /// Get the `Function`s which directly call the function with the given
+    /// name (possibly through `GlobalAlias`es), in the order they appear in
+    /// the `Module`; see
+    /// [`CallGraph::callers()`](struct.CallGraph.html#method.callers)
+    pub fn functions_calling(&self, target: &str) -> Vec<&Function> {
+        let callgraph = CallGraph::new(self);
+        let callers = callgraph.callers(target);
+        self.functions
+            .iter()
+            .filter(|func| callers.contains(&func.name.as_str()))
+            .collect()
+    }
+
+    /// Get the `Name`s of the globals listed in `llvm.used`, which the
+    /// compiler, assembler, and linker are all required to preserve.
+    /// See [LLVM 9 docs on the `llvm.used` Global Variable](https://releases.llvm.org/9.0.0/docs/LangRef.html#the-llvm-used-global-variable)
+    pub fn used_symbols(&self) -> Vec<Name> {
+        self.special_global_elements("llvm.used")
+            .iter()
+            .filter_map(referenced_global)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the `Name`s of the globals listed in `llvm.compiler.used`, which
+    /// the compiler (but not necessarily the linker) is required to preserve.
+    /// See [LLVM 9 docs on the `llvm.compiler.used` Global Variable](https://releases.llvm.org/9.0.0/docs/LangRef.html#the-llvm-compiler-used-global-variable)
+    pub fn compiler_used_symbols(&self) -> Vec<Name> {
+        self.special_global_elements("llvm.compiler.used")
+            .iter()
+            .filter_map(referenced_global)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the constructors listed in `llvm.global_ctors`, sorted by priority
+    /// (i.e., in the order they will be called).
+    /// See [LLVM 9 docs on the `llvm.global_ctors` Global Variable](https://releases.llvm.org/9.0.0/docs/LangRef.html#the-llvm-global-ctors-global-variable)
+    pub fn global_ctors(&self) -> Vec<CtorEntry> {
+        self.decode_ctor_entries("llvm.global_ctors")
+    }
+
+    /// Get the destructors listed in `llvm.global_dtors`, sorted by priority
+    /// (i.e., in the order they will be called).
+    /// See [LLVM 9 docs on the `llvm.global_dtors` Global Variable](https://releases.llvm.org/9.0.0/docs/LangRef.html#the-llvm-global-dtors-global-variable)
+    pub fn global_dtors(&self) -> Vec<CtorEntry> {
+        self.decode_ctor_entries("llvm.global_dtors")
+    }
+
+    fn decode_ctor_entries(&self, global_name: &str) -> Vec<CtorEntry> {
+        let mut entries: Vec<CtorEntry> = self
+            .special_global_elements(global_name)
+            .iter()
+            .filter_map(|entry| match entry {
+                Constant::Struct { values, .. } => {
+                    let priority = match values.first() {
+                        Some(Constant::Int { value, .. }) => *value as u32,
+                        _ => return None,
+                    };
+                    let function = referenced_global(values.get(1)?)?.clone();
+                    // older bitcode has only the first two fields
+                    let associated_data = values.get(2).and_then(referenced_global).cloned();
+                    Some(CtorEntry { priority, function, associated_data })
+                },
+                _ => None,
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.priority); // this is a stable sort, so entries with equal priority remain in their original order
+        entries
+    }
+
+    /// Get the elements of the array initializer of the special global
+    /// variable with the given name (e.g., `llvm.used`), or an empty slice if
+    /// there is no such global
+    fn special_global_elements(&self, name: &str) -> &[Constant] {
+        match self.get_global_var_by_name(&Name::from(name)).and_then(|var| var.initializer.as_ref()) {
+            Some(Constant::Array { elements, .. }) => elements,
+            _ => &[],
+        }
+    }
+
+    /// Iterate over all of the call sites in the `Module`: that is, all of the
+    /// `Call` instructions and `Invoke` terminators in all of its `Function`s.
+    pub fn call_sites(&self) -> impl Iterator<Item = CallSite<'_>> {
+        self.functions.iter().flat_map(move |func| {
+            func.basic_blocks.iter().flat_map(move |bb| {
+                let calls = bb.instrs.iter().filter_map(|inst| match inst {
+                    Instruction::Call(call) => Some(Either::Left(call)),
+                    _ => None,
+                });
+                let invoke = match &bb.term {
+                    Terminator::Invoke(invoke) => Some(Either::Right(invoke)),
+                    _ => None,
+                };
+                calls.chain(invoke).map(move |call| {
+                    let function = match call {
+                        Either::Left(call) => &call.function,
+                        Either::Right(invoke) => &invoke.function,
+                    };
+                    CallSite {
+                        caller: func,
+                        call,
+                        callee: match function {
+                            Either::Right(Operand::ConstantOperand(c)) => {
+                                referenced_global(c).and_then(|name| self.ultimate_target(name))
+                            },
+                            _ => None,
+                        },
+                    }
+                })
+            })
+        })
+    }
+
+    /// Get the names of the functions whose address is taken: that is, which
+    /// are used as a value somewhere in the `Module` (e.g., stored to memory,
+    /// passed as an argument, or referenced in a global initializer), other
+    /// than as the callee of a direct call.
+    ///
+    /// This may include functions which are only declared in the `Module`.
+    /// References through a `GlobalAlias` are attributed to the aliased
+    /// function.
+    pub fn address_taken_functions(&self) -> HashSet<String> {
+        let mut referenced: HashSet<String> = HashSet::new();
+        for var in &self.global_vars {
+            if let Some(initializer) = &var.initializer {
+                add_function_references(initializer, &mut referenced);
+            }
+        }
+        for alias in &self.global_aliases {
+            add_function_references(&alias.aliasee, &mut referenced);
+        }
+        for func in &self.functions {
+            for c in func.personality_function.iter().chain(&func.prefix_data).chain(&func.prologue_data) {
+                add_function_references(c, &mut referenced);
+            }
+            for bb in &func.basic_blocks {
+                for inst in &bb.instrs {
+                    let operands = match inst {
+                        // the callee of a call doesn't have its address taken
+                        Instruction::Call(call) => call
+                            .arguments
+                            .iter()
+                            .map(|(op, _)| op)
+                            .chain(call.operand_bundles.iter().flat_map(|bundle| bundle.operands.iter()))
+                            .collect(),
+                        _ => inst.operands(),
+                    };
+                    for op in operands {
+                        if let Operand::ConstantOperand(c) = op {
+                            add_function_references(c, &mut referenced);
+                        }
+                    }
+                }
+                let operands = match &bb.term {
+                    Terminator::Invoke(invoke) => invoke
+                        .arguments
+                        .iter()
+                        .map(|(op, _)| op)
+                        .chain(invoke.operand_bundles.iter().flat_map(|bundle| bundle.operands.iter()))
+                        .collect(),
+                    Terminator::CallBr(callbr) => callbr.arguments.iter().map(|(op, _)| op).collect(),
+                    term => term.operands(),
+                };
+                for op in operands {
+                    if let Operand::ConstantOperand(c) = op {
+                        add_function_references(c, &mut referenced);
+                    }
+                }
+            }
+        }
+        // the aliasee of each alias was scanned above, so any aliased function is already included
+        referenced.retain(|name| self.get_global_alias_by_name(&Name::from(name.as_str())).is_none());
+        referenced
+    }
+
+    /// Get the `Function`s defined in this `Module` whose address is taken
+    /// (see [`address_taken_functions()`](struct.Module.html#method.address_taken_functions))
+    /// and whose type matches the type of the function pointer called by the
+    /// given `Call`, and which therefore may be called by it.
+    ///
+    /// Returns an empty `Vec` if the `Call` is to inline assembly.
+    pub fn candidates_for_indirect_call(&self, call: &Call) -> Vec<&Function> {
+        let func_ty = match &call.function {
+            Either::Right(op) => match op.get_type() {
+                Type::PointerType { pointee_type, .. } => *pointee_type,
+                _ => return vec![],
+            },
+            Either::Left(_) => return vec![],
+        };
+        let address_taken = self.address_taken_functions();
+        self.functions
+            .iter()
+            .filter(|func| address_taken.contains(&func.name) && func.get_type() == func_ty)
+            .collect()
+    }
+
+    /// Find any symbol name which is defined more than once in the `Module`,
+    /// e.g., by both a `Function` and a `GlobalVariable`. For each such name,
+    /// get the kinds of all the symbols with that name, in the order they
+    /// appear in the `Module`.
+    ///
+    /// LLVM itself never produces a `Module` like this, but one may arise from
+    /// hand-construction or from merging `Module`s.
+    pub fn find_duplicate_symbols(&self) -> Vec<(String, Vec<SymbolKind>)> {
+        let functions = self.functions.iter().map(|func| {
+            let kind = if func.basic_blocks.is_empty() {
+                SymbolKind::Declaration
+            } else {
+                SymbolKind::Function
+            };
+            (func.name.clone(), kind)
+        });
+        let global_vars = self
+            .global_vars
+            .iter()
+            .map(|var| (symbol_name(&var.name), SymbolKind::GlobalVariable));
+        let global_aliases = self
+            .global_aliases
+            .iter()
+            .map(|alias| (symbol_name(&alias.name), SymbolKind::GlobalAlias));
+
+        let mut symbols: Vec<(String, Vec<SymbolKind>)> = vec![];
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        for (name, kind) in functions.chain(global_vars).chain(global_aliases) {
+            match indices.get(&name) {
+                Some(&i) => symbols[i].1.push(kind),
+                None => {
+                    indices.insert(name.clone(), symbols.len());
+                    symbols.push((name, vec![kind]));
+                },
+            }
+        }
+        symbols.retain(|(_, kinds)| kinds.len() > 1);
+        symbols
+    }
+
     /// Parse the LLVM bitcode (.bc) file at the given path to create a `Module`
     pub fn from_bc_path(path: impl AsRef<Path>) -> Result<Self, String> {
-        // implementation here inspired by the `inkwell` crate's `Module::parse_bitcode_from_path`
-        use std::ffi::{CStr, CString};
-        use std::mem;
+        Self::from_bc_path_timed(path).map(|(module, _)| module)
+    }
 
-        let path = CString::new(
-            path.as_ref()
-                .to_str()
-                .expect("Did not find a valid Unicode path string"),
-        )
-        .expect("Failed to convert to CString");
-        debug!("Creating a Module from path {:?}", path);
-
-        let memory_buffer = unsafe {
-            let mut memory_buffer = std::ptr::null_mut();
-            let mut err_string = std::mem::zeroed();
-            let return_code = LLVMCreateMemoryBufferWithContentsOfFile(
-                path.as_ptr() as *const _,
-                &mut memory_buffer,
-                &mut err_string,
-            );
-            if return_code != 0 {
-                return Err(CStr::from_ptr(err_string)
-                    .to_str()
-                    .expect("Failed to convert CStr")
-                    .to_owned());
-            }
-            memory_buffer
-        };
-        debug!("Created a MemoryBuffer");
+    /// Write the `Module` to `writer` in a compact binary format, which
+    /// [`from_cache()`](struct.Module.html#method.from_cache) reads back much
+    /// faster than the `Module` can be parsed from bitcode.
+    ///
+    /// The format is specific to the version of `llvm-ir` which wrote it;
+    /// `from_cache()` refuses to read caches written by other versions.
+    #[cfg(feature = "bincode")]
+    pub fn to_cache(&self, mut writer: impl Write) -> Result<(), String> {
+        bincode::serialize_into(&mut writer, CACHE_VERSION).map_err(|e| e.to_string())?;
+        bincode::serialize_into(&mut writer, self).map_err(|e| e.to_string())
+    }
+
+    /// Read a `Module` written by [`to_cache()`](struct.Module.html#method.to_cache)
+    #[cfg(feature = "bincode")]
+    pub fn from_cache(mut reader: impl Read) -> Result<Self, String> {
+        let version: String = bincode::deserialize_from(&mut reader).map_err(|e| e.to_string())?;
+        if version != CACHE_VERSION {
+            return Err(format!("Cache was written by llvm-ir {}, not {}", version, CACHE_VERSION));
+        }
+        bincode::deserialize_from(&mut reader).map_err(|e| e.to_string())
+    }
+
+    /// Parse the LLVM assembly (textual IR, .ll) file at the given path into
+    /// a `Module`
+    pub fn from_ll_path(path: impl AsRef<Path>) -> Result<Self, String> {
+        let memory_buffer = memory_buffer_from_path(path.as_ref())?;
+        let context = Context::new();
+        let module = parse_ir(&context, memory_buffer)?;
+        Self::from_llvm_ref(module, &mut ParseTimings::default())
+    }
+
+    /// Like [`from_bc_path()`](struct.Module.html#method.from_bc_path), but
+    /// additionally return how long each phase of parsing took
+    pub fn from_bc_path_timed(path: impl AsRef<Path>) -> Result<(Self, ParseTimings), String> {
+        let start = Instant::now();
+        let memory_buffer = memory_buffer_from_path(path.as_ref())?;
+        Self::from_memory_buffer_timed(memory_buffer, start)
+    }
+
+    /// Like [`from_bc_path()`](struct.Module.html#method.from_bc_path), but
+    /// additionally return a `ValueNumberMap` giving the number `llvm-dis`
+    /// would print for each unnamed value.
+    pub fn from_bc_path_with_numbering(path: impl AsRef<Path>) -> Result<(Self, ValueNumberMap), String> {
+        let module = Self::from_bc_path(path)?;
+        let numbering = ValueNumberMap::new(&module);
+        Ok((module, numbering))
+    }
+
+    /// Like [`from_bc_path()`](struct.Module.html#method.from_bc_path), but
+    /// additionally report each part of the bitcode which couldn't be fully
+    /// decoded (e.g., comdat names), and where it was found.
+    pub fn from_bc_path_verbose(path: impl AsRef<Path>) -> Result<(Self, Vec<ParseWarning>), String> {
+        let module = Self::from_bc_path(path)?;
+        let warnings = collect_warnings(&module);
+        for warning in &warnings {
+            debug!("Parse warning: {}", warning);
+        }
+        Ok((module, warnings))
+    }
 
-        let context = crate::from_llvm::Context::new();
+    /// Parse each LLVM bitcode member of the Unix `ar` archive (e.g., static
+    /// library) at the given path, returning the name and `Module` of each.
+    /// Members which are not bitcode are skipped.
+    ///
+    /// Thin archives are supported; their members are read from the paths
+    /// they reference, relative to the archive.
+    pub fn from_archive_path(path: impl AsRef<Path>) -> Result<Vec<(String, Self)>, String> {
+        debug!("Reading archive {:?}", path.as_ref());
+        crate::archive::read_archive(path.as_ref())?
+            .into_iter()
+            .filter(|member| crate::archive::is_bitcode(&member.data))
+            .map(|member| {
+                debug!("Creating a Module from archive member {:?}", member.name);
+                let memory_buffer = unsafe {
+                    LLVMCreateMemoryBufferWithMemoryRangeCopy(
+                        member.data.as_ptr() as *const _,
+                        member.data.len(),
+                        b"\0".as_ptr() as *const _,
+                    )
+                };
+                let module = Self::from_memory_buffer(memory_buffer)
+                    .map_err(|e| format!("Archive member {}: {}", member.name, e))?;
+                Ok((member.name, module))
+            })
+            .collect()
+    }
+
+    /// Parse each LLVM bitcode (.bc) and LLVM assembly (.ll) file in the
+    /// given directory (and, if `recursive`, in its subdirectories), returning
+    /// the path of each file along with the result of parsing it. A file
+    /// which fails to parse doesn't prevent the others from being parsed.
+    ///
+    /// Files are returned in order of their paths. If a directory can't be
+    /// read, its path is returned with the error.
+    pub fn from_directory(dir: impl AsRef<Path>, recursive: bool) -> Vec<(PathBuf, Result<Self, String>)> {
+        let mut paths = vec![];
+        let mut results = vec![];
+        collect_ir_paths(dir.as_ref(), recursive, &mut paths, &mut results);
+        for path in paths {
+            debug!("Creating a Module from {:?}", path);
+            let module = if path.extension() == Some("ll".as_ref()) {
+                Self::from_ll_path(&path)
+            } else {
+                Self::from_bc_path(&path)
+            };
+            let module = module.map_err(|e| format!("{}: {}", path.display(), e));
+            results.push((path, module));
+        }
+        results.sort_by(|(a, _), (b, _)| a.cmp(b));
+        results
+    }
 
-        use llvm_sys::bit_reader::LLVMParseBitcodeInContext2;
-        let module = unsafe {
-            let mut module: mem::MaybeUninit<LLVMModuleRef> = mem::MaybeUninit::uninit();
-            let return_code =
-                LLVMParseBitcodeInContext2(context.ctx, memory_buffer, module.as_mut_ptr());
-            LLVMDisposeMemoryBuffer(memory_buffer);
-            if return_code != 0 {
-                return Err("Failed to parse bitcode".to_string());
+    /// Parse the LLVM bitcode (.bc) file at the given path, passing each
+    /// defined `Function` to `f` as soon as it is decoded, without ever
+    /// building a `Module`. Each `Function` is dropped once `f` is done with
+    /// it (unless `f` keeps it), so the decoded functions are never all in
+    /// memory at once. Global variables, aliases, and module-level metadata
+    /// are not decoded.
+    ///
+    /// The weak references in `NamedStructType`s are only valid until
+    /// `for_each_function_from_bc_path()` returns.
+    ///
+    /// This is not lazy parsing: LLVM itself still parses the entire bitcode
+    /// file up front. (LLVM can load bitcode lazily, with
+    /// `LLVMGetBitcodeModuleInContext2()`, but the LLVM 9 C API has no way to
+    /// materialize the body of one function at a time.) This only bounds the
+    /// memory used by this crate's representation, which is typically much
+    /// larger than LLVM's.
+    pub fn for_each_function_from_bc_path(path: impl AsRef<Path>, mut f: impl FnMut(Function)) -> Result<(), String> {
+        let memory_buffer = memory_buffer_from_path(path.as_ref())?;
+        let context = Context::new();
+        let module = parse_bitcode(&context, memory_buffer)?;
+        let gnmap = global_name_map(module);
+        let mut tynamemap = TyNameMap::new();
+        let mut ditypemap = DITypeMap::new();
+        take_decode_error();
+        for func in get_defined_functions(module) {
+            let func = Function::from_llvm_ref(func, &gnmap, &mut tynamemap, &mut ditypemap);
+            if let Some(error) = take_decode_error() {
+                return Err(error);
             }
-            module.assume_init()
+            f(func);
+        }
+        Ok(())
+    }
+
+    /// Parse a `Module` from the bitcode in the given `LLVMMemoryBufferRef`,
+    /// disposing of the buffer
+    fn from_memory_buffer(memory_buffer: LLVMMemoryBufferRef) -> Result<Self, String> {
+        Self::from_memory_buffer_timed(memory_buffer, Instant::now()).map(|(module, _)| module)
+    }
+
+    /// `start` is the time at which parsing began (e.g., before reading the
+    /// file into the `memory_buffer`), for the purposes of `ParseTimings`
+    fn from_memory_buffer_timed(memory_buffer: LLVMMemoryBufferRef, start: Instant) -> Result<(Self, ParseTimings), String> {
+        let context = Context::new();
+        let module = parse_bitcode(&context, memory_buffer)?;
+        let mut timings = ParseTimings {
+            bitcode: start.elapsed(),
+            ..ParseTimings::default()
         };
-        debug!("Parsed bitcode to llvm_sys module");
-        Ok(Self::from_llvm_ref(module))
+        let module = Self::from_llvm_ref(module, &mut timings)?;
+        timings.total = start.elapsed();
+        Ok((module, timings))
     }
 }
 
-/// See [LLVM 9 docs on Global Variables](https://releases.llvm.org/9.0.0/docs/LangRef.html#global-variables)
+/// How long each phase of parsing a `Module` took, as produced by
+/// [`Module::from_bc_path_timed()`](struct.Module.html#method.from_bc_path_timed).
+///
+/// `total` includes all of the phases, plus any time not attributed to one of
+/// them, so it is slightly more than their sum.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct ParseTimings {
+    /// Reading the bitcode and parsing it with LLVM
+    pub bitcode: Duration,
+    /// The first pass over the module, which assigns `Name`s to all global objects
+    pub name_mapping: Duration,
+    /// Decoding `Function`s, including the debug information attached to them
+    pub functions: Duration,
+    /// Decoding `GlobalVariable`s and `GlobalAlias`es
+    pub globals: Duration,
+    /// Decoding module-level metadata
+    pub metadata: Duration,
+    pub total: Duration,
+}
+
+/// The location of an instruction in a `Module`: the `index`th instruction of
+/// the named block in the named function. An `index` equal to the number of
+/// (non-terminator) instructions in the block refers to the block's
+/// terminator.
+///
+/// Unlike a reference, an `InstructionRef` can be stored without borrowing the
+/// `Module`. However, it is only meaningful as long as the `Module` isn't
+/// mutated: adding, removing, or reordering instructions, blocks, or functions
+/// (or renaming them) may leave it referring to a different instruction, or
+/// none at all.
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct InstructionRef {
+    /// The name of the `Function` containing the instruction
+    pub function: String,
+    /// The name of the `BasicBlock` containing the instruction
+    pub block: Name,
+    /// The index of the instruction within the `BasicBlock`
+    pub index: usize,
+}
+
+/// Error returned by [`Module::rename_symbol()`](struct.Module.html#method.rename_symbol)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum RenameError {
+    /// There is no global with the given name to rename
+    NotFound(String),
+    /// There is already a global with the given new name
+    AlreadyExists(String),
+}
+
+impl std::fmt::Display for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RenameError::NotFound(name) => write!(f, "No global named {:?}", name),
+            RenameError::AlreadyExists(name) => write!(f, "A global named {:?} already exists", name),
+        }
+    }
+}
+
+/// Error returned by [`Module::replace_function_body()`](struct.Module.html#method.replace_function_body)
 #[derive(PartialEq, Clone, Debug)]
+pub enum ReplaceError {
+    /// There is no function defined with the given name
+    NotFound(String),
+    /// The new body has no blocks
+    NoBlocks,
+    /// The function with the new body failed verification
+    Invalid(Vec<VerifyError>),
+}
+
+impl std::fmt::Display for ReplaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReplaceError::NotFound(name) => write!(f, "No function named {:?}", name),
+            ReplaceError::NoBlocks => write!(f, "The new function body has no blocks"),
+            ReplaceError::Invalid(errors) => {
+                write!(f, "The new function body is invalid: ")?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for ReplaceError {}
+
+/// A call site in a `Module`, as produced by
+/// [`Module::call_sites()`](struct.Module.html#method.call_sites)
+#[derive(PartialEq, Clone, Debug)]
+pub struct CallSite<'m> {
+    /// The `Function` containing the call site
+    pub caller: &'m Function,
+    /// The `Call` instruction or `Invoke` terminator itself
+    pub call: Either<&'m Call, &'m Invoke>,
+    /// For direct calls, the name of the called global, after following any
+    /// `GlobalAlias`es. `None` for indirect calls and calls to inline
+    /// assembly, and for direct calls whose chain of aliases can't be
+    /// followed (see [`Module::ultimate_target()`](struct.Module.html#method.ultimate_target)).
+    pub callee: Option<&'m Name>,
+}
+
+impl<'m> CallSite<'m> {
+    /// Is this a direct call (i.e., a call to a statically known global)?
+    ///
+    /// This depends only on the called operand being a (possibly casted)
+    /// reference to a global, so a call through a cyclic chain of aliases is
+    /// direct even though its `callee` is `None`.
+    pub fn is_direct(&self) -> bool {
+        let function = match self.call {
+            Either::Left(call) => &call.function,
+            Either::Right(invoke) => &invoke.function,
+        };
+        match function {
+            Either::Right(Operand::ConstantOperand(c)) => referenced_global(c).is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// Map from the `Name::Number`s which llvm-ir assigns to unnamed values, to
+/// the numbers which `llvm-dis` would print for those values, as produced by
+/// [`Module::from_bc_path_with_numbering()`](struct.Module.html#method.from_bc_path_with_numbering).
+///
+/// These usually agree, but not always: for instance, llvm-ir gives a `Name`
+/// to the (unused) result of every `Invoke`, even one whose result is void,
+/// while LLVM only numbers values with non-void type.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct ValueNumberMap {
+    numbers: HashMap<(String, Name), usize>,
+}
+
+impl ValueNumberMap {
+    /// Get the number `llvm-dis` would print for the value with the given
+    /// `Name` in the function with the given name, or `None` if `llvm-dis`
+    /// would not print a number for it (e.g., it has a string name, or is void)
+    pub fn get(&self, func_name: &str, name: &Name) -> Option<usize> {
+        self.numbers.get(&(func_name.to_owned(), name.clone())).copied()
+    }
+
+    fn new(module: &Module) -> Self {
+        let mut numbers = HashMap::new();
+        for func in &module.functions {
+            // parameters, blocks, and non-void instruction results share a
+            // single numbering, in order
+            let params = func.parameters.iter().map(|p| &p.name);
+            let bodies = func.basic_blocks.iter().flat_map(|bb| {
+                let instrs = bb
+                    .instrs
+                    .iter()
+                    .filter(|inst| inst.get_type() != Type::VoidType)
+                    .filter_map(|inst| inst.try_get_result());
+                let term = match &bb.term {
+                    Terminator::Invoke(invoke) if invoke.get_type() != Type::VoidType => Some(&invoke.result),
+                    Terminator::CallBr(callbr) if callbr.get_type() != Type::VoidType => Some(&callbr.result),
+                    Terminator::CatchSwitch(catchswitch) => Some(&catchswitch.result),
+                    _ => None,
+                };
+                std::iter::once(&bb.name).chain(instrs).chain(term)
+            });
+            let unnamed = params.chain(bodies).filter(|name| name.as_number().is_some());
+            for (number, name) in unnamed.enumerate() {
+                numbers.insert((func.name.clone(), name.clone()), number);
+            }
+        }
+        Self { numbers }
+    }
+}
+
+/// An entry in `llvm.global_ctors` or `llvm.global_dtors`, as produced by
+/// [`Module::global_ctors()`](struct.Module.html#method.global_ctors) or
+/// [`Module::global_dtors()`](struct.Module.html#method.global_dtors)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CtorEntry {
+    /// Entries with lower priority values are called first
+    pub priority: u32,
+    /// The constructor or destructor function
+    pub function: Name,
+    /// The global whose initialization this entry is associated with, if any
+    pub associated_data: Option<Name>,
+}
+
+fn map_operand_types(operand: &mut Operand, map: &mut impl FnMut(&mut Type)) {
+    match operand {
+        Operand::LocalOperand { ty, .. } => map(ty),
+        Operand::ConstantOperand(c) => map_constant_types(c, map),
+        Operand::MetadataOperand => {},
+    }
+}
+
+fn map_constant_types(c: &mut Constant, map: &mut impl FnMut(&mut Type)) {
+    if let Constant::Int { bits, value } = c {
+        let mut ty = Type::IntegerType { bits: *bits };
+        map(&mut ty);
+        if let Type::IntegerType { bits: new_bits } = ty {
+            *bits = new_bits;
+            if new_bits < 64 {
+                *value &= (1 << new_bits) - 1;
+            }
+        }
+    }
+    c.types_mut().into_iter().for_each(&mut *map);
+    for operand in c.operands_mut() {
+        map_constant_types(operand, map);
+    }
+}
+
+/// The kind of a symbol reported by
+/// [`Module::find_duplicate_symbols()`](struct.Module.html#method.find_duplicate_symbols)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SymbolKind {
+    /// A `Function` with a body
+    Function,
+    /// A `Function` without a body
+    Declaration,
+    GlobalVariable,
+    GlobalAlias,
+}
+
+/// The name of a global as it would appear in LLVM IR (without the `@`)
+fn symbol_name(name: &Name) -> String {
+    match name {
+        Name::Name(name) => name.to_string(),
+        Name::Interned(name) => name.to_string(),
+        Name::Number(n) => n.to_string(),
+    }
+}
+
+/// Add `ty` to `types`, along with all of the types it is built from (not
+/// including the bodies of named structs)
+fn add_type(types: &mut HashSet<Type>, ty: &Type) {
+    if types.contains(ty) {
+        return;
+    }
+    types.insert(ty.clone());
+    match ty {
+        Type::PointerType { pointee_type, .. } => add_type(types, pointee_type),
+        Type::FuncType { result_type, param_types, .. } => {
+            add_type(types, result_type);
+            for param_type in param_types {
+                add_type(types, param_type);
+            }
+        },
+        Type::VectorType { element_type, .. } | Type::ArrayType { element_type, .. } => add_type(types, element_type),
+        Type::StructType { element_types, .. } => {
+            for element_type in element_types {
+                add_type(types, element_type);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Add `c`, and all of the `Constant`s it is built from, to `constants` (if
+/// not already present)
+fn add_constant(constants: &mut Vec<Constant>, c: &Constant) {
+    if constants.contains(c) {
+        return;
+    }
+    constants.push(c.clone());
+    for operand in c.operands() {
+        add_constant(constants, operand);
+    }
+}
+
+/// Add the names of the named struct types referenced anywhere in `ty` to
+/// `names`, without looking into the bodies of those named struct types
+fn add_struct_names(names: &mut HashSet<String>, ty: &Type) {
+    match ty {
+        Type::NamedStructType { name, .. } => {
+            names.insert(name.clone());
+        },
+        Type::PointerType { pointee_type, .. } => add_struct_names(names, pointee_type),
+        Type::FuncType { result_type, param_types, .. } => {
+            add_struct_names(names, result_type);
+            for param_type in param_types {
+                add_struct_names(names, param_type);
+            }
+        },
+        Type::VectorType { element_type, .. } | Type::ArrayType { element_type, .. } => add_struct_names(names, element_type),
+        Type::StructType { element_types, .. } => {
+            for element_type in element_types {
+                add_struct_names(names, element_type);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Add the names of all of the functions (and aliases of functions)
+/// referenced anywhere in `c` to `names`
+fn add_function_references(c: &Constant, names: &mut HashSet<String>) {
+    if let Constant::GlobalReference { name, ty: Type::FuncType { .. } } = c {
+        if let Some(name) = name.as_str() {
+            names.insert(name.to_owned());
+        }
+    }
+    for operand in c.operands() {
+        add_function_references(operand, names);
+    }
+}
+
+/// Call `f` on each `Name` in the `Operand`: its own name if it is a local,
+/// or the names of the globals it references if it is a constant
+fn visit_operand_names(operand: &mut Operand, f: &mut impl FnMut(&mut Name)) {
+    match operand {
+        Operand::LocalOperand { name, .. } => f(name),
+        Operand::ConstantOperand(c) => visit_constant_names(c, f),
+        Operand::MetadataOperand => {},
+    }
+}
+
+/// Call `f` on the `Name` of each global referenced anywhere in `c`
+fn visit_constant_names(c: &mut Constant, f: &mut impl FnMut(&mut Name)) {
+    if let Constant::GlobalReference { name, .. } = c {
+        f(name);
+    }
+    for operand in c.operands_mut() {
+        visit_constant_names(operand, f);
+    }
+}
+
+/// Does `c` reference the global with the given `Name` anywhere?
+fn constant_references(c: &Constant, name: &Name) -> bool {
+    match c {
+        Constant::GlobalReference { name: n, .. } => n == name,
+        _ => c.operands().into_iter().any(|operand| constant_references(operand, name)),
+    }
+}
+
+/// Replace any reference to a global in `replacements` (found anywhere in `c`)
+/// with the corresponding replacement `Constant`
+fn resolve_aliases_in_constant(c: &mut Constant, replacements: &HashMap<Name, Constant>) {
+    if let Constant::GlobalReference { name, .. } = c {
+        if let Some(replacement) = replacements.get(name) {
+            *c = replacement.clone();
+        }
+        return;
+    }
+    for operand in c.operands_mut() {
+        resolve_aliases_in_constant(operand, replacements);
+    }
+}
+
+/// If the `Constant` is a reference to a global, possibly wrapped in pointer
+/// casts, get the `Name` of the referenced global
+fn referenced_global(c: &Constant) -> Option<&Name> {
+    match c {
+        Constant::GlobalReference { name, .. } => Some(name),
+        Constant::BitCast(bc) => referenced_global(&bc.operand),
+        Constant::AddrSpaceCast(ac) => referenced_global(&ac.operand),
+        _ => None,
+    }
+}
+
+/// See [LLVM 9 docs on Global Variables](https://releases.llvm.org/9.0.0/docs/LangRef.html#global-variables)
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlobalVariable {
     pub name: Name,
     pub linkage: Linkage,
     pub visibility: Visibility,
     pub is_constant: bool,
+    /// Whether the global may be initialized or modified by something outside
+    /// the program (e.g., the runtime or a loader) before the program starts,
+    /// so its initializer can't be relied upon.
+    /// See [LLVM 9 docs on Global Variables](https://releases.llvm.org/9.0.0/docs/LangRef.html#global-variables)
+    pub externally_initialized: bool,
     pub ty: Type,
     pub addr_space: AddrSpace,
     pub dll_storage_class: DLLStorageClass,
@@ -116,6 +1687,10 @@ pub struct GlobalVariable {
     pub comdat: Option<Comdat>, // llvm-hs-pure has Option<String> for some reason
     pub alignment: u32,
     pub debugloc: Option<DebugLoc>,
+    /// The source-level description of the variable (if any), decoded from
+    /// its `!dbg` attachment.
+    /// See [`debug_info()`](struct.GlobalVariable.html#method.debug_info).
+    pub debug_variable: Option<MetadataNodeID>,
     // --TODO not yet implemented-- pub metadata: Vec<(String, MetadataRef<MetadataNode>)>,
 }
 
@@ -125,6 +1700,47 @@ impl Typed for GlobalVariable {
     }
 }
 
+impl GlobalVariable {
+    /// Get the element of the `initializer` at the given path of indices,
+    /// descending one level into an `Array`, `Struct`, or `Vector` constant
+    /// per index (like the indices of an `extractvalue`). An empty path gives
+    /// the whole initializer.
+    ///
+    /// Returns `None` if the global has no initializer, if an index is out of
+    /// range, or if the path descends into something other than those
+    /// aggregates. Note that this includes `Constant::AggregateZero`, whose
+    /// elements aren't individually represented.
+    pub fn initializer_element(&self, indices: &[usize]) -> Option<&Constant> {
+        let mut cur = self.initializer.as_ref()?;
+        for &index in indices {
+            cur = match cur {
+                Constant::Array { elements, .. } => elements.get(index)?,
+                Constant::Struct { values, .. } => values.get(index)?,
+                Constant::Vector(elements) => elements.get(index)?,
+                _ => return None,
+            };
+        }
+        Some(cur)
+    }
+
+    /// Get the source-level description of the variable (its name in the
+    /// source, file, line, etc), if it has debug info.
+    /// `module` must be the `Module` containing this `GlobalVariable`.
+    pub fn debug_info<'m>(&self, module: &'m Module) -> Option<&'m DIGlobalVariable> {
+        module.debug_global_variables.get(self.debug_variable?)
+    }
+
+    /// A copy of the `GlobalVariable` with its `DebugLoc` and debug
+    /// information removed
+    pub(crate) fn without_debug_info(&self) -> Self {
+        Self {
+            debugloc: None,
+            debug_variable: None,
+            ..self.clone()
+        }
+    }
+}
+
 impl HasDebugLoc for GlobalVariable {
     fn get_debug_loc(&self) -> &Option<DebugLoc> {
         &self.debugloc
@@ -132,7 +1748,8 @@ impl HasDebugLoc for GlobalVariable {
 }
 
 /// See [LLVM 9 docs on Global Aliases](https://releases.llvm.org/9.0.0/docs/LangRef.html#aliases)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlobalAlias {
     pub name: Name,
     pub aliasee: Constant,
@@ -151,14 +1768,16 @@ impl Typed for GlobalAlias {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnnamedAddr {
     Local,
     Global,
 }
 
 /// See [LLVM 9 docs on Linkage Types](https://releases.llvm.org/9.0.0/docs/LangRef.html#linkage)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Linkage {
     Private,
     Internal,
@@ -180,7 +1799,8 @@ pub enum Linkage {
 }
 
 /// See [LLVM 9 docs on Visibility Styles](https://releases.llvm.org/9.0.0/docs/LangRef.html#visibility-styles)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Visibility {
     Default,
     Hidden,
@@ -188,7 +1808,8 @@ pub enum Visibility {
 }
 
 /// See [LLVM 9 docs on DLL Storage Classes](https://releases.llvm.org/9.0.0/docs/LangRef.html#dllstorageclass)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DLLStorageClass {
     Default,
     Import,
@@ -196,7 +1817,8 @@ pub enum DLLStorageClass {
 }
 
 /// See [LLVM 9 docs on Thread Local Storage Models](https://releases.llvm.org/9.0.0/docs/LangRef.html#thread-local-storage-models)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ThreadLocalMode {
     NotThreadLocal,
     GeneralDynamic,
@@ -225,13 +1847,15 @@ pub struct NamedMetadata {
 */
 
 /// See [LLVM 9 docs on Comdats](https://releases.llvm.org/9.0.0/docs/LangRef.html#langref-comdats)
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Comdat {
     pub name: String,
     pub selection_kind: SelectionKind,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SelectionKind {
     Any,
     ExactMatch,
@@ -240,62 +1864,25 @@ pub enum SelectionKind {
     SameSize,
 }
 
-/* llvm-hs parses the data_layout into basically this structure
-
-#[derive(Clone, Debug)]
-pub struct DataLayout {
-    pub endianness: Endianness,
-    pub mangling: Option<Mangling>,
-    pub stack_alignment: Option<u32>,
-    pub pointer_layouts: HashMap<AddrSpace, (u32, AlignmentInfo)>,
-    pub type_layouts: HashMap<(AlignType, u32), AlignmentInfo>,
-    pub aggregate_layout: AlignmentInfo,
-    pub native_sizes: Option<HashSet<u32>>,
-}
-
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub enum Endianness {
-    LittleEndian,
-    BigEndian,
-}
-
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub enum Mangling {
-    ELF,
-    MIPS,
-    MachO,
-    WindowsCOFF,
-}
-
-#[derive(PartialEq, Eq, Clone, Debug)]
-pub struct AlignmentInfo {
-    pub abi_alignment: u32,
-    pub preferred_alignment: u32,
-}
-
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
-pub enum AlignType {
-    Integer,
-    Vector,
-    Float,
-}
-
-*/
-
 // ********* //
 // from_llvm //
 // ********* //
 
+use crate::context::Context;
 use crate::constant::GlobalNameMap;
 use crate::from_llvm::*;
+use crate::debuginfo::DITypeMap;
 use crate::types::TyNameMap;
 use llvm_sys::{LLVMDLLStorageClass, LLVMLinkage, LLVMThreadLocalMode, LLVMUnnamedAddr, LLVMVisibility};
 use llvm_sys::comdat::*;
 
 impl Module {
-    pub(crate) fn from_llvm_ref(module: LLVMModuleRef) -> Self {
+    /// Records how long each phase takes in `timings`
+    /// Fails if something in the module couldn't be decoded (see
+    /// `record_decode_error()`)
+    pub(crate) fn from_llvm_ref(module: LLVMModuleRef, timings: &mut ParseTimings) -> Result<Self, String> {
         debug!("Creating a Module from an LLVMModuleRef");
-        let mut global_ctr = 0; // this ctr is used to number global objects that aren't named
+        take_decode_error();
 
         // Modules require two passes over their contents.
         // First we make a pass just to map global objects -- in particular, Functions,
@@ -304,51 +1891,240 @@ impl Module {
         // This is necessary because these structures may reference each other in a
         //   circular fashion, and we need to be able to fill in the Name of the
         //   referenced object from having only its `LLVMValueRef`.
-        let gnmap: GlobalNameMap = get_defined_functions(module)
-            .chain(get_declared_functions(module))
-            .chain(get_globals(module))
-            .chain(get_global_aliases(module))
-            .map(|g| {
-                (
-                    g,
-                    Name::name_or_num(unsafe { get_value_name(g) }, &mut global_ctr),
-                )
-            })
-            .collect();
-        global_ctr = 0; // reset the global_ctr; the second pass should number everything exactly the same though
+        let phase_start = Instant::now();
+        let gnmap = global_name_map(module);
+        timings.name_mapping = phase_start.elapsed();
 
         let mut tynamemap = TyNameMap::new();
+        let mut ditypemap = DITypeMap::new();
 
-        Self {
+        let phase_start = Instant::now();
+        let functions = get_defined_functions(module)
+            .map(|f| Function::from_llvm_ref(f, &gnmap, &mut tynamemap, &mut ditypemap))
+            .collect();
+        timings.functions = phase_start.elapsed();
+
+        let phase_start = Instant::now();
+        let mut global_ctr = 0; // the second pass should number everything exactly the same as the first
+        let global_vars = get_globals(module)
+            .map(|g| GlobalVariable::from_llvm_ref(g, &mut global_ctr, &gnmap, &mut tynamemap, &mut ditypemap))
+            .collect();
+        let global_aliases = get_global_aliases(module)
+            .map(|g| GlobalAlias::from_llvm_ref(g, &mut global_ctr, &gnmap, &mut tynamemap))
+            .collect();
+        timings.globals = phase_start.elapsed();
+
+        let phase_start = Instant::now();
+        let (debug_types, debug_scopes, debug_global_variables, debug_local_variables) = ditypemap.into_parts();
+        let metadata_kind_names = {
+            let ctx = unsafe { LLVMGetModuleContext(module) };
+            BUILTIN_METADATA_KINDS
+                .iter()
+                .map(|&name| {
+                    let id = unsafe {
+                        LLVMGetMDKindIDInContext(ctx, name.as_ptr() as *const _, name.len() as u32)
+                    };
+                    (id, name.to_owned())
+                })
+                .collect()
+        };
+        timings.metadata = phase_start.elapsed();
+
+        if let Some(error) = take_decode_error() {
+            return Err(error);
+        }
+
+        Ok(Self {
             name: unsafe { get_module_identifier(module) },
             source_file_name: unsafe { get_source_file_name(module) },
             data_layout: unsafe { get_data_layout_str(module) },
             target_triple: unsafe { get_target(module) },
-            functions: get_defined_functions(module)
-                .map(|f| Function::from_llvm_ref(f, &gnmap, &mut tynamemap))
-                .collect(),
-            global_vars: get_globals(module)
-                .map(|g| GlobalVariable::from_llvm_ref(g, &mut global_ctr, &gnmap, &mut tynamemap))
-                .collect(),
-            global_aliases: get_global_aliases(module)
-                .map(|g| GlobalAlias::from_llvm_ref(g, &mut global_ctr, &gnmap, &mut tynamemap))
-                .collect(),
+            functions,
+            global_vars,
+            global_aliases,
             // function_attribute_groups: unimplemented!("function_attribute_groups"),  // llvm-hs collects these in the decoder monad or something
             named_struct_types: tynamemap,
+            debug_types,
+            debug_scopes,
+            debug_global_variables,
+            debug_local_variables,
             inline_assembly: unsafe { get_module_inline_asm(module) },
+            metadata_kind_names,
             // metadata_nodes: unimplemented!("metadata_nodes"),
             // named_metadatas: unimplemented!("named_metadatas"),
             // comdats: unimplemented!("comdats"),  // I think llvm-hs also collects these along the way
+        })
+    }
+}
+
+/// Read the file at the given path into an `LLVMMemoryBufferRef`
+fn memory_buffer_from_path(path: &Path) -> Result<LLVMMemoryBufferRef, String> {
+    // implementation here inspired by the `inkwell` crate's `Module::parse_bitcode_from_path`
+    use std::ffi::{CStr, CString};
+
+    let path = CString::new(
+        path.to_str()
+            .expect("Did not find a valid Unicode path string"),
+    )
+    .expect("Failed to convert to CString");
+    debug!("Creating a Module from path {:?}", path);
+
+    let memory_buffer = unsafe {
+        let mut memory_buffer = std::ptr::null_mut();
+        let mut err_string = std::mem::zeroed();
+        let return_code = LLVMCreateMemoryBufferWithContentsOfFile(
+            path.as_ptr() as *const _,
+            &mut memory_buffer,
+            &mut err_string,
+        );
+        if return_code != 0 {
+            return Err(CStr::from_ptr(err_string)
+                .to_str()
+                .expect("Failed to convert CStr")
+                .to_owned());
+        }
+        memory_buffer
+    };
+    debug!("Created a MemoryBuffer");
+    Ok(memory_buffer)
+}
+
+/// Find the .bc and .ll files in `dir` (and, if `recursive`, its
+/// subdirectories), adding them to `paths`. Directories which can't be read
+/// are added to `errors`.
+fn collect_ir_paths(dir: &Path, recursive: bool, paths: &mut Vec<PathBuf>, errors: &mut Vec<(PathBuf, Result<Module, String>)>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push((dir.to_path_buf(), Err(format!("Failed to read directory {}: {}", dir.display(), e))));
+            return;
+        },
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                errors.push((dir.to_path_buf(), Err(format!("Failed to read directory {}: {}", dir.display(), e))));
+                continue;
+            },
+        };
+        if path.is_dir() {
+            if recursive {
+                collect_ir_paths(&path, recursive, paths, errors);
+            }
+        } else if path.extension() == Some("bc".as_ref()) || path.extension() == Some("ll".as_ref()) {
+            paths.push(path);
         }
     }
 }
 
+/// Parse the bitcode in the given `LLVMMemoryBufferRef` into an llvm-sys
+/// module owned by `context`, disposing of the buffer
+fn parse_bitcode(context: &Context, memory_buffer: LLVMMemoryBufferRef) -> Result<LLVMModuleRef, String> {
+    use llvm_sys::bit_reader::LLVMParseBitcodeInContext2;
+    use std::mem;
+
+    // LLVM reports an invalid bitcode signature by exiting the process, so
+    // check for it ourselves first
+    let is_bitcode = unsafe {
+        let start = LLVMGetBufferStart(memory_buffer) as *const u8;
+        let size = LLVMGetBufferSize(memory_buffer);
+        size >= 4 && crate::archive::is_bitcode(std::slice::from_raw_parts(start, 4))
+    };
+    if !is_bitcode {
+        unsafe { LLVMDisposeMemoryBuffer(memory_buffer) };
+        return Err("Invalid bitcode signature".to_string());
+    }
+
+    let module = unsafe {
+        let mut module: mem::MaybeUninit<LLVMModuleRef> = mem::MaybeUninit::uninit();
+        let return_code =
+            LLVMParseBitcodeInContext2(context.ctx, memory_buffer, module.as_mut_ptr());
+        LLVMDisposeMemoryBuffer(memory_buffer);
+        if return_code != 0 {
+            return Err("Failed to parse bitcode".to_string());
+        }
+        module.assume_init()
+    };
+    debug!("Parsed bitcode to llvm_sys module");
+    Ok(module)
+}
+
+/// Parse the LLVM assembly in the given `LLVMMemoryBufferRef` into an llvm-sys
+/// module owned by `context`, disposing of the buffer
+fn parse_ir(context: &Context, memory_buffer: LLVMMemoryBufferRef) -> Result<LLVMModuleRef, String> {
+    use llvm_sys::ir_reader::LLVMParseIRInContext;
+    use std::ffi::CStr;
+
+    let mut module = std::ptr::null_mut();
+    let mut err_string = std::ptr::null_mut();
+    // `LLVMParseIRInContext` takes ownership of the buffer
+    let return_code = unsafe { LLVMParseIRInContext(context.ctx, memory_buffer, &mut module, &mut err_string) };
+    if return_code != 0 {
+        let err = unsafe { CStr::from_ptr(err_string) }.to_string_lossy().into_owned();
+        unsafe { LLVMDisposeMessage(err_string) };
+        return Err(err);
+    }
+    debug!("Parsed IR to llvm_sys module");
+    Ok(module)
+}
+
+/// Map each global object (`Function`, `GlobalVariable`, or `GlobalAlias`) in
+/// the module to its `Name`
+fn global_name_map(module: LLVMModuleRef) -> GlobalNameMap {
+    let mut global_ctr = 0; // this ctr is used to number global objects that aren't named
+    get_defined_functions(module)
+        .chain(get_declared_functions(module))
+        .chain(get_globals(module))
+        .chain(get_global_aliases(module))
+        .map(|g| {
+            (
+                g,
+                Name::name_or_num(unsafe { get_value_name(g) }, &mut global_ctr),
+            )
+        })
+        .collect()
+}
+
+/// Names of the metadata kinds built into LLVM (`FixedMetadataKinds.def` in the LLVM source)
+const BUILTIN_METADATA_KINDS: &[&str] = &[
+    "dbg",
+    "tbaa",
+    "prof",
+    "fpmath",
+    "range",
+    "tbaa.struct",
+    "invariant.load",
+    "alias.scope",
+    "noalias",
+    "nontemporal",
+    "llvm.mem.parallel_loop_access",
+    "nonnull",
+    "dereferenceable",
+    "dereferenceable_or_null",
+    "make.implicit",
+    "unpredictable",
+    "invariant.group",
+    "align",
+    "llvm.loop",
+    "type",
+    "section_prefix",
+    "absolute_symbol",
+    "associated",
+    "callees",
+    "irr_loop",
+    "llvm.access.group",
+    "callback",
+    "llvm.preserve.access.index",
+];
+
 impl GlobalVariable {
     pub(crate) fn from_llvm_ref(
         global: LLVMValueRef,
         ctr: &mut usize,
         gnmap: &GlobalNameMap,
         tnmap: &mut TyNameMap,
+        ditypemap: &mut DITypeMap,
     ) -> Self {
         let ty = Type::from_llvm_ref(unsafe { LLVMTypeOf(global) }, tnmap);
         debug!("Processing a GlobalVariable with type {:?}", ty);
@@ -357,6 +2133,7 @@ impl GlobalVariable {
             linkage: Linkage::from_llvm(unsafe { LLVMGetLinkage(global) }),
             visibility: Visibility::from_llvm(unsafe { LLVMGetVisibility(global) }),
             is_constant: unsafe { LLVMIsGlobalConstant(global) } != 0,
+            externally_initialized: unsafe { LLVMIsExternallyInitialized(global) } != 0,
             ty: ty.clone(),
             addr_space: match ty {
                 Type::PointerType { addr_space, .. } => addr_space,
@@ -384,6 +2161,7 @@ impl GlobalVariable {
             },
             alignment: unsafe { LLVMGetAlignment(global) },
             debugloc: DebugLoc::from_llvm_no_col(global),
+            debug_variable: ditypemap.decode_global_variable(global),
             // metadata: unimplemented!("metadata"),
         }
     }
@@ -1,18 +1,31 @@
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 /// Many LLVM objects have a `Name`, which is either a string name, or just a
 /// sequential numbering (e.g. `%3`).
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Hash)]
+///
+/// String names are normally held as `Name::Name`. After
+/// [`Module::intern_strings()`](../module/struct.Module.html#method.intern_strings),
+/// they are instead held as `Name::Interned`, so that equal names share
+/// storage. The two variants compare, order, hash, and print the same for
+/// the same string; use [`as_str()`](#method.as_str) to get the string of
+/// either one.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Name {
     /// has a string name
     Name(String),
     /// doesn't have a string name and was given this sequential number
     Number(usize),
+    /// has a string name, which may share storage with other equal `Name`s
+    Interned(Arc<str>),
 }
 
 impl Name {
     pub(crate) fn name_or_num(s: String, ctr: &mut usize) -> Self {
-        if s != "" {
+        if !s.is_empty() {
             Name::Name(s)
         } else {
             let rval = Name::Number(*ctr);
@@ -20,6 +33,74 @@ impl Name {
             rval
         }
     }
+
+    /// Construct a numbered `Name` (e.g., `%3`)
+    pub fn number(n: usize) -> Self {
+        Name::Number(n)
+    }
+
+    /// If this is a numbered `Name`, get its number; for string names, returns `None`
+    pub fn as_number(&self) -> Option<usize> {
+        match self {
+            Name::Number(n) => Some(*n),
+            Name::Name(_) | Name::Interned(_) => None,
+        }
+    }
+
+    /// If this is a string `Name` (interned or not), get its string; for
+    /// numbered names, returns `None`
+    pub fn as_str(&self) -> Option<&str> {
+        self.key().ok()
+    }
+
+    /// The string or number of the `Name`, treating `Name::Interned` the same
+    /// as `Name::Name`
+    fn key(&self) -> Result<&str, usize> {
+        match self {
+            Name::Name(s) => Ok(s),
+            Name::Interned(s) => Ok(s),
+            Name::Number(n) => Err(*n),
+        }
+    }
+}
+
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Name {}
+
+/// String names are ordered before numbered names, as if `Name::Interned`
+/// were `Name::Name`
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+/// Prints `Name::Interned` as `Name::Name`, so that interning doesn't change
+/// the `Debug` output of anything containing `Name`s
+impl fmt::Debug for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.key() {
+            Ok(s) => f.debug_tuple("Name").field(&s).finish(),
+            Err(n) => f.debug_tuple("Number").field(&n).finish(),
+        }
+    }
 }
 
 impl From<String> for Name {
@@ -40,11 +121,34 @@ impl From<usize> for Name {
     }
 }
 
+/// Write `name` as an LLVM identifier with the given prefix (e.g., `%` or
+/// `@`), quoting it if it contains characters not allowed in an unquoted
+/// identifier
+pub(crate) fn write_identifier(f: &mut fmt::Formatter, prefix: char, name: &str) -> fmt::Result {
+    let is_simple = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "-$._".contains(c));
+    if is_simple {
+        write!(f, "{}{}", prefix, name)
+    } else {
+        // like LLVM, escape quotes, backslashes, and nonprintable characters as hex
+        write!(f, "{}\"", prefix)?;
+        for byte in name.bytes() {
+            match byte {
+                b'"' | b'\\' => write!(f, "\\{:02X}", byte)?,
+                _ if byte.is_ascii_graphic() || byte == b' ' => write!(f, "{}", byte as char)?,
+                _ => write!(f, "\\{:02X}", byte)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
 impl fmt::Display for Name {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Name::Name(s) => write!(f, "{:?}", s),
-            Name::Number(n) => write!(f, "%{}", n),
+        match self.key() {
+            Ok(s) => write!(f, "{:?}", s),
+            Err(n) => write!(f, "%{}", n),
         }
     }
 }
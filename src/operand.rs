@@ -1,9 +1,11 @@
 use crate::constant::Constant;
 use crate::name::Name;
-use crate::types::{Type, Typed};
+use crate::module::Module;
+use crate::types::{Type, Typed, TypedInModule};
 use std::collections::HashMap;
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operand {
     /// e.g., `i32 %foo`
     LocalOperand {
@@ -15,6 +17,18 @@ pub enum Operand {
     MetadataOperand, // --TODO not yet implemented-- MetadataOperand(Box<Metadata>),
 }
 
+/// Storage for the variable-length operand lists of instructions, such as
+/// the indices of a `GetElementPtr`.
+///
+/// This is a `Vec<Operand>`, unless the `smallvec` feature is enabled, in
+/// which case up to three operands are stored inline without a heap
+/// allocation. Either way it derefs to `[Operand]`, and can be built with
+/// `collect()` or `.into()` from a `Vec<Operand>`.
+#[cfg(not(feature = "smallvec"))]
+pub type OperandVec = Vec<Operand>;
+#[cfg(feature = "smallvec")]
+pub type OperandVec = smallvec::SmallVec<[Operand; 3]>;
+
 impl Typed for Operand {
     fn get_type(&self) -> Type {
         match self {
@@ -25,6 +39,15 @@ impl Typed for Operand {
     }
 }
 
+impl TypedInModule for Operand {
+    fn get_type_in(&self, module: &Module) -> Type {
+        match self {
+            Operand::ConstantOperand(c) => c.get_type_in(module),
+            _ => self.get_type(),
+        }
+    }
+}
+
 // ********* //
 // from_llvm //
 // ********* //
@@ -0,0 +1,48 @@
+//! `Deserialize` for `Module`. The weak references from `NamedStructType`s to
+//! the struct bodies in the `Module`'s `named_struct_types` aren't
+//! serialized (they may be cyclic), so they have to be restored after the
+//! rest of the `Module` is deserialized.
+
+use crate::debuginfo::{DIGlobalVariable, DILocalVariable, DIScope, DIType};
+use crate::function::Function;
+use crate::module::{GlobalAlias, GlobalVariable, Module};
+use crate::types::Type;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+// Deserializes the fields of a `Module` exactly as
+// `#[derive(Deserialize)]` on `Module` would
+#[derive(Deserialize)]
+#[serde(remote = "Module")]
+struct ModuleFields {
+    name: String,
+    source_file_name: String,
+    data_layout: String,
+    target_triple: Option<String>,
+    functions: Vec<Function>,
+    global_vars: Vec<GlobalVariable>,
+    global_aliases: Vec<GlobalAlias>,
+    named_struct_types: HashMap<String, Option<Arc<RwLock<Type>>>>,
+    inline_assembly: String,
+    metadata_kind_names: HashMap<u32, String>,
+    debug_types: Vec<DIType>,
+    debug_scopes: Vec<DIScope>,
+    debug_global_variables: Vec<DIGlobalVariable>,
+    debug_local_variables: Vec<DILocalVariable>,
+}
+
+impl<'de> Deserialize<'de> for Module {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut module = ModuleFields::deserialize(deserializer)?;
+        let named_struct_types = module.named_struct_types.clone();
+        module.map_types(|ty| match ty {
+            Type::NamedStructType { name, .. } => Type::NamedStructType {
+                name: name.clone(),
+                ty: named_struct_types.get(name).cloned().flatten().map(|arc| Arc::downgrade(&arc)),
+            },
+            _ => ty.clone(),
+        });
+        Ok(module)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::constant::Constant;
 use crate::debugloc::{DebugLoc, HasDebugLoc};
 use crate::function::{CallingConvention, FunctionAttribute, ParameterAttribute};
-use crate::instruction::{HasResult, InlineAssembly};
+use crate::instruction::{called_function_name, HasResult, InlineAssembly, OperandBundle};
 use crate::name::Name;
 use crate::operand::Operand;
 use crate::types::{Type, Typed};
@@ -10,7 +10,8 @@ use std::convert::TryFrom;
 
 /// Terminator instructions end a basic block.
 /// See [LLVM 9 docs on Terminator Instructions](https://releases.llvm.org/9.0.0/docs/LangRef.html#terminator-instructions)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Terminator {
     Ret(Ret),
     Br(Br),
@@ -91,6 +92,163 @@ impl Terminator {
 }
 */
 
+impl Terminator {
+    /// Get a mutable reference to the `DebugLoc` of the `Terminator`
+    pub(crate) fn debugloc_mut(&mut self) -> &mut Option<DebugLoc> {
+        match self {
+            Terminator::Ret(t) => &mut t.debugloc,
+            Terminator::Br(t) => &mut t.debugloc,
+            Terminator::CondBr(t) => &mut t.debugloc,
+            Terminator::Switch(t) => &mut t.debugloc,
+            Terminator::IndirectBr(t) => &mut t.debugloc,
+            Terminator::Invoke(t) => &mut t.debugloc,
+            Terminator::Resume(t) => &mut t.debugloc,
+            Terminator::Unreachable(t) => &mut t.debugloc,
+            Terminator::CleanupRet(t) => &mut t.debugloc,
+            Terminator::CatchRet(t) => &mut t.debugloc,
+            Terminator::CatchSwitch(t) => &mut t.debugloc,
+            Terminator::CallBr(t) => &mut t.debugloc,
+        }
+    }
+
+    /// If this is a direct `Invoke`, get the name of the called function; see
+    /// [`Call::called_function_name()`](../instruction/struct.Call.html#method.called_function_name)
+    pub fn called_function_name(&self) -> Option<&str> {
+        match self {
+            Terminator::Invoke(invoke) => invoke.called_function_name(),
+            _ => None,
+        }
+    }
+
+    /// Get all of the `Operand`s of the `Terminator`.
+    ///
+    /// For an `Invoke` or `CallBr`, these are the called function (unless it
+    /// is inline assembly), followed by the arguments, followed by the
+    /// operands of any operand bundles. Successor blocks are not included;
+    /// see [`successors()`](enum.Terminator.html#method.successors).
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Terminator::Ret(t) => t.return_operand.iter().collect(),
+            Terminator::Br(_) => vec![],
+            Terminator::CondBr(t) => vec![&t.condition],
+            Terminator::Switch(t) => vec![&t.operand],
+            Terminator::IndirectBr(t) => vec![&t.operand],
+            Terminator::Invoke(t) => t
+                .function
+                .as_ref()
+                .right()
+                .into_iter()
+                .chain(t.arguments.iter().map(|(op, _)| op))
+                .chain(t.operand_bundles.iter().flat_map(|bundle| bundle.operands.iter()))
+                .collect(),
+            Terminator::Resume(t) => vec![&t.operand],
+            Terminator::Unreachable(_) => vec![],
+            Terminator::CleanupRet(t) => vec![&t.cleanup_pad],
+            Terminator::CatchRet(t) => vec![&t.catch_pad],
+            Terminator::CatchSwitch(t) => vec![&t.parent_pad],
+            Terminator::CallBr(t) => t.function.as_ref().right().into_iter().chain(t.arguments.iter().map(|(op, _)| op)).collect(),
+        }
+    }
+
+    /// Get mutable references to all of the `Operand`s of the `Terminator`,
+    /// in the same order as [`operands()`](enum.Terminator.html#method.operands)
+    pub fn operands_mut(&mut self) -> Vec<&mut Operand> {
+        match self {
+            Terminator::Ret(t) => t.return_operand.iter_mut().collect(),
+            Terminator::Br(_) => vec![],
+            Terminator::CondBr(t) => vec![&mut t.condition],
+            Terminator::Switch(t) => vec![&mut t.operand],
+            Terminator::IndirectBr(t) => vec![&mut t.operand],
+            Terminator::Invoke(t) => t
+                .function
+                .as_mut()
+                .right()
+                .into_iter()
+                .chain(t.arguments.iter_mut().map(|(op, _)| op))
+                .chain(t.operand_bundles.iter_mut().flat_map(|bundle| bundle.operands.iter_mut()))
+                .collect(),
+            Terminator::Resume(t) => vec![&mut t.operand],
+            Terminator::Unreachable(_) => vec![],
+            Terminator::CleanupRet(t) => vec![&mut t.cleanup_pad],
+            Terminator::CatchRet(t) => vec![&mut t.catch_pad],
+            Terminator::CatchSwitch(t) => vec![&mut t.parent_pad],
+            Terminator::CallBr(t) => t.function.as_mut().right().into_iter().chain(t.arguments.iter_mut().map(|(op, _)| op)).collect(),
+        }
+    }
+
+    /// Get mutable references to the `Type`s stored directly in the
+    /// `Terminator` (i.e., the type of called inline assembly), not including
+    /// the types of its operands
+    pub(crate) fn types_mut(&mut self) -> Vec<&mut Type> {
+        match self {
+            Terminator::Invoke(t) => t.function.as_mut().left().map(|asm| &mut asm.ty).into_iter().collect(),
+            Terminator::CallBr(t) => t.function.as_mut().left().map(|asm| &mut asm.ty).into_iter().collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Get the `Name`s of all of the blocks which the `Terminator` may
+    /// transfer control to. For a `Switch`, these are the destinations of
+    /// each case in order, followed by the default destination.
+    pub fn successors(&self) -> Vec<&Name> {
+        match self {
+            Terminator::Ret(_) => vec![],
+            Terminator::Br(t) => vec![&t.dest],
+            Terminator::CondBr(t) => vec![&t.true_dest, &t.false_dest],
+            Terminator::Switch(t) => t.dests.iter().map(|(_, dest)| dest).chain(std::iter::once(&t.default_dest)).collect(),
+            Terminator::IndirectBr(t) => t.possible_dests.iter().collect(),
+            Terminator::Invoke(t) => vec![&t.return_label, &t.exception_label],
+            Terminator::Resume(_) => vec![],
+            Terminator::Unreachable(_) => vec![],
+            Terminator::CleanupRet(t) => t.unwind_dest.iter().collect(),
+            Terminator::CatchRet(t) => vec![&t.successor],
+            Terminator::CatchSwitch(t) => t.catch_handlers.iter().chain(t.default_unwind_dest.iter()).collect(),
+            Terminator::CallBr(t) => std::iter::once(&t.return_label).chain(t.other_labels.iter()).collect(),
+        }
+    }
+
+    /// Get mutable references to the `Name`s of all of the blocks which the
+    /// `Terminator` may transfer control to
+    pub(crate) fn successors_mut(&mut self) -> Vec<&mut Name> {
+        match self {
+            Terminator::Ret(_) => vec![],
+            Terminator::Br(t) => vec![&mut t.dest],
+            Terminator::CondBr(t) => vec![&mut t.true_dest, &mut t.false_dest],
+            Terminator::Switch(t) => t.dests.iter_mut().map(|(_, dest)| dest).chain(std::iter::once(&mut t.default_dest)).collect(),
+            Terminator::IndirectBr(t) => t.possible_dests.iter_mut().collect(),
+            Terminator::Invoke(t) => vec![&mut t.return_label, &mut t.exception_label],
+            Terminator::Resume(_) => vec![],
+            Terminator::Unreachable(_) => vec![],
+            Terminator::CleanupRet(t) => t.unwind_dest.iter_mut().collect(),
+            Terminator::CatchRet(t) => vec![&mut t.successor],
+            Terminator::CatchSwitch(t) => t.catch_handlers.iter_mut().chain(t.default_unwind_dest.iter_mut()).collect(),
+            Terminator::CallBr(t) => std::iter::once(&mut t.return_label).chain(t.other_labels.iter_mut()).collect(),
+        }
+    }
+
+    /// Get the result (destination) of the `Terminator`, or `None` if it
+    /// doesn't have a result
+    pub(crate) fn try_get_result(&self) -> Option<&Name> {
+        match self {
+            Terminator::Invoke(t) => Some(&t.result),
+            Terminator::CatchSwitch(t) => Some(&t.result),
+            Terminator::CallBr(t) => Some(&t.result),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the result (destination) of the
+    /// `Terminator`, or `None` if it doesn't have a result
+    pub(crate) fn try_get_result_mut(&mut self) -> Option<&mut Name> {
+        match self {
+            Terminator::Invoke(t) => Some(&mut t.result),
+            Terminator::CatchSwitch(t) => Some(&mut t.result),
+            Terminator::CallBr(t) => Some(&mut t.result),
+            _ => None,
+        }
+    }
+}
+
 macro_rules! impl_term {
     ($term:ty, $id:ident) => {
         impl From<$term> for Terminator {
@@ -146,7 +304,8 @@ macro_rules! void_typed {
 }
 
 /// See [LLVM 9 docs on the 'ret' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#ret-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ret {
     /// The value being returned, or `None` if returning void.
     pub return_operand: Option<Operand>,
@@ -160,7 +319,8 @@ void_typed!(Ret); // technically the instruction has void type, even though the
 /// See [LLVM 9 docs on the 'br' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#br-instruction).
 /// The LLVM 'br' instruction has both conditional and unconditional variants, which we separate -- this is
 /// the unconditional variant, while the conditional variant is [`CondBr`](struct.CondBr.html).
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Br {
     /// The [`Name`](../enum.Name.html) of the [`BasicBlock`](../struct.BasicBlock.html) destination.
     pub dest: Name,
@@ -174,7 +334,8 @@ void_typed!(Br);
 /// See [LLVM 9 docs on the 'br' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#br-instruction).
 /// The LLVM 'br' instruction has both conditional and unconditional variants, which we separate -- this is
 /// the conditional variant, while the unconditional variant is [`Br`](struct.Br.html).
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CondBr {
     /// The branch condition.
     pub condition: Operand,
@@ -190,7 +351,8 @@ impl_term!(CondBr, CondBr);
 void_typed!(CondBr);
 
 /// See [LLVM 9 docs on the 'switch' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#switch-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Switch {
     pub operand: Operand,
     pub dests: Vec<(Constant, Name)>,
@@ -203,7 +365,8 @@ impl_term!(Switch, Switch);
 void_typed!(Switch);
 
 /// See [LLVM 9 docs on the 'indirectbr' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#indirectbr-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndirectBr {
     /// Address to jump to (must be derived from a [`Constant::BlockAddress`](../enum.Constant.html))
     pub operand: Operand,
@@ -220,16 +383,21 @@ impl_term!(IndirectBr, IndirectBr);
 void_typed!(IndirectBr);
 
 /// See [LLVM 9 docs on the 'invoke' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#invoke-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Invoke {
     pub function: Either<InlineAssembly, Operand>,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
+    /// The attributes of each argument at this call site; see
+    /// [`Call::argument_attributes`](../instruction/struct.Call.html#structfield.argument_attributes)
+    pub argument_attributes: Vec<Vec<ParameterAttribute>>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub result: Name, // The name of the variable that will get the result of the call (if the callee returns with 'ret')
     pub return_label: Name, // Should be the name of a basic block. If the callee returns normally (i.e., with 'ret'), control flow resumes here.
     pub exception_label: Name, // Should be the name of a basic block. If the callee returns with 'resume' or another exception-handling mechanism, control flow resumes here.
     pub function_attributes: Vec<FunctionAttribute>, // llvm-hs has the equivalent of Vec<Either<GroupID, FunctionAttribute>>, but I'm not sure how the GroupID option comes up
     pub calling_convention: CallingConvention,
+    pub operand_bundles: Vec<OperandBundle>,
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -237,17 +405,29 @@ pub struct Invoke {
 impl_term!(Invoke, Invoke);
 impl_hasresult!(Invoke);
 
+impl Invoke {
+    /// For a direct call, get the name of the called function; see
+    /// [`Call::called_function_name()`](../instruction/struct.Call.html#method.called_function_name)
+    pub fn called_function_name(&self) -> Option<&str> {
+        called_function_name(&self.function)
+    }
+}
+
 impl Typed for Invoke {
     fn get_type(&self) -> Type {
         match self.function.get_type() {
-            Type::FuncType { result_type, .. } => *result_type,
-            ty => panic!("Expected the function argument of an Invoke to have type FuncType; got {:?}", ty),
+            Type::PointerType { pointee_type, .. } => match *pointee_type {
+                Type::FuncType { result_type, .. } => *result_type,
+                ty => panic!("Expected Invoke's function argument to be of type pointer-to-function, got pointer-to-{:?}", ty),
+            },
+            ty => panic!("Expected Invoke's function argument to be of type pointer-to-function, got {:?}", ty),
         }
     }
 }
 
 /// See [LLVM 9 docs on the 'resume' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#resume-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resume {
     pub operand: Operand,
     pub debugloc: Option<DebugLoc>,
@@ -258,7 +438,8 @@ impl_term!(Resume, Resume);
 void_typed!(Resume);
 
 /// See [LLVM 9 docs on the 'unreachable' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#unreachable-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unreachable {
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
@@ -268,7 +449,8 @@ impl_term!(Unreachable, Unreachable);
 void_typed!(Unreachable);
 
 /// See [LLVM 9 docs on the 'cleanupret' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#cleanupret-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CleanupRet {
     pub cleanup_pad: Operand,
     /// `None` here indicates 'unwind to caller'
@@ -281,7 +463,8 @@ impl_term!(CleanupRet, CleanupRet);
 void_typed!(CleanupRet);
 
 /// See [LLVM 9 docs on the 'catchret' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#catchret-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CatchRet {
     pub catch_pad: Operand,
     pub successor: Name,
@@ -293,7 +476,8 @@ impl_term!(CatchRet, CatchRet);
 void_typed!(CatchRet);
 
 /// See [LLVM 9 docs on the 'catchswitch' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#catchswitch-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CatchSwitch {
     pub parent_pad: Operand,
     /// Cannot be empty
@@ -310,21 +494,23 @@ impl_hasresult!(CatchSwitch);
 
 impl Typed for CatchSwitch {
     fn get_type(&self) -> Type {
-        unimplemented!("Typed for CatchSwitch")
-        // It's clear that there is a result of this instruction, but the documentation doesn't appear to clearly describe what its type is
+        Type::TokenType // the result is a token which the `catchpad`s in the `catch_handlers` refer to
     }
 }
 
 /// See [LLVM 9 docs on the 'callbr' instruction](https://releases.llvm.org/9.0.0/docs/LangRef.html#callbr-instruction)
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallBr {
     pub function: Either<InlineAssembly, Operand>,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
+    /// The attributes of each argument at this call site; see
+    /// [`Call::argument_attributes`](../instruction/struct.Call.html#structfield.argument_attributes)
+    pub argument_attributes: Vec<Vec<ParameterAttribute>>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub result: Name, // The name of the variable that will get the result of the call (if the callee returns with 'ret')
     pub return_label: Name, // Should be the name of a basic block. If the callee returns normally (i.e., with 'ret'), control flow resumes here.
-    /// `other_labels` should be `Vec<Name>`, but it appears there is no way to get this information with the LLVM C API (as opposed to the C++ API)
-    pub other_labels: (),  //Vec<Name>, // Should be names of basic blocks. The callee may use an inline-asm 'goto' to resume control flow at one of these places.
+    pub other_labels: Vec<Name>, // Should be names of basic blocks. The callee may use an inline-asm 'goto' to resume control flow at one of these places.
     pub function_attributes: Vec<FunctionAttribute>,
     pub calling_convention: CallingConvention,
     pub debugloc: Option<DebugLoc>,
@@ -337,8 +523,11 @@ impl_hasresult!(CallBr);
 impl Typed for CallBr {
     fn get_type(&self) -> Type {
         match self.function.get_type() {
-            Type::FuncType { result_type, .. } => *result_type,
-            ty => panic!("Expected the function argument of a CallBr to have type FuncType; got {:?}", ty),
+            Type::PointerType { pointee_type, .. } => match *pointee_type {
+                Type::FuncType { result_type, .. } => *result_type,
+                ty => panic!("Expected CallBr's function argument to be of type pointer-to-function, got pointer-to-{:?}", ty),
+            },
+            ty => panic!("Expected CallBr's function argument to be of type pointer-to-function, got {:?}", ty),
         }
     }
 }
@@ -539,6 +728,7 @@ impl Invoke {
         let callinfo = CallInfo::from_llvm_ref(term, vnmap, gnmap, tnmap);
         Self {
             function: callinfo.function,
+            argument_attributes: callinfo.arguments.iter().map(|(_, attrs)| attrs.clone()).collect(),
             arguments: callinfo.arguments,
             return_attributes: callinfo.return_attributes,
             result: Name::name_or_num(unsafe { get_value_name(term) }, ctr),
@@ -552,6 +742,7 @@ impl Invoke {
                 .clone(),
             function_attributes: callinfo.function_attributes,
             calling_convention: callinfo.calling_convention,
+            operand_bundles: callinfo.operand_bundles,
             debugloc: DebugLoc::from_llvm_with_col(term),
             // metadata: InstructionMetadata::from_llvm_inst(term),
         }
@@ -720,14 +911,25 @@ impl CallBr {
         let callinfo = CallInfo::from_llvm_ref(term, vnmap, gnmap, tnmap);
         Self {
             function: callinfo.function,
+            argument_attributes: callinfo.arguments.iter().map(|(_, attrs)| attrs.clone()).collect(),
             arguments: callinfo.arguments,
             return_attributes: callinfo.return_attributes,
             result: Name::name_or_num(unsafe { get_value_name(term) }, ctr),
             return_label: bbmap
-                .get(unsafe { &LLVMGetNormalDest(term) })
-                .expect("Failed to find invoke return destination in map")
+                .get(unsafe { &LLVMGetSuccessor(term, 0) }) // LLVMGetNormalDest() is only for Invoke, and gives the wrong block for a CallBr
+                .expect("Failed to find callbr return destination in map")
                 .clone(),
-            other_labels: (),
+            other_labels: {
+                let num_dests = unsafe { LLVMGetNumSuccessors(term) };
+                (1..num_dests) // LLVMGetSuccessor(0) gives the return label
+                    .map(|i| {
+                        bbmap
+                            .get(unsafe { &LLVMGetSuccessor(term, i) })
+                            .expect("Failed to find callbr other label in map")
+                            .clone()
+                    })
+                    .collect()
+            },
             function_attributes: callinfo.function_attributes,
             calling_convention: callinfo.calling_convention,
             debugloc: DebugLoc::from_llvm_with_col(term),
@@ -1,10 +1,15 @@
-use crate::module::AddrSpace;
+use crate::datalayout::{DataLayout, LayoutEntry, StructLayout};
+use crate::module::{AddrSpace, Module};
 //use crate::name::Name;
+use crate::name::{write_identifier, Name};
 use either::Either;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, RwLock, Weak};
 
 /// See [LLVM 9 docs on Type System](https://releases.llvm.org/9.0.0/docs/LangRef.html#type-system)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum Type {
     /// See [LLVM 9 docs on Void Type](https://releases.llvm.org/9.0.0/docs/LangRef.html#void-type)
@@ -52,6 +57,10 @@ pub enum Type {
         /// The actual struct type, which will be a `StructType` variant.
         /// A `None` here indicates an opaque type; see [LLVM 9 docs on Opaque Structure Types](https://releases.llvm.org/9.0.0/docs/LangRef.html#t-opaque).
         /// The weak reference should remain valid for at least the lifetime of the `Module` in which the named struct type is defined.
+        ///
+        /// This is not serialized; deserializing a `Module` points it back at
+        /// the `Module`'s `named_struct_types`.
+        #[cfg_attr(feature = "serde", serde(skip))]
         ty: Option<Weak<RwLock<Type>>>,
     },
     /// See [LLVM 9 docs on X86_MMX Type](https://releases.llvm.org/9.0.0/docs/LangRef.html#x86-mmx-type)
@@ -108,6 +117,37 @@ impl PartialEq for Type {
 // Our `PartialEq` still satisfies the required properties of `Eq`
 impl Eq for Type {}
 
+// `Hash` must be consistent with our `PartialEq`, so in particular it disregards
+//   the weak refs in `NamedStructType`
+impl Hash for Type {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Type::IntegerType { bits } => bits.hash(state),
+            Type::PointerType { pointee_type, addr_space } => {
+                pointee_type.hash(state);
+                addr_space.hash(state);
+            },
+            Type::FPType(fpt) => fpt.hash(state),
+            Type::FuncType { result_type, param_types, is_var_arg } => {
+                result_type.hash(state);
+                param_types.hash(state);
+                is_var_arg.hash(state);
+            },
+            Type::VectorType { element_type, num_elements } | Type::ArrayType { element_type, num_elements } => {
+                element_type.hash(state);
+                num_elements.hash(state);
+            },
+            Type::StructType { element_types, is_packed } => {
+                element_types.hash(state);
+                is_packed.hash(state);
+            },
+            Type::NamedStructType { name, .. } => name.hash(state),
+            Type::VoidType | Type::X86_MMXType | Type::MetadataType | Type::LabelType | Type::TokenType => {},
+        }
+    }
+}
+
 impl Type {
     pub fn bool() -> Type {
         Type::IntegerType { bits: 1 }
@@ -141,16 +181,283 @@ impl Type {
         Type::FPType(FPType::Double)
     }
 
+    /// Is this the integer type `i1`?
+    pub fn is_bool(&self) -> bool {
+        self.is_integer_of_width(1)
+    }
+
+    /// If this is an integer type, get its width in bits
+    pub fn integer_width(&self) -> Option<u32> {
+        match self {
+            Type::IntegerType { bits } => Some(*bits),
+            _ => None,
+        }
+    }
+
+    /// Is this an integer type with the given width in bits?
+    pub fn is_integer_of_width(&self, bits: u32) -> bool {
+        self.integer_width() == Some(bits)
+    }
+
+    /// Is this one of the floating-point types?
+    pub fn is_floating_point(&self) -> bool {
+        matches!(self, Type::FPType(_))
+    }
+
+    /// Get the broad category of this type, e.g., `TypeCategory::Struct` for
+    /// both literal and named struct types
+    pub fn category(&self) -> TypeCategory {
+        match self {
+            Type::VoidType => TypeCategory::Void,
+            Type::IntegerType { .. } => TypeCategory::Integer,
+            Type::PointerType { .. } => TypeCategory::Pointer,
+            Type::FPType(_) => TypeCategory::Float,
+            Type::FuncType { .. } => TypeCategory::Function,
+            Type::VectorType { .. } => TypeCategory::Vector,
+            Type::ArrayType { .. } => TypeCategory::Array,
+            Type::StructType { .. } | Type::NamedStructType { .. } => TypeCategory::Struct,
+            Type::MetadataType => TypeCategory::Metadata,
+            Type::LabelType => TypeCategory::Label,
+            Type::TokenType => TypeCategory::Token,
+            Type::X86_MMXType => TypeCategory::Other,
+        }
+    }
+
     pub fn pointer_to(ty: Type) -> Type {
         Type::PointerType {
             pointee_type: Box::new(ty),
             addr_space: 0, // default to addr_space 0
         }
     }
+
+    /// Maximum width of an integer type, in bits.
+    /// See [LLVM 9 docs on Integer Type](https://releases.llvm.org/9.0.0/docs/LangRef.html#integer-type)
+    pub const MAX_INT_BITS: u32 = (1 << 23) - 1;
+
+    /// Construct an integer type of the given width, checking that the width
+    /// is one LLVM allows (`1` through `2^23 - 1` bits)
+    pub fn int(bits: u32) -> Result<Type, TypeError> {
+        if bits == 0 || bits > Self::MAX_INT_BITS {
+            Err(TypeError::InvalidIntegerWidth(bits))
+        } else {
+            Ok(Type::IntegerType { bits })
+        }
+    }
+
+    /// Construct an array type, checking that the element type is one which
+    /// LLVM allows in arrays. (Unlike vectors, arrays may have zero elements.)
+    pub fn array(element_type: Type, num_elements: usize) -> Result<Type, TypeError> {
+        match element_type {
+            Type::VoidType | Type::LabelType | Type::MetadataType | Type::FuncType { .. } | Type::TokenType => {
+                Err(TypeError::InvalidElementType(element_type))
+            },
+            _ => Ok(Type::ArrayType { element_type: Box::new(element_type), num_elements }),
+        }
+    }
+
+    /// Construct a vector type, checking that the number of elements is
+    /// nonzero and that the element type is an integer, floating-point, or
+    /// pointer type
+    pub fn vector(element_type: Type, num_elements: usize) -> Result<Type, TypeError> {
+        if num_elements == 0 {
+            return Err(TypeError::ZeroLengthVector);
+        }
+        match element_type {
+            Type::IntegerType { .. } | Type::FPType(_) | Type::PointerType { .. } => {
+                Ok(Type::VectorType { element_type: Box::new(element_type), num_elements })
+            },
+            _ => Err(TypeError::InvalidElementType(element_type)),
+        }
+    }
+
+    /// Get the layout in memory of this struct type (either a `StructType` or
+    /// a non-opaque `NamedStructType`) under the given `DataLayout`,
+    /// including any padding between or after its fields.
+    ///
+    /// Returns `None` if this is not a struct type, or if any of its fields
+    /// are unsized.
+    pub fn struct_layout(&self, dl: &DataLayout) -> Option<StructLayout> {
+        let (element_types, is_packed) = match self {
+            Type::StructType { element_types, is_packed } => (element_types.clone(), *is_packed),
+            Type::NamedStructType { ty: Some(weak), .. } => {
+                let arc = weak.upgrade().expect("Weak reference disappeared");
+                let body = arc.read().unwrap();
+                match &*body {
+                    Type::StructType { element_types, is_packed } => (element_types.clone(), *is_packed),
+                    _ => return None,
+                }
+            },
+            _ => return None,
+        };
+        let offsets = dl.struct_field_offsets(&element_types, is_packed)?;
+        let size = dl.alloc_size(self)?;
+        let alignment = dl.abi_alignment(self)?;
+
+        let mut entries = Vec::with_capacity(element_types.len());
+        let mut end = 0;
+        for (index, (ty, offset)) in element_types.into_iter().zip(offsets).enumerate() {
+            if offset > end {
+                entries.push(LayoutEntry::Padding { offset: end, size: offset - end });
+            }
+            let field_size = dl.alloc_size(&ty)?;
+            entries.push(LayoutEntry::Field { index, offset, size: field_size, ty });
+            end = offset + field_size;
+        }
+        if size > end {
+            entries.push(LayoutEntry::Padding { offset: end, size: size - end });
+        }
+        Some(StructLayout { entries, size, alignment })
+    }
+
+    /// Rebuild the `Type` with `f` applied to each of its component types
+    /// (bottom-up), and then apply `f` to the result.
+    ///
+    /// The bodies of named structs are not visited, as they may be recursive.
+    pub(crate) fn map_recursive<F: FnMut(&Type) -> Type>(&self, f: &mut F) -> Type {
+        let ty = match self {
+            Type::PointerType { pointee_type, addr_space } => Type::PointerType {
+                pointee_type: Box::new(pointee_type.map_recursive(f)),
+                addr_space: *addr_space,
+            },
+            Type::FuncType { result_type, param_types, is_var_arg } => Type::FuncType {
+                result_type: Box::new(result_type.map_recursive(f)),
+                param_types: param_types.iter().map(|ty| ty.map_recursive(f)).collect(),
+                is_var_arg: *is_var_arg,
+            },
+            Type::VectorType { element_type, num_elements } => Type::VectorType {
+                element_type: Box::new(element_type.map_recursive(f)),
+                num_elements: *num_elements,
+            },
+            Type::ArrayType { element_type, num_elements } => Type::ArrayType {
+                element_type: Box::new(element_type.map_recursive(f)),
+                num_elements: *num_elements,
+            },
+            Type::StructType { element_types, is_packed } => Type::StructType {
+                element_types: element_types.iter().map(|ty| ty.map_recursive(f)).collect(),
+                is_packed: *is_packed,
+            },
+            _ => self.clone(),
+        };
+        f(&ty)
+    }
+}
+
+/// Formats the `Type` as it would appear in LLVM IR, e.g. `{ i32, i8* }`.
+/// Named structs are formatted as a reference (e.g., `%struct.Foo`), not
+/// with their definition.
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::VoidType => write!(f, "void"),
+            Type::IntegerType { bits } => write!(f, "i{}", bits),
+            Type::PointerType { pointee_type, addr_space: 0 } => write!(f, "{}*", pointee_type),
+            Type::PointerType { pointee_type, addr_space } => write!(f, "{} addrspace({})*", pointee_type, addr_space),
+            Type::FPType(fpt) => write!(f, "{}", fpt),
+            Type::FuncType { result_type, param_types, is_var_arg } => {
+                write!(f, "{} (", result_type)?;
+                for (i, param_type) in param_types.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param_type)?;
+                }
+                if *is_var_arg {
+                    if !param_types.is_empty() {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "...")?;
+                }
+                write!(f, ")")
+            },
+            Type::VectorType { element_type, num_elements } => write!(f, "<{} x {}>", num_elements, element_type),
+            Type::ArrayType { element_type, num_elements } => write!(f, "[{} x {}]", num_elements, element_type),
+            Type::StructType { element_types, is_packed } => {
+                if *is_packed {
+                    write!(f, "<")?;
+                }
+                if element_types.is_empty() {
+                    write!(f, "{{}}")?;
+                } else {
+                    write!(f, "{{ ")?;
+                    for (i, element_type) in element_types.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", element_type)?;
+                    }
+                    write!(f, " }}")?;
+                }
+                if *is_packed {
+                    write!(f, ">")?;
+                }
+                Ok(())
+            },
+            Type::NamedStructType { name, .. } => write_identifier(f, '%', name),
+            Type::X86_MMXType => write!(f, "x86_mmx"),
+            Type::MetadataType => write!(f, "metadata"),
+            Type::LabelType => write!(f, "label"),
+            Type::TokenType => write!(f, "token"),
+        }
+    }
+}
+
+/// Error returned by the checked `Type` constructors (e.g., [`Type::int()`](enum.Type.html#method.int)),
+/// and by [`Module::verify_types()`](../struct.Module.html#method.verify_types)
+#[derive(PartialEq, Clone, Debug)]
+pub enum TypeError {
+    /// Integer types must be between `1` and `2^23 - 1` bits wide
+    InvalidIntegerWidth(u32),
+    /// Vector types must have at least one element
+    ZeroLengthVector,
+    /// The given type is not allowed as the element type of this array or vector type
+    InvalidElementType(Type),
+    /// An operand of the `index`th instruction of `block` (or of its
+    /// terminator, if `index` is the number of instructions) has type
+    /// `found`, but its other operands or its function require `expected`.
+    /// (The types are boxed to keep `TypeError` small for the constructors.)
+    Mismatch { function: String, block: Name, index: usize, expected: Box<Type>, found: Box<Type> },
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TypeError::InvalidIntegerWidth(bits) => write!(f, "Invalid integer width: {} bits", bits),
+            TypeError::ZeroLengthVector => write!(f, "Vector types must have at least one element"),
+            TypeError::InvalidElementType(ty) => write!(f, "Invalid element type: {}", ty),
+            TypeError::Mismatch { function, block, index, expected, found } => write!(
+                f,
+                "{}: block {}, instruction {}: expected type {}, found {}",
+                function, block, index, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// The broad category of a [`Type`](enum.Type.html), as returned by
+/// [`Type::category()`](enum.Type.html#method.category)
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum TypeCategory {
+    Integer,
+    Float,
+    Pointer,
+    Vector,
+    Array,
+    /// Both literal (`StructType`) and named (`NamedStructType`) structs
+    Struct,
+    Function,
+    Void,
+    Metadata,
+    Token,
+    Label,
+    /// Currently only `X86_MMXType`
+    Other,
 }
 
 /// See [LLVM 9 docs on Floating-Point Types](https://releases.llvm.org/9.0.0/docs/LangRef.html#floating-point-types)
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum FPType {
     Half,
@@ -161,6 +468,19 @@ pub enum FPType {
     PPC_FP128,
 }
 
+impl fmt::Display for FPType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FPType::Half => write!(f, "half"),
+            FPType::Single => write!(f, "float"),
+            FPType::Double => write!(f, "double"),
+            FPType::FP128 => write!(f, "fp128"),
+            FPType::X86_FP80 => write!(f, "x86_fp80"),
+            FPType::PPC_FP128 => write!(f, "ppc_fp128"),
+        }
+    }
+}
+
 impl From<FPType> for Type {
     fn from(fpt: FPType) -> Type {
         Type::FPType(fpt)
@@ -178,6 +498,39 @@ impl Typed for Type {
     }
 }
 
+/// The `TypedInModule` trait is used for things whose [`Type`](../enum.Type.html)
+/// may depend on the definitions of named struct types, such as `GetElementPtr`
+/// and `ExtractValue`. Those definitions are looked up in the given `Module`,
+/// so this works even if the `Module` the value came from has since been
+/// dropped, and never panics on a `NamedStructType` the way `get_type()` can.
+///
+/// For everything else, `get_type_in()` is the same as `get_type()`.
+pub trait TypedInModule {
+    fn get_type_in(&self, module: &Module) -> Type;
+}
+
+/// Get the type of the value at the given indices into an aggregate of the
+/// given type, resolving named struct types using the given `Module`.
+/// Returns `None` if an index is out of range, or if an indexed type is not an
+/// aggregate or is an opaque struct.
+pub(crate) fn extractvalue_type_in(module: &Module, mut cur_type: Type, indices: &[u32]) -> Option<Type> {
+    for &index in indices {
+        if let Type::NamedStructType { name, .. } = &cur_type {
+            let arc = module.named_struct_types.get(name)?.as_ref()?.clone(); // `None` if opaque
+            let inner = arc.read().unwrap().clone();
+            cur_type = inner;
+        }
+        cur_type = match cur_type {
+            Type::ArrayType { element_type, num_elements } if (index as usize) < num_elements => *element_type,
+            Type::StructType { mut element_types, .. } if (index as usize) < element_types.len() => {
+                element_types.swap_remove(index as usize)
+            },
+            _ => return None,
+        };
+    }
+    Some(cur_type)
+}
+
 impl Typed for FPType {
     fn get_type(&self) -> Type {
         self.clone().into()
@@ -0,0 +1,235 @@
+use crate::cfg::Cfg;
+use crate::function::Function;
+use crate::instruction::{InlineAssembly, Instruction};
+use crate::module::Module;
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::Terminator;
+use crate::types::{Type, TypeError, Typed};
+use either::Either;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A structural problem found by [`Module::verify()`](../struct.Module.html#method.verify).
+///
+/// This is a much smaller set of checks than LLVM's own verifier: it is meant
+/// to catch the mistakes most easily made when transforming a `Module` in
+/// place, such as dangling references to removed blocks or values.
+#[derive(PartialEq, Clone, Debug)]
+pub enum VerifyError {
+    /// The function has more than one block with this name
+    DuplicateBlock { function: String, block: Name },
+    /// The function defines this local (parameter or result) more than once
+    DuplicateDefinition { function: String, name: Name },
+    /// A terminator or `Phi` in `block` refers to a block which the function
+    /// doesn't have
+    UndefinedBlock { function: String, block: Name, target: Name },
+    /// An operand in `block` refers to a local which the function doesn't define
+    UndefinedLocal { function: String, block: Name, name: Name },
+    /// The `Ret` ending `block` doesn't return the function's return type
+    ReturnTypeMismatch { function: String, block: Name, expected: Type, found: Type },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::DuplicateBlock { function, block } => {
+                write!(f, "{}: duplicate block {}", function, block)
+            },
+            VerifyError::DuplicateDefinition { function, name } => {
+                write!(f, "{}: {} is defined more than once", function, name)
+            },
+            VerifyError::UndefinedBlock { function, block, target } => {
+                write!(f, "{}: block {} refers to nonexistent block {}", function, block, target)
+            },
+            VerifyError::UndefinedLocal { function, block, name } => {
+                write!(f, "{}: block {} uses undefined value {}", function, block, name)
+            },
+            VerifyError::ReturnTypeMismatch { function, block, expected, found } => write!(
+                f,
+                "{}: block {} returns {}, but the function returns {}",
+                function, block, found, expected
+            ),
+        }
+    }
+}
+
+/// Run all of the checks on all of the functions in the `Module`
+pub(crate) fn verify_module(module: &Module) -> Result<(), Vec<VerifyError>> {
+    let errors: Vec<VerifyError> = module.functions.iter().flat_map(verify_function).collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+pub(crate) fn verify_function(func: &Function) -> Vec<VerifyError> {
+    let mut errors = vec![];
+    let function = || func.name.clone();
+
+    let mut blocks: HashSet<&Name> = HashSet::new();
+    for bb in &func.basic_blocks {
+        if !blocks.insert(&bb.name) {
+            errors.push(VerifyError::DuplicateBlock { function: function(), block: bb.name.clone() });
+        }
+    }
+
+    let mut locals: HashSet<&Name> = HashSet::new();
+    let results = func.basic_blocks.iter().flat_map(|bb| {
+        bb.instrs.iter().filter_map(Instruction::try_get_result).chain(bb.term.try_get_result())
+    });
+    for name in func.parameters.iter().map(|param| &param.name).chain(results) {
+        if !locals.insert(name) {
+            errors.push(VerifyError::DuplicateDefinition { function: function(), name: name.clone() });
+        }
+    }
+
+    let cfg = Cfg::new(func);
+    for bb in &func.basic_blocks {
+        let phi_blocks = bb.instrs.iter().filter_map(|instr| match instr {
+            Instruction::Phi(phi) => Some(phi.incoming_values.iter().map(|(_, block)| block)),
+            _ => None,
+        });
+        for target in cfg.successors(&bb.name).iter().copied().chain(phi_blocks.flatten()) {
+            if !blocks.contains(target) {
+                errors.push(VerifyError::UndefinedBlock {
+                    function: function(),
+                    block: bb.name.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+
+        let operands = bb.instrs.iter().flat_map(Instruction::operands).chain(bb.term.operands());
+        for operand in operands {
+            if let Operand::LocalOperand { name, .. } = operand {
+                if !locals.contains(name) {
+                    errors.push(VerifyError::UndefinedLocal {
+                        function: function(),
+                        block: bb.name.clone(),
+                        name: name.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Terminator::Ret(ret) = &bb.term {
+            let found = ret.return_operand.as_ref().map_or(Type::VoidType, |op| op.get_type());
+            if found != func.return_type {
+                errors.push(VerifyError::ReturnTypeMismatch {
+                    function: function(),
+                    block: bb.name.clone(),
+                    expected: func.return_type.clone(),
+                    found,
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+/// Check the types of the operands of all of the instructions and
+/// terminators in the `Module`
+pub(crate) fn verify_module_types(module: &Module) -> Result<(), Vec<TypeError>> {
+    let mut errors = vec![];
+    for func in &module.functions {
+        for bb in &func.basic_blocks {
+            let error = |index, (expected, found)| TypeError::Mismatch {
+                function: func.name.clone(),
+                block: bb.name.clone(),
+                index,
+                expected: Box::new(expected),
+                found: Box::new(found),
+            };
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                errors.extend(instruction_type_mismatches(inst).into_iter().map(|m| error(index, m)));
+            }
+            let mismatches = match &bb.term {
+                Terminator::Ret(ret) => {
+                    let found = ret.return_operand.as_ref().map_or(Type::VoidType, |op| op.get_type());
+                    mismatch(func.return_type.clone(), found).into_iter().collect()
+                },
+                Terminator::CondBr(condbr) => mismatch(Type::bool(), condbr.condition.get_type()).into_iter().collect(),
+                Terminator::Invoke(invoke) => argument_mismatches(&invoke.function, &invoke.arguments),
+                _ => vec![],
+            };
+            errors.extend(mismatches.into_iter().map(|m| error(bb.instrs.len(), m)));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// The `(expected, found)` types of the operands of `inst` which don't have
+/// the types they should
+fn instruction_type_mismatches(inst: &Instruction) -> Vec<(Type, Type)> {
+    if inst.is_binary_op() {
+        // both operands have the type of the operation
+        let operands = inst.operands();
+        return mismatch(operands[0].get_type(), operands[1].get_type()).into_iter().collect();
+    }
+    match inst {
+        Instruction::ICmp(icmp) => mismatch(icmp.operand0.get_type(), icmp.operand1.get_type()).into_iter().collect(),
+        Instruction::FCmp(fcmp) => mismatch(fcmp.operand0.get_type(), fcmp.operand1.get_type()).into_iter().collect(),
+        Instruction::Store(store) => {
+            let value_type = store.value.get_type();
+            match store.address.get_type() {
+                Type::PointerType { pointee_type, .. } => mismatch(*pointee_type, value_type).into_iter().collect(),
+                found => vec![(Type::pointer_to(value_type), found)],
+            }
+        },
+        Instruction::Load(load) => match load.address.get_type() {
+            Type::PointerType { .. } => vec![],
+            found => vec![(Type::pointer_to(found.clone()), found)],
+        },
+        Instruction::Select(select) => {
+            let condition_type = select.condition.get_type();
+            let expected_condition = match &condition_type {
+                Type::VectorType { num_elements, .. } => Type::VectorType {
+                    element_type: Box::new(Type::bool()),
+                    num_elements: *num_elements,
+                },
+                _ => Type::bool(),
+            };
+            mismatch(expected_condition, condition_type)
+                .into_iter()
+                .chain(mismatch(select.true_value.get_type(), select.false_value.get_type()))
+                .collect()
+        },
+        Instruction::Call(call) => argument_mismatches(&call.function, &call.arguments),
+        _ => vec![],
+    }
+}
+
+/// The `(expected, found)` types of the arguments of a call which don't have
+/// the types of the callee's corresponding (non-variadic) parameters
+fn argument_mismatches<A>(function: &Either<InlineAssembly, Operand>, arguments: &[(Operand, A)]) -> Vec<(Type, Type)> {
+    let func_ty = match function {
+        Either::Left(asm) => asm.get_type(),
+        Either::Right(op) => op.get_type(),
+    };
+    match func_ty {
+        Type::PointerType { pointee_type, .. } => match *pointee_type {
+            Type::FuncType { param_types, .. } => param_types
+                .into_iter()
+                .zip(arguments)
+                .filter_map(|(expected, (arg, _))| mismatch(expected, arg.get_type()))
+                .collect(),
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+fn mismatch(expected: Type, found: Type) -> Option<(Type, Type)> {
+    if expected == found {
+        None
+    } else {
+        Some((expected, found))
+    }
+}
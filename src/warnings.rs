@@ -0,0 +1,69 @@
+use crate::instruction::Instruction;
+use crate::intrinsic::Intrinsic;
+use crate::module::{InstructionRef, Module};
+use crate::name::Name;
+use crate::operand::Operand;
+use either::Either;
+use std::fmt;
+
+/// Something in the bitcode which couldn't be fully decoded, as reported by
+/// [`Module::from_bc_path_verbose()`](../struct.Module.html#method.from_bc_path_verbose).
+///
+/// These are limitations of the LLVM C API (or of this crate): the affected
+/// parts of the `Module` hold placeholders, or are missing some detail.
+#[derive(PartialEq, Clone, Debug)]
+pub enum ParseWarning {
+    /// The name of the `Comdat` of this function or global variable couldn't
+    /// be decoded; its `Comdat::name` is a placeholder
+    ComdatName { global: Name },
+    /// A metadata operand (other than those of `llvm.dbg.declare` and
+    /// `llvm.dbg.value`, which are decoded for `Function::local_variables()`),
+    /// which is represented as `Operand::MetadataOperand` without its contents
+    MetadataOperand { location: InstructionRef },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseWarning::ComdatName { global } => {
+                write!(f, "global {}: comdat name could not be decoded", global)
+            },
+            ParseWarning::MetadataOperand { location: InstructionRef { function, block, index } } => {
+                write!(f, "{}: block {}, instruction {}: metadata operand could not be decoded", function, block, index)
+            },
+        }
+    }
+}
+
+/// Find everything in the `Module` which couldn't be fully decoded
+pub(crate) fn collect_warnings(module: &Module) -> Vec<ParseWarning> {
+    let mut warnings = vec![];
+
+    for var in &module.global_vars {
+        if var.comdat.is_some() {
+            warnings.push(ParseWarning::ComdatName { global: var.name.clone() });
+        }
+    }
+    for func in &module.functions {
+        if func.comdat.is_some() {
+            warnings.push(ParseWarning::ComdatName { global: Name::from(func.name.as_str()) });
+        }
+
+        for (inst, inst_ref) in module.locations_in(&func.name) {
+            if let Either::Left(inst) = inst {
+                let is_dbg = match inst {
+                    Instruction::Call(call) => matches!(
+                        Intrinsic::from_call(call),
+                        Some(Intrinsic::DbgDeclare) | Some(Intrinsic::DbgValue)
+                    ),
+                    _ => false,
+                };
+                if !is_dbg && inst.operands().iter().any(|op| matches!(op, Operand::MetadataOperand)) {
+                    warnings.push(ParseWarning::MetadataOperand { location: inst_ref });
+                }
+            }
+        }
+    }
+
+    warnings
+}
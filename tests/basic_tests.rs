@@ -1,18 +1,32 @@
 use either::Either;
+use llvm_ir::constant;
 use llvm_ir::instruction;
 use llvm_ir::terminator;
+use llvm_ir::types::FPType;
 use llvm_ir::Constant;
+use llvm_ir::Context;
+use llvm_ir::DataLayout;
+use llvm_ir::datalayout::LayoutEntry;
 use llvm_ir::HasDebugLoc;
+use llvm_ir::FPPredicate;
+use llvm_ir::Function;
 use llvm_ir::IntPredicate;
+use llvm_ir::Instruction;
+use llvm_ir::Intrinsic;
 use llvm_ir::Module;
+use llvm_ir::module::InstructionRef;
 use llvm_ir::Name;
 use llvm_ir::Operand;
+use llvm_ir::Terminator;
 use llvm_ir::Type;
+use llvm_ir::TypeCategory;
+use llvm_ir::TypeError;
 use llvm_ir::Typed;
 use std::convert::TryInto;
 use std::ops::Deref;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -268,8 +282,8 @@ fn loopbc() {
     assert_eq!(gep.dest, Name::Number(12));
     assert_eq!(gep.in_bounds, true);
     assert_eq!(
-        gep.indices,
-        vec![
+        gep.indices[..],
+        [
             Operand::ConstantOperand(Constant::Int { bits: 64, value: 0 }),
             Operand::LocalOperand {
                 name: Name::Number(11),
@@ -302,6 +316,50 @@ fn loopbc() {
     assert_eq!(ret.get_type(), Type::VoidType);
 }
 
+#[test]
+fn predecessors_of() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = &module.functions[0];
+    assert_eq!(func.predecessors_of(&Name::from(22)), vec![&Name::from(2), &Name::from(19)]);
+    assert_eq!(func.predecessors_of(&Name::from(19)), vec![&Name::from(10), &Name::from(14)]);
+    assert_eq!(func.predecessors_of(&Name::from(7)), vec![&Name::from(2)]);
+    assert!(func.predecessors_of(&Name::from(2)).is_empty());
+    assert!(func.predecessors_of(&Name::from("nonexistent")).is_empty());
+}
+
+#[test]
+fn instruction_refs() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let at = |block: usize, index: usize| InstructionRef { function: "loop".to_owned(), block: Name::from(block), index };
+
+    let def = module.definition_of("loop", &Name::from(11)).expect("Should find a definition");
+    assert_eq!(def, at(10, 0));
+    let phi: instruction::Phi = module.instruction_at(&def).cloned().expect("Should resolve").try_into().expect("Should be a phi");
+    assert_eq!(phi.dest, Name::from(11));
+    assert!(module.terminator_at(&def).is_none());
+
+    assert_eq!(module.uses_of("loop", &Name::from(11)), vec![at(10, 1), at(10, 3), at(14, 0), at(19, 0)]);
+    let icmp: instruction::ICmp = module.instruction_at(&at(10, 3)).cloned().expect("Should resolve").try_into().expect("Should be an icmp");
+    assert_eq!(icmp.dest, Name::from(13));
+
+    // uses by terminators refer to one past the last instruction
+    assert_eq!(module.uses_of("loop", &Name::from(21)), vec![at(19, 2)]);
+    assert!(module.instruction_at(&at(19, 2)).is_none());
+    match module.terminator_at(&at(19, 2)) {
+        Some(Terminator::CondBr(_)) => {},
+        term => panic!("Expected a CondBr, got {:?}", term),
+    }
+
+    // parameters have no defining instruction
+    assert_eq!(module.definition_of("loop", &Name::from(0)), None);
+    assert_eq!(module.instruction_at(&at(10, 100)), None);
+    assert_eq!(module.instruction_at(&InstructionRef { function: "nope".to_owned(), block: Name::from(10), index: 0 }), None);
+}
+
 #[test]
 fn switchbc() {
     init_logging();
@@ -332,6 +390,30 @@ fn switchbc() {
     assert_eq!(phi.incoming_values.len(), 10);
 }
 
+#[test]
+fn terminator_operands_and_successors() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let condbr = &module.functions[0].basic_blocks[0].term;
+    assert_eq!(condbr.operands(), vec![&Operand::LocalOperand { name: Name::Number(6), ty: Type::bool() }]);
+    assert_eq!(condbr.successors(), vec![&Name::Number(7), &Name::Number(22)]);
+
+    let path = Path::new("tests/basic_bc/switch.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let switch = &module.functions[0].basic_blocks[0].term;
+    assert_eq!(switch.operands(), vec![&Operand::LocalOperand { name: Name::Number(0), ty: Type::i32() }]);
+    let successors = switch.successors();
+    assert_eq!(successors.len(), 10);
+    assert_eq!(successors[0], &Name::Number(12));
+    assert_eq!(successors[1], &Name::Number(2));
+    assert_eq!(successors[9], &Name::Number(10)); // the default destination
+
+    let ret = module.functions[0].basic_blocks.last().map(|bb| &bb.term).expect("Should have a block");
+    assert_eq!(ret.successors(), Vec::<&Name>::new());
+    assert_eq!(ret.operands().len(), 1);
+}
+
 #[test]
 fn variablesbc() {
     init_logging();
@@ -385,6 +467,35 @@ fn variablesbcg() {
     assert_eq!(debugloc.directory, debug_directory);
 }
 
+#[test]
+fn local_variables() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/variables.bc-g");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("variables").expect("Failed to find function");
+    let local_variables = func.local_variables(&module);
+    assert_eq!(local_variables.len(), 4);
+    let names: Vec<&str> = local_variables.iter().map(|var| var.name.as_str()).collect();
+    assert_eq!(names, vec!["byvalue", "ptr", "stack_alloc", "heap_alloc"]);
+
+    let stack_alloc = &local_variables[2];
+    assert_eq!(stack_alloc.line, 8);
+    assert!(stack_alloc.is_declare);
+    assert_eq!(stack_alloc.value, Some(Name::Number(4)));
+
+    let ptr = &local_variables[1];
+    assert_eq!(ptr.line, 7);
+    assert!(!ptr.is_declare);
+    assert_eq!(ptr.value, Some(Name::Number(1)));
+    assert_eq!(ptr.type_name, None);  // pointer types are unnamed
+
+    // without debuginfo, there are no local variables
+    let path = Path::new("tests/basic_bc/variables.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("variables").expect("Failed to find function");
+    assert!(func.local_variables(&module).is_empty());
+}
+
 #[test]
 fn rustbc() {
     // This tests against the checked-in rust.bc, which was generated from the checked-in rust.rs with rustc 1.39.0
@@ -743,3 +854,431 @@ fn indirectly_recursive_type() {
         );
     }
 }
+
+#[test]
+fn metadata_kind_names() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/hello.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert_eq!(module.metadata_kind_name(0), Some("dbg"));
+    assert_eq!(module.metadata_kind_name(1), Some("tbaa"));
+    assert!(module.metadata_kind_names.values().any(|name| name == "range"));
+    assert_eq!(module.metadata_kind_name(100_000), None);
+}
+
+#[test]
+fn constant_ordering() {
+    init_logging();
+    let mut constants = vec![
+        Constant::Int { bits: 32, value: 26 },
+        Constant::Int { bits: 8, value: 3 },
+        Constant::Int { bits: 64, value: 0 },
+        Constant::Int { bits: 32, value: 3 },
+        Constant::Int { bits: 1, value: 1 },
+    ];
+    constants.sort();
+    assert_eq!(constants, vec![
+        Constant::Int { bits: 64, value: 0 },
+        Constant::Int { bits: 1, value: 1 },
+        Constant::Int { bits: 8, value: 3 },
+        Constant::Int { bits: 32, value: 3 },
+        Constant::Int { bits: 32, value: 26 },
+    ]);
+
+    // constants of different kinds are ordered by kind
+    let int = Constant::Int { bits: 32, value: 1000 };
+    let float = Constant::Float(constant::Float::Double(-1.0));
+    let null = Constant::Null(Type::pointer_to(Type::i8()));
+    assert!(int < float);
+    assert!(float < null);
+    assert!(int < null);
+
+    // constant expressions are ordered by their operands, then by their text
+    let a = Constant::Trunc(Box::new(constant::Trunc { operand: int.clone(), to_type: Type::i8() }));
+    let b = Constant::Trunc(Box::new(constant::Trunc { operand: int.clone(), to_type: Type::i16() }));
+    let c = Constant::Trunc(Box::new(constant::Trunc { operand: Constant::Int { bits: 32, value: 1 }, to_type: Type::i16() }));
+    assert_eq!(a.cmp(&a.clone()), std::cmp::Ordering::Equal);
+    assert!(a > c);
+    assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+    assert_ne!(a.cmp(&b), std::cmp::Ordering::Equal);
+
+    // floats are ordered totally, by bit pattern for NaNs and zeros
+    let nan = Constant::Float(constant::Float::Double(f64::NAN));
+    let zero = Constant::Float(constant::Float::Double(0.0));
+    let neg_zero = Constant::Float(constant::Float::Double(-0.0));
+    assert_eq!(nan, nan.clone());
+    assert!(neg_zero < zero);
+    assert!(zero < nan);
+
+    // so a mixed collection of constants can be sorted, and sorts the same
+    // way regardless of the starting order
+    let mut mixed = vec![b.clone(), null.clone(), nan.clone(), a.clone(), int.clone(), c.clone(), zero.clone()];
+    let mut reversed = mixed.clone();
+    reversed.reverse();
+    mixed.sort();
+    reversed.sort();
+    assert_eq!(mixed, reversed);
+    assert_eq!(mixed[.. 4], [int, zero, nan, null]);
+    assert_eq!(mixed[4], c);
+}
+
+#[test]
+fn data_layout() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/hello.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let dl = DataLayout::parse(&module.data_layout).expect("Failed to parse data layout");
+    assert_eq!(dl.pointer_size_bits(0), 64);
+    assert_eq!(dl.alloc_size(&Type::i64()), Some(8));
+    assert_eq!(dl.abi_alignment(&Type::i64()), Some(8));
+    assert_eq!(dl.alloc_size(&Type::FPType(FPType::X86_FP80)), Some(16));
+    let s = Type::StructType { element_types: vec![Type::i8(), Type::i32(), Type::i8()], is_packed: false };
+    assert_eq!(dl.alloc_size(&s), Some(12));
+    assert_eq!(dl.struct_field_offsets(&[Type::i8(), Type::i32(), Type::i8()], false), Some(vec![0, 4, 8]));
+    assert_eq!(dl.type_size_in_bits(&Type::FuncType { result_type: Box::new(Type::VoidType), param_types: vec![], is_var_arg: false }), None);
+
+    // with no layout specified, LLVM's defaults are used
+    let default = DataLayout::parse("").expect("Failed to parse data layout");
+    assert_eq!(default, DataLayout::default());
+    assert_eq!(default.abi_alignment(&Type::i64()), Some(4));
+    let i686 = DataLayout::parse("e-m:e-p:32:32-f64:32:64-f80:32-n8:16:32-S128").expect("Failed to parse data layout");
+    assert_eq!(i686.pointer_size_bits(0), 32);
+    assert_eq!(i686.alloc_size(&Type::pointer_to(Type::i8())), Some(4));
+    assert!(DataLayout::parse("e-q:64").is_err());
+}
+
+#[test]
+fn module_pointer_sizes() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/hello.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert_eq!(module.pointer_size_bits(0), Some(64));
+    assert_eq!(module.pointer_size_bits(1), Some(64));
+
+    module.data_layout = "e-m:e-p:32:32-p1:64:64-f64:32:64-f80:32-n8:16:32-S128".to_owned();
+    assert_eq!(module.pointer_size_bits(0), Some(32));
+    assert_eq!(module.pointer_size_bits(1), Some(64));
+    assert_eq!(module.pointer_size_bits(2), Some(32));
+
+    module.data_layout = String::new();
+    assert_eq!(module.pointer_size_bits(0), Some(64));
+    module.data_layout = "e-q:64".to_owned();
+    assert_eq!(module.pointer_size_bits(0), None);
+}
+
+#[test]
+fn type_predicates() {
+    assert!(Type::bool().is_bool());
+    assert!(!Type::i8().is_bool());
+    assert_eq!(Type::i32().integer_width(), Some(32));
+    assert_eq!(Type::double().integer_width(), None);
+    assert!(Type::i64().is_integer_of_width(64));
+    assert!(!Type::i64().is_integer_of_width(32));
+    assert!(Type::single().is_floating_point());
+    assert!(Type::FPType(FPType::X86_FP80).is_floating_point());
+    assert!(!Type::i32().is_floating_point());
+    assert!(!Type::pointer_to(Type::double()).is_floating_point());
+}
+
+#[test]
+fn parse_timings() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/rust.bc-g");
+    let (module, timings) = Module::from_bc_path_timed(&path).expect("Failed to parse module");
+    assert_eq!(module.functions.len(), Module::from_bc_path(&path).expect("Failed to parse module").functions.len());
+    let sum = timings.bitcode + timings.name_mapping + timings.functions + timings.globals + timings.metadata;
+    assert!(sum <= timings.total, "{:?}", timings);
+    // each of these phases has real work to do for this module
+    assert!(timings.bitcode > Duration::from_secs(0), "{:?}", timings);
+    assert!(timings.functions > Duration::from_secs(0), "{:?}", timings);
+    assert!(timings.metadata > Duration::from_secs(0), "{:?}", timings);
+}
+
+#[test]
+fn for_each_function_from_bc_path() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/rust.bc-g");
+    let mut names = vec![];
+    Module::for_each_function_from_bc_path(&path, |func| names.push(func.name)).expect("Failed to parse module");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert_eq!(names.len(), module.functions.len());
+    assert!(names.iter().eq(module.functions.iter().map(|func| &func.name)));
+
+    let mut count = 0;
+    Module::for_each_function_from_bc_path("tests/basic_bc/hello.bc", |_| count += 1).expect("Failed to parse module");
+    assert_eq!(count, 1);
+    assert!(Module::for_each_function_from_bc_path("tests/basic_bc/nonexistent.bc", |_| ()).is_err());
+}
+
+#[test]
+fn intrinsic_functions() {
+    let memcpy = Function::new("llvm.memcpy.p0i8.p0i8.i64");
+    let memmove = Function::new("llvm.memmove.p0i8.p0i8.i64");
+    assert!(memcpy.is_intrinsic());
+    assert!(memcpy.intrinsic_id().is_some());
+    assert!(memmove.intrinsic_id().is_some());
+    assert_ne!(memcpy.intrinsic_id(), memmove.intrinsic_id());
+    // overloads of the same intrinsic share an ID
+    assert_eq!(memcpy.intrinsic_id(), Function::new("llvm.memcpy.p0i8.p0i8.i32").intrinsic_id());
+
+    let unknown = Function::new("llvm.not.a.real.intrinsic");
+    assert!(unknown.is_intrinsic());
+    assert_eq!(unknown.intrinsic_id(), None);
+    let memcpy = Function::new("memcpy");
+    assert!(!memcpy.is_intrinsic());
+    assert_eq!(memcpy.intrinsic_id(), None);
+
+    let path = Path::new("tests/basic_bc/hello.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert!(module.functions.iter().all(|func| !func.is_intrinsic()));
+}
+
+#[test]
+fn type_categories() {
+    assert_eq!(Type::i32().category(), TypeCategory::Integer);
+    assert_eq!(Type::bool().category(), TypeCategory::Integer);
+    assert_eq!(Type::double().category(), TypeCategory::Float);
+    assert_eq!(Type::pointer_to(Type::i8()).category(), TypeCategory::Pointer);
+    assert_eq!(Type::vector(Type::single(), 4).unwrap().category(), TypeCategory::Vector);
+    assert_eq!(Type::array(Type::i8(), 4).unwrap().category(), TypeCategory::Array);
+    assert_eq!(Type::StructType { element_types: vec![Type::i8()], is_packed: false }.category(), TypeCategory::Struct);
+    assert_eq!(Type::NamedStructType { name: "struct.Foo".to_owned(), ty: None }.category(), TypeCategory::Struct);
+    let func_ty = Type::FuncType { result_type: Box::new(Type::VoidType), param_types: vec![], is_var_arg: false };
+    assert_eq!(func_ty.category(), TypeCategory::Function);
+    assert_eq!(Type::VoidType.category(), TypeCategory::Void);
+    assert_eq!(Type::MetadataType.category(), TypeCategory::Metadata);
+    assert_eq!(Type::TokenType.category(), TypeCategory::Token);
+    assert_eq!(Type::LabelType.category(), TypeCategory::Label);
+    assert_eq!(Type::X86_MMXType.category(), TypeCategory::Other);
+}
+
+#[test]
+fn contexts() {
+    init_logging();
+    for _ in 0..100 {
+        let context = Context::new();
+        assert!(!context.as_raw().is_null());
+    }
+    let a = Context::default();
+    let b = Context::default();
+    assert_ne!(a.as_raw(), b.as_raw());
+}
+
+#[test]
+fn signed_and_unsigned_ints() {
+    init_logging();
+    let byte = Constant::Int { bits: 8, value: 255 };
+    assert_eq!(byte.as_signed(), Some(-1));
+    assert_eq!(byte.as_unsigned(), Some(255));
+
+    let small = Constant::Int { bits: 8, value: 127 };
+    assert_eq!(small.as_signed(), Some(127));
+    assert_eq!(small.as_unsigned(), Some(127));
+
+    let bool_true = Constant::Int { bits: 1, value: 1 };
+    assert_eq!(bool_true.as_signed(), Some(-1));
+    assert_eq!(bool_true.as_unsigned(), Some(1));
+
+    let max = Constant::Int { bits: 64, value: u64::MAX };
+    assert_eq!(max.as_signed(), Some(-1));
+    assert_eq!(max.as_unsigned(), Some(u128::from(u64::MAX)));
+
+    let i33 = Constant::Int { bits: 33, value: 1 << 32 };
+    assert_eq!(i33.as_signed(), Some(-(1 << 32)));
+    assert_eq!(i33.as_unsigned(), Some(1 << 32));
+
+    assert_eq!(Constant::Int { bits: 128, value: 1 }.as_signed(), None);
+    assert_eq!(Constant::Int { bits: 128, value: 1 }.as_unsigned(), None);
+    assert_eq!(Constant::Float(constant::Float::Double(1.0)).as_signed(), None);
+}
+
+#[test]
+fn type_display() {
+    let named = |name: &str| Type::NamedStructType { name: name.to_owned(), ty: None };
+    assert_eq!(Type::i32().to_string(), "i32");
+    assert_eq!(Type::double().to_string(), "double");
+    assert_eq!(Type::FPType(FPType::X86_FP80).to_string(), "x86_fp80");
+    assert_eq!(named("struct.Foo").to_string(), "%struct.Foo");
+    assert_eq!(named("class.std::vector").to_string(), "%\"class.std::vector\"");
+    assert_eq!(Type::array(Type::i8(), 4).unwrap().to_string(), "[4 x i8]");
+    assert_eq!(Type::vector(Type::single(), 4).unwrap().to_string(), "<4 x float>");
+    let struct_ty = Type::StructType { element_types: vec![Type::i32(), Type::pointer_to(Type::i8())], is_packed: false };
+    assert_eq!(struct_ty.to_string(), "{ i32, i8* }");
+    let packed = Type::StructType { element_types: vec![Type::i8(), named("struct.Foo")], is_packed: true };
+    assert_eq!(packed.to_string(), "<{ i8, %struct.Foo }>");
+    assert_eq!(Type::StructType { element_types: vec![], is_packed: false }.to_string(), "{}");
+    let func_ty = Type::FuncType { result_type: Box::new(Type::VoidType), param_types: vec![Type::i32()], is_var_arg: false };
+    assert_eq!(Type::pointer_to(func_ty).to_string(), "void (i32)*");
+    let printf_ty = Type::FuncType { result_type: Box::new(Type::i32()), param_types: vec![Type::pointer_to(Type::i8())], is_var_arg: true };
+    assert_eq!(printf_ty.to_string(), "i32 (i8*, ...)");
+    let global_ptr = Type::PointerType { pointee_type: Box::new(Type::i32()), addr_space: 1 };
+    assert_eq!(global_ptr.to_string(), "i32 addrspace(1)*");
+}
+
+#[test]
+fn fold_comparisons() {
+    let minus_one = Constant::Int { bits: 32, value: 0xFFFF_FFFF };
+    let zero = Constant::Int { bits: 32, value: 0 };
+    assert_eq!(Constant::fold_icmp(IntPredicate::SLT, &minus_one, &zero), Some(true));
+    assert_eq!(Constant::fold_icmp(IntPredicate::ULT, &minus_one, &zero), Some(false));
+    assert_eq!(Constant::fold_icmp(IntPredicate::NE, &minus_one, &zero), Some(true));
+    assert_eq!(Constant::fold_icmp(IntPredicate::SGE, &zero, &zero), Some(true));
+    assert_eq!(Constant::fold_icmp(IntPredicate::EQ, &zero, &Constant::Int { bits: 64, value: 0 }), None);
+    assert_eq!(Constant::fold_icmp(IntPredicate::SLT, &Constant::Int { bits: 1, value: 1 }, &Constant::Int { bits: 1, value: 0 }), Some(true));
+
+    let nan = Constant::Float(constant::Float::Double(f64::NAN));
+    let fzero = Constant::Float(constant::Float::Double(0.0));
+    let one = Constant::Float(constant::Float::Double(1.0));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::UNO, &nan, &fzero), Some(true));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::ORD, &nan, &fzero), Some(false));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::OEQ, &nan, &nan), Some(false));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::UEQ, &nan, &fzero), Some(true));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::OLT, &fzero, &one), Some(true));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::UGT, &fzero, &one), Some(false));
+    assert_eq!(Constant::fold_fcmp(FPPredicate::OEQ, &fzero, &Constant::Float(constant::Float::Single(0.0))), None);
+}
+
+#[test]
+fn checked_type_constructors() {
+    assert_eq!(Type::int(0), Err(TypeError::InvalidIntegerWidth(0)));
+    assert_eq!(Type::int(128), Ok(Type::IntegerType { bits: 128 }));
+    assert_eq!(Type::int(Type::MAX_INT_BITS), Ok(Type::IntegerType { bits: Type::MAX_INT_BITS }));
+    assert_eq!(Type::int(1 << 23), Err(TypeError::InvalidIntegerWidth(1 << 23)));
+
+    assert_eq!(Type::array(Type::i8(), 0), Ok(Type::ArrayType { element_type: Box::new(Type::i8()), num_elements: 0 }));
+    assert_eq!(Type::array(Type::VoidType, 4), Err(TypeError::InvalidElementType(Type::VoidType)));
+
+    assert_eq!(Type::vector(Type::i32(), 4), Ok(Type::VectorType { element_type: Box::new(Type::i32()), num_elements: 4 }));
+    assert_eq!(Type::vector(Type::i32(), 0), Err(TypeError::ZeroLengthVector));
+    let arr = Type::array(Type::i32(), 2).unwrap();
+    assert_eq!(Type::vector(arr.clone(), 2), Err(TypeError::InvalidElementType(arr)));
+}
+
+#[test]
+fn name_numbers() {
+    let name = Name::number(3);
+    assert_eq!(name, Name::Number(3));
+    assert_eq!(name.as_number(), Some(3));
+    assert_eq!(Name::number(name.as_number().unwrap()), name);
+    assert_eq!(Name::from("x").as_number(), None);
+}
+
+#[test]
+fn intrinsics() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/rust.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("_ZN4core10intrinsics19copy_nonoverlapping17h6fe3ca3ba4278673E").expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+    assert_eq!(bb.instrs[0].as_intrinsic(), None);  // not a call
+    let i8ptr = Type::pointer_to(Type::i8());
+    assert_eq!(bb.instrs[3].as_intrinsic(), Some(Intrinsic::Memcpy {
+        dest: Operand::LocalOperand { name: Name::Number(1), ty: i8ptr.clone() },
+        src: Operand::LocalOperand { name: Name::Number(2), ty: i8ptr },
+        len: Operand::LocalOperand { name: Name::Number(0), ty: Type::i64() },
+        dest_alignment: Some(8),
+        src_alignment: Some(8),
+        is_volatile: false,
+    }));
+
+    let func = module.get_func_by_name("_ZN47_$LT$isize$u20$as$u20$core..ops..arith..Add$GT$3add17h5b676053fe71193bE").expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+    assert_eq!(bb.instrs[0].as_intrinsic(), Some(Intrinsic::Other("llvm.sadd.with.overflow.i64".to_owned())));
+    match bb.instrs[3].as_intrinsic() {
+        Some(Intrinsic::Expect { expected, .. }) => assert_eq!(expected, Operand::ConstantOperand(Constant::Int { bits: 1, value: 0 })),
+        intrinsic => panic!("Expected llvm.expect, got {:?}", intrinsic),
+    }
+
+    // calls to ordinary functions are not intrinsics
+    let func = module.get_func_by_name("_ZN4rust9rust_loop17h3ed0672b8cf44eb1E").expect("Failed to find function");
+    let call = func.basic_blocks.iter().flat_map(|bb| &bb.instrs).find(|inst| matches!(inst, Instruction::Call(_))).expect("Expected a call");
+    assert_eq!(call.as_intrinsic(), None);
+}
+
+#[test]
+fn struct_layout() {
+    let dl = DataLayout::parse("e-m:e-i64:64-f80:128-n8:16:32:64-S128").expect("Failed to parse data layout");
+    let element_types = vec![Type::i8(), Type::i32(), Type::i8()];
+
+    let ty = Type::StructType { element_types: element_types.clone(), is_packed: false };
+    let layout = ty.struct_layout(&dl).expect("Failed to compute layout");
+    assert_eq!(layout.entries, vec![
+        LayoutEntry::Field { index: 0, offset: 0, size: 1, ty: Type::i8() },
+        LayoutEntry::Padding { offset: 1, size: 3 },
+        LayoutEntry::Field { index: 1, offset: 4, size: 4, ty: Type::i32() },
+        LayoutEntry::Field { index: 2, offset: 8, size: 1, ty: Type::i8() },
+        LayoutEntry::Padding { offset: 9, size: 3 },
+    ]);
+    assert_eq!(layout.size, 12);
+    assert_eq!(layout.alignment, 4);
+
+    let packed = Type::StructType { element_types, is_packed: true };
+    let layout = packed.struct_layout(&dl).expect("Failed to compute layout");
+    assert!(layout.entries.iter().all(|entry| matches!(entry, LayoutEntry::Field { .. })));
+    assert_eq!(layout.size, 6);
+    assert_eq!(layout.alignment, 1);
+
+    assert_eq!(Type::i32().struct_layout(&dl), None);
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn cache_round_trip() {
+    init_logging();
+    for path in &["tests/basic_bc/rust.bc", "tests/basic_bc/linkedlist.bc-g"] {
+        let module = Module::from_bc_path(path).expect("Failed to parse module");
+        let mut cache = Vec::new();
+        module.to_cache(&mut cache).expect("Failed to write cache");
+        let cached = Module::from_cache(&cache[..]).expect("Failed to read cache");
+        assert_eq!(cached.name, module.name);
+        assert_eq!(cached.data_layout, module.data_layout);
+        assert_eq!(cached.functions, module.functions);
+        assert_eq!(cached.global_vars, module.global_vars);
+        assert_eq!(cached.global_aliases, module.global_aliases);
+        assert_eq!(cached.metadata_kind_names, module.metadata_kind_names);
+        assert_eq!(cached.debug_types, module.debug_types);
+        assert_eq!(cached.debug_scopes, module.debug_scopes);
+        let mut names: Vec<_> = cached.named_struct_types.keys().collect();
+        names.sort();
+        let mut expected_names: Vec<_> = module.named_struct_types.keys().collect();
+        expected_names.sort();
+        assert_eq!(names, expected_names);
+        for (name, ty) in &module.named_struct_types {
+            let cached_ty = &cached.named_struct_types[name];
+            assert_eq!(
+                cached_ty.as_ref().map(|ty| ty.read().unwrap().clone()),
+                ty.as_ref().map(|ty| ty.read().unwrap().clone()),
+            );
+        }
+    }
+
+    // references to named structs point at the cached module's own struct
+    // bodies, including from within recursive structs
+    let module = Module::from_bc_path("tests/basic_bc/linkedlist.bc").expect("Failed to parse module");
+    let mut cache = Vec::new();
+    module.to_cache(&mut cache).expect("Failed to write cache");
+    let cached = Module::from_cache(&cache[..]).expect("Failed to read cache");
+    let structty = cached.named_struct_types["struct.SimpleLinkedList"].clone().expect("SimpleLinkedList should not be an opaque type");
+    let next_ty = match structty.read().unwrap().deref() {
+        Type::StructType { element_types, .. } => element_types[1].clone(),
+        ty => panic!("Expected SimpleLinkedList to be a StructType, got {:?}", ty),
+    };
+    match next_ty {
+        Type::PointerType { pointee_type, .. } => match *pointee_type {
+            Type::NamedStructType { ty: Some(weak), .. } => {
+                assert!(Arc::ptr_eq(&weak.upgrade().expect("Failed to upgrade weak ref"), &structty));
+            },
+            ty => panic!("Expected a non-opaque NamedStructType, got {:?}", ty),
+        },
+        ty => panic!("Expected a PointerType, got {:?}", ty),
+    }
+    let func = cached.get_func_by_name("simple_linked_list").expect("Failed to find function");
+    let alloca: &instruction::Alloca = &func.basic_blocks[0].instrs[1].clone().try_into().expect("Should be an alloca");
+    let dl = DataLayout::parse(&cached.data_layout).expect("Failed to parse data layout");
+    assert_eq!(alloca.allocated_type.struct_layout(&dl).map(|layout| layout.size), Some(16));
+
+    // a truncated cache is an error
+    assert!(Module::from_cache(&cache[.. cache.len() / 2]).is_err());
+    assert!(Module::from_cache(&[][..]).is_err());
+}
@@ -0,0 +1,21 @@
+; a GPU-flavored module using the generic (0), global (1), and shared (3)
+; address spaces
+source_filename = "addrspaces.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@tile = internal addrspace(3) global [64 x float] undef
+
+define void @scale(float addrspace(1)* %out, float* %in, i64 %i) {
+  %src = getelementptr float, float* %in, i64 %i
+  %x = load float, float* %src
+  %slot = getelementptr [64 x float], [64 x float] addrspace(3)* @tile, i64 0, i64 %i
+  store float %x, float addrspace(3)* %slot
+  %dst = getelementptr float, float addrspace(1)* %out, i64 %i
+  store float %x, float addrspace(1)* %dst
+  ret void
+}
+
+define i32 @host(i32 %x) {
+  ret i32 %x
+}
@@ -0,0 +1,30 @@
+; extractvalue from literal and named struct types
+source_filename = "aggregates.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%struct.inner = type { i8, i16 }
+%struct.outer = type { i32, %struct.inner, [4 x %struct.inner] }
+
+define i8 @literal({ i32, { i8, i16 } } %agg) {
+  %1 = extractvalue { i32, { i8, i16 } } %agg, 1, 0
+  ret i8 %1
+}
+
+define i16 @named(%struct.outer %agg) {
+  %1 = extractvalue %struct.outer %agg, 2, 3, 1
+  %2 = extractvalue %struct.outer %agg, 1
+  ret i16 %1
+}
+
+@outer = global %struct.outer zeroinitializer
+
+define i16* @gep_named(%struct.outer* %p) {
+  %1 = getelementptr %struct.outer, %struct.outer* %p, i64 0, i32 2, i64 3, i32 1
+  ret i16* %1
+}
+
+define i8 @load_const_gep() {
+  %1 = load i8, i8* getelementptr (%struct.outer, %struct.outer* @outer, i64 0, i32 1, i32 0)
+  ret i8 %1
+}
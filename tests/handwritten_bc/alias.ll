@@ -0,0 +1,22 @@
+; pointers derived from allocas, globals, and parameters
+source_filename = "alias.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@g = global [4 x i32] zeroinitializer
+@h = global i32 0
+@g_alias = alias [4 x i32], [4 x i32]* @g
+
+define void @pointers(i32* %p, i64 %i) {
+  %a = alloca [4 x i32]
+  %b = alloca i32
+  %a_elem = getelementptr [4 x i32], [4 x i32]* %a, i64 0, i64 %i
+  %a_first = getelementptr [4 x i32], [4 x i32]* %a, i64 0, i64 0
+  %a_cast = bitcast [4 x i32]* %a to i8*
+  %g_elem = getelementptr [4 x i32], [4 x i32]* @g, i64 0, i64 %i
+  %g_cast = bitcast [4 x i32]* @g to i32*
+  %h_cast = bitcast i32* @h to i8*
+  %g_alias_cast = bitcast [4 x i32]* @g_alias to i32*
+  %p_elem = getelementptr i32, i32* %p, i64 %i
+  ret void
+}
@@ -0,0 +1,28 @@
+; functions with static and dynamically-sized allocas
+source_filename = "allocas.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%struct.pair = type { i8, i64 }
+
+define void @static() {
+  %buf = alloca [64 x i8]
+  %x = alloca i32
+  ret void
+}
+
+define void @arrays() {
+  %xs = alloca i32, i32 10
+  %pairs = alloca %struct.pair, i64 2
+  ret void
+}
+
+define void @dynamic(i32 %n) {
+  %x = alloca i32
+  %buf = alloca i8, i32 %n
+  ret void
+}
+
+define void @none() {
+  ret void
+}
@@ -0,0 +1,23 @@
+; a small arithmetic function, for lowering to three-address code
+source_filename = "arith.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@limit = global i32 100
+
+declare i32 @clamp(i32)
+
+define i32 @arith(i32 %a, i32 %b) {
+entry:
+  %sum = add i32 %a, %b
+  %prod = mul i32 %sum, 3
+  %max = load i32, i32* @limit
+  %cmp = icmp slt i32 %prod, %max
+  br i1 %cmp, label %small, label %big
+small:
+  ret i32 %prod
+big:
+  %clamped = call i32 @clamp(i32 %prod)
+  store i32 %clamped, i32* @limit
+  ret i32 %clamped
+}
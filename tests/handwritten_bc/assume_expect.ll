@@ -0,0 +1,22 @@
+; llvm.assume and llvm.expect
+source_filename = "assume_expect.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i64 @hints(i64 %x) {
+entry:
+  %c = icmp ult i64 %x, 100
+  call void @llvm.assume(i1 %c)
+  %e = call i64 @llvm.expect.i64(i64 %x, i64 42)
+  %b = icmp eq i64 %e, 42
+  br i1 %b, label %likely, label %unlikely
+
+likely:
+  ret i64 0
+
+unlikely:
+  ret i64 %x
+}
+
+declare void @llvm.assume(i1)
+declare i64 @llvm.expect.i64(i64, i64)
@@ -0,0 +1,12 @@
+; atomicrmw with integer and floating-point operations
+source_filename = "atomicrmw.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define float @rmw(i32* %i, float* %f) {
+  %old.add = atomicrmw add i32* %i, i32 1 seq_cst
+  %old.umin = atomicrmw volatile umin i32* %i, i32 7 monotonic
+  %old.fadd = atomicrmw fadd float* %f, float 1.0 seq_cst
+  %old.fsub = atomicrmw volatile fsub float* %f, float 2.0 syncscope("singlethread") acquire
+  ret float %old.fsub
+}
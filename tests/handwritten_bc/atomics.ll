@@ -0,0 +1,13 @@
+; plain and atomic loads and stores
+source_filename = "atomics.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @atomics(i32* %p) {
+  %plain = load i32, i32* %p, align 4
+  %acq = load atomic i32, i32* %p acquire, align 4
+  store i32 %plain, i32* %p, align 4
+  store atomic i32 %acq, i32* %p syncscope("singlethread") release, align 4
+  %both = load atomic volatile i32, i32* %p seq_cst, align 4
+  ret i32 %both
+}
@@ -0,0 +1,18 @@
+; block addresses of a named and an unnamed block
+source_filename = "blockaddress.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@table = global [2 x i8*] [i8* blockaddress(@pick, %a), i8* blockaddress(@pick, %0)]
+
+define i32 @pick(i1 %c) {
+entry:
+  %addr = select i1 %c, i8* blockaddress(@pick, %a), i8* blockaddress(@pick, %0)
+  indirectbr i8* %addr, [label %a, label %0]
+
+a:
+  ret i32 1
+
+0:
+  ret i32 2
+}
@@ -0,0 +1,26 @@
+; calls and invokes with operand bundles
+source_filename = "bundles.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare void @callee(i32)
+declare i32 @__gxx_personality_v0(...)
+
+define void @deopt(i32 %x) {
+  call void @callee(i32 %x) [ "deopt"(i32 %x, i32 7) ]
+  call void @callee(i32 %x) [ "deopt"(<2 x i32> <i32 1, i32 2>, { i32, i32 } { i32 3, i32 4 }), "empty"(), "gc-live"(i32 %x) ]
+  call void @callee(i32 %x)
+  ret void
+}
+
+define void @invokes(i32 %x) personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @callee(i32 %x) [ "deopt"(i32 %x) ] to label %cont unwind label %lpad
+
+cont:
+  ret void
+
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
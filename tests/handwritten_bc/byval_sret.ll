@@ -0,0 +1,25 @@
+; parameters and call arguments passed with byval and sret.
+;
+; LLVM 9 writes these attributes without a type (`byval`, `sret`) and infers
+; the type from the pointee type. LLVM 12 and later require the explicit
+; `sret(<ty>)` form, which LLVM 9 doesn't accept, so this file can't be
+; assembled with LLVM 9's llvm-as: byval_sret.bc was produced with `llvm-as`
+; from LLVM 14 (`make byval_sret.bc`). For LLVM 9, remove the `(%Big)` from
+; each attribute.
+source_filename = "byval_sret.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%Big = type { i64, i64, i64 }
+
+define void @f(%Big* noalias sret(%Big) %out, %Big* byval(%Big) align 8 %in, i64* %plain) {
+  %v = load %Big, %Big* %in
+  store %Big %v, %Big* %out
+  ret void
+}
+
+define void @caller(%Big* %p) {
+  %r = alloca %Big
+  call void @f(%Big* sret(%Big) %r, %Big* byval(%Big) %p, i64* null)
+  ret void
+}
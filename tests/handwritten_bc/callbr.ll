@@ -0,0 +1,22 @@
+; an `asm goto` with two indirect labels
+source_filename = "callbr.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @asm_goto(i32 %x) {
+entry:
+  br label %asm
+
+asm:
+  callbr void asm "", "r,X,X"(i32 %x, i8* blockaddress(@asm_goto, %fail), i8* blockaddress(@asm_goto, %retry))
+          to label %normal [label %fail, label %retry]
+
+normal:
+  ret i32 1
+
+fail:
+  ret i32 0
+
+retry:
+  br label %asm
+}
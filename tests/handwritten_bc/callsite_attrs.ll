@@ -0,0 +1,23 @@
+; attributes on the arguments of call sites, which may differ from the
+; attributes declared on the callee's parameters
+source_filename = "callsite_attrs.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare void @use(i8*, i32)
+declare void @read(i8* readonly)
+declare i32 @__gxx_personality_v0(...)
+
+define void @caller(i8* %p) personality i32 (...)* @__gxx_personality_v0 {
+entry:
+  call void @use(i8* nocapture %p, i32 zeroext 3)
+  call void @read(i8* %p)
+  invoke void @use(i8* nonnull %p, i32 0)
+          to label %cont unwind label %lpad
+cont:
+  ret void
+lpad:
+  %lp = landingpad { i8*, i32 }
+          cleanup
+  resume { i8*, i32 } %lp
+}
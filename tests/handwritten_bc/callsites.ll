@@ -0,0 +1,36 @@
+; direct calls, indirect calls, calls through aliases, and invokes
+source_filename = "callsites.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@callee_alias = alias i32 (i32), i32 (i32)* @callee
+@alias_of_alias = alias i32 (i32), i32 (i32)* @callee_alias
+
+define i32 @callee(i32 %x) {
+  %1 = add i32 %x, 1
+  ret i32 %1
+}
+
+declare void @external()
+
+declare i32 @__gxx_personality_v0(...)
+
+define i32 @caller(i32 (i32)* %fptr, i32 %x) {
+  %1 = call i32 @callee(i32 %x)
+  %2 = call i32 %fptr(i32 %1)
+  %3 = call i32 @alias_of_alias(i32 %2)
+  call void @external()
+  ret i32 %3
+}
+
+define void @invoker() personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @external() to label %cont unwind label %lpad
+
+cont:
+  ret void
+
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
@@ -0,0 +1,20 @@
+; the same functions and globals as canonical_b.ll, in a different order and
+; without debug info
+source_filename = "canonical.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@counter = global i32 0
+@limit = constant i32 10
+
+define i32 @add(i32, i32) {
+  %3 = add i32 %0, %1
+  ret i32 %3
+}
+
+define void @bump() {
+  %1 = load i32, i32* @counter
+  %2 = call i32 @add(i32 %1, i32 1)
+  store i32 %2, i32* @counter
+  ret void
+}
@@ -0,0 +1,42 @@
+; the same functions and globals as canonical_a.ll, in a different order and
+; with debug info
+source_filename = "canonical.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@limit = constant i32 10
+@counter = global i32 0
+
+define void @bump() !dbg !10 {
+  %1 = load i32, i32* @counter, !dbg !12
+  %2 = call i32 @add(i32 %1, i32 1), !dbg !12
+  store i32 %2, i32* @counter, !dbg !12
+  ret void, !dbg !13
+}
+
+define i32 @add(i32, i32) !dbg !7 {
+  call void @llvm.dbg.value(metadata i32 %0, metadata !14, metadata !DIExpression()), !dbg !15
+  %3 = add i32 %0, %1, !dbg !15
+  ret i32 %3, !dbg !15
+}
+
+declare void @llvm.dbg.value(metadata, metadata, metadata)
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "handwritten", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+!1 = !DIFile(filename: "canonical.c", directory: "/tmp")
+!2 = !{}
+!3 = !{i32 2, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!5 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!6 = !{!5, !5, !5}
+!7 = distinct !DISubprogram(name: "add", scope: !1, file: !1, line: 1, type: !8, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0, retainedNodes: !2)
+!8 = !DISubroutineType(types: !6)
+!9 = !DISubroutineType(types: !{null})
+!10 = distinct !DISubprogram(name: "bump", scope: !1, file: !1, line: 2, type: !9, scopeLine: 2, spFlags: DISPFlagDefinition, unit: !0, retainedNodes: !2)
+!12 = !DILocation(line: 2, column: 20, scope: !10)
+!13 = !DILocation(line: 2, column: 40, scope: !10)
+!14 = !DILocalVariable(name: "a", arg: 1, scope: !7, file: !1, line: 1, type: !5)
+!15 = !DILocation(line: 1, column: 30, scope: !7)
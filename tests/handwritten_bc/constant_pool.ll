@@ -0,0 +1,15 @@
+; constants used in several places
+source_filename = "constant_pool.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@answer = global i32 42
+@pair = global [2 x i32] [i32 42, i32 7]
+@ptr = global i8* bitcast (i32* @answer to i8*)
+
+define i32 @f(i32 %x) {
+  %y = add i32 %x, 42
+  %z = mul i32 %y, 7
+  store i32 %z, i32* @answer
+  ret i32 %z
+}
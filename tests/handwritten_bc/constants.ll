@@ -0,0 +1,34 @@
+; global initializers exercising many kinds of constants
+source_filename = "constants.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%struct.pair = type { i32, i8* }
+%"struct.odd name" = type { i8 }
+
+@i = global i32 -7
+@big = global i64 -9223372036854775808
+@flag = global i1 true
+@f = global float 1.500000e+00
+@d = global double 1.000000e-01
+@negzero = global double -0.000000e+00
+@null = global i8* null
+@zero = global [4 x i32] zeroinitializer
+@undef = global i16 undef
+@str = global [7 x i8] c"hi \22x\22\00"
+@arr = global [3 x i32] [i32 1, i32 2, i32 3]
+@vec = global <2 x i64> <i64 1, i64 -1>
+@pair = global %struct.pair { i32 5, i8* getelementptr inbounds ([7 x i8], [7 x i8]* @str, i64 0, i64 1) }
+@literal = global { i32, i8* } { i32 1, i8* null }
+@packed = global <{ i8, i32 }> <{ i8 1, i32 2 }>
+@odd = global %"struct.odd name" { i8 3 }
+@"quoted global" = global i32 0
+@cast = global i64 ptrtoint (i32* @"quoted global" to i64)
+@sum = global i64 add (i64 ptrtoint (i32* @i to i64), i64 4)
+@cmp = global i1 icmp ult (i32* @i, i32* @"quoted global")
+@sel = global i32 select (i1 icmp ult (i32* @i, i32* @"quoted global"), i32 1, i32 2)
+@nested = global [2 x %struct.pair] [%struct.pair { i32 1, i8* bitcast (i32* @i to i8*) }, %struct.pair zeroinitializer]
+@half = global half 0xH3C00
+@quad = global fp128 0xL0123456789ABCDEF3FFF000000000001
+@x87 = global x86_fp80 0xK4000C90FDAA22168C000
+@ppc = global ppc_fp128 0xM3FF00000000000000000000000000001
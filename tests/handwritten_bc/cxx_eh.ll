@@ -0,0 +1,25 @@
+; a C++-style function with a personality function and unwinding-related attributes
+source_filename = "cxx_eh.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare void @may_throw()
+declare i32 @__gxx_personality_v0(...)
+
+define void @catches() #0 personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @may_throw() to label %cont unwind label %lpad
+
+cont:
+  ret void
+
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
+
+define void @no_eh() {
+  ret void
+}
+
+attributes #0 = { noinline uwtable "frame-pointer"="all" }
@@ -0,0 +1,39 @@
+; calls to llvm.dbg.declare, llvm.dbg.value, and llvm.dbg.label
+source_filename = "dbg_intrinsics.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @labeled(i32 %x) !dbg !7 {
+entry:
+  %x.addr = alloca i32
+  call void @llvm.dbg.declare(metadata i32* %x.addr, metadata !11, metadata !DIExpression()), !dbg !13
+  store i32 %x, i32* %x.addr, !dbg !13
+  br label %out
+
+out:
+  call void @llvm.dbg.label(metadata !12), !dbg !14
+  %y = add i32 %x, 1, !dbg !14
+  call void @llvm.dbg.value(metadata i32 %y, metadata !11, metadata !DIExpression()), !dbg !14
+  ret i32 %y, !dbg !14
+}
+
+declare void @llvm.dbg.declare(metadata, metadata, metadata)
+declare void @llvm.dbg.value(metadata, metadata, metadata)
+declare void @llvm.dbg.label(metadata)
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "clang version 9.0.0", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+!1 = !DIFile(filename: "dbg_intrinsics.c", directory: "/tmp")
+!2 = !{}
+!3 = !{i32 2, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!5 = !DISubroutineType(types: !6)
+!6 = !{!10, !10}
+!7 = distinct !DISubprogram(name: "labeled", scope: !1, file: !1, line: 1, type: !5, scopeLine: 1, flags: DIFlagPrototyped, spFlags: DISPFlagDefinition, unit: !0, retainedNodes: !2)
+!10 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!11 = !DILocalVariable(name: "x", arg: 1, scope: !7, file: !1, line: 1, type: !10)
+!12 = !DILabel(scope: !7, name: "out", file: !1, line: 3)
+!13 = !DILocation(line: 1, column: 17, scope: !7)
+!14 = !DILocation(line: 3, column: 1, scope: !7)
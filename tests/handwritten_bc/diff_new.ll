@@ -0,0 +1,30 @@
+; The "new" version of a module, to be diffed against diff_old.ll
+
+@counter = global i32 0
+@limit = global i32 20
+@added_var = global i32 5
+
+define i32 @unchanged(i32 %x) {
+entry:
+  %y = add i32 %x, 1
+  ret i32 %y
+}
+
+define i32 @modified(i32 %x) {
+entry:
+  %cmp = icmp sgt i32 %x, 0
+  br i1 %cmp, label %pos, label %exit
+pos:
+  %dbl = mul i32 %x, 2
+  %inc = add i32 %dbl, 1
+  br label %exit
+exit:
+  %r = phi i32 [ %x, %entry ], [ %inc, %pos ]
+  ret i32 %r
+}
+
+define i32 @added(i32 %x) {
+entry:
+  %r = call i32 @modified(i32 %x)
+  ret i32 %r
+}
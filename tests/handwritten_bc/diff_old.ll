@@ -0,0 +1,27 @@
+; The "old" version of a module, to be diffed against diff_new.ll
+
+@counter = global i32 0
+@limit = global i32 10
+
+define i32 @unchanged(i32 %x) {
+entry:
+  %y = add i32 %x, 1
+  ret i32 %y
+}
+
+define i32 @modified(i32 %x) {
+entry:
+  %cmp = icmp sgt i32 %x, 0
+  br i1 %cmp, label %pos, label %exit
+pos:
+  %dbl = mul i32 %x, 2
+  br label %exit
+exit:
+  %r = phi i32 [ %x, %entry ], [ %dbl, %pos ]
+  ret i32 %r
+}
+
+define void @removed() {
+entry:
+  ret void
+}
@@ -0,0 +1,48 @@
+; debug metadata for the C source
+;   struct pair { int count; char *name; };
+;   typedef struct pair pair_t;
+;   void locals(void) { pair_t p; const int arr[4]; }
+source_filename = "ditypes.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%struct.pair = type { i32, i8* }
+
+define void @locals() !dbg !7 {
+  %1 = alloca %struct.pair, align 8
+  %2 = alloca [4 x i32], align 16
+  call void @llvm.dbg.declare(metadata %struct.pair* %1, metadata !10, metadata !DIExpression()), !dbg !21
+  call void @llvm.dbg.declare(metadata [4 x i32]* %2, metadata !22, metadata !DIExpression()), !dbg !27
+  ret void, !dbg !28
+}
+
+declare void @llvm.dbg.declare(metadata, metadata, metadata)
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "handwritten", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+!1 = !DIFile(filename: "ditypes.c", directory: "/tmp")
+!2 = !{}
+!3 = !{i32 2, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!7 = distinct !DISubprogram(name: "locals", scope: !1, file: !1, line: 4, type: !8, scopeLine: 4, spFlags: DISPFlagDefinition, unit: !0, retainedNodes: !2)
+!8 = !DISubroutineType(types: !9)
+!9 = !{null}
+!10 = !DILocalVariable(name: "p", scope: !7, file: !1, line: 4, type: !11)
+!11 = !DIDerivedType(tag: DW_TAG_typedef, name: "pair_t", file: !1, line: 2, baseType: !12)
+!12 = distinct !DICompositeType(tag: DW_TAG_structure_type, name: "pair", file: !1, line: 1, size: 128, elements: !13)
+!13 = !{!14, !16}
+!14 = !DIDerivedType(tag: DW_TAG_member, name: "count", scope: !12, file: !1, line: 1, baseType: !15, size: 32)
+!15 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!16 = !DIDerivedType(tag: DW_TAG_member, name: "name", scope: !12, file: !1, line: 1, baseType: !17, size: 64, offset: 64)
+!17 = !DIDerivedType(tag: DW_TAG_pointer_type, baseType: !18, size: 64)
+!18 = !DIBasicType(name: "char", size: 8, encoding: DW_ATE_signed_char)
+!21 = !DILocation(line: 4, column: 28, scope: !7)
+!22 = !DILocalVariable(name: "arr", scope: !7, file: !1, line: 4, type: !23)
+!23 = !DICompositeType(tag: DW_TAG_array_type, baseType: !24, size: 128, elements: !25)
+!24 = !DIDerivedType(tag: DW_TAG_const_type, baseType: !15)
+!25 = !{!26}
+!26 = !DISubrange(count: 4)
+!27 = !DILocation(line: 4, column: 41, scope: !7)
+!28 = !DILocation(line: 4, column: 47, scope: !7)
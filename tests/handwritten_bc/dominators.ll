@@ -0,0 +1,42 @@
+; an if/else diamond followed by a loop containing another diamond
+source_filename = "dominators.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @diamonds(i1 %c, i32 %n) {
+entry:
+  br i1 %c, label %then, label %else
+
+then:
+  br label %merge
+
+else:
+  br label %merge
+
+merge:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %merge ], [ %next, %latch ]
+  %done = icmp eq i32 %i, %n
+  br i1 %done, label %exit, label %body
+
+body:
+  br i1 %c, label %left, label %right
+
+left:
+  br label %latch
+
+right:
+  br label %latch
+
+latch:
+  %next = add i32 %i, 1
+  br label %header
+
+exit:
+  ret i32 %i
+
+dead:
+  br label %merge
+}
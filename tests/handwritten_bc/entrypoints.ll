@@ -0,0 +1,55 @@
+; a module with various kinds of entry points
+source_filename = "entrypoints.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@llvm.global_ctors = appending global [2 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 200, void ()* @late_ctor, i8* null }, { i32, void ()*, i8* } { i32 101, void ()* @early_ctor, i8* bitcast (i32* @ctor_data to i8*) }]
+@llvm.global_dtors = appending global [1 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 65535, void ()* @dtor, i8* null }]
+@llvm.used = appending global [2 x i8*] [i8* bitcast (void ()* @kept to i8*), i8* bitcast (i32* @ctor_data to i8*)], section "llvm.metadata"
+@llvm.compiler.used = appending global [1 x i8*] [i8* bitcast (void ()* @compiler_kept to i8*)], section "llvm.metadata"
+@ctor_data = internal global i32 0
+
+define internal void @early_ctor() {
+  ret void
+}
+
+define internal void @late_ctor() {
+  ret void
+}
+
+define internal void @dtor() {
+  ret void
+}
+
+define internal void @kept() {
+  ret void
+}
+
+define internal void @compiler_kept() {
+  ret void
+}
+
+define internal i32 @helper(i32 %x) {
+  %1 = call i32 @helper(i32 %x)
+  ret i32 %1
+}
+
+define i32 @api(i32 %x) {
+  %1 = call i32 @helper(i32 %x)
+  %2 = call i32 @api(i32 %1)
+  ret i32 %2
+}
+
+define i32 @called_externally_visible(i32 %x) {
+  ret i32 %x
+}
+
+define i32 @main() {
+  %1 = call i32 @called_externally_visible(i32 0)
+  %2 = call i32 @main()
+  ret i32 %1
+}
+
+define internal void @unused() {
+  ret void
+}
@@ -0,0 +1,16 @@
+; functions with and without a garbage collector strategy
+source_filename = "gc.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define void @statepoint() gc "statepoint-example" {
+  ret void
+}
+
+define void @shadow_stack() gc "shadow-stack" {
+  ret void
+}
+
+define void @no_gc() {
+  ret void
+}
@@ -0,0 +1,28 @@
+; getelementptrs with constant and non-constant indices, some of which are out of bounds
+source_filename = "gep_bounds.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%pair = type { i8, i32 }
+
+@arr = global [4 x i32] zeroinitializer
+@matrix = global [2 x [4 x i32]] zeroinitializer
+@pairs = global [2 x %pair] zeroinitializer
+
+define void @geps(i64 %i) {
+  %in = getelementptr inbounds [4 x i32], [4 x i32]* @arr, i64 0, i64 3
+  %out = getelementptr [4 x i32], [4 x i32]* @arr, i64 0, i64 4
+  %neg = getelementptr [4 x i32], [4 x i32]* @arr, i64 0, i64 -1
+  %flat = getelementptr [2 x [4 x i32]], [2 x [4 x i32]]* @matrix, i64 0, i64 0, i64 5
+  %field = getelementptr [2 x %pair], [2 x %pair]* @pairs, i64 0, i64 1, i32 1
+  %past = getelementptr [2 x %pair], [2 x %pair]* @pairs, i64 1, i64 0, i32 0
+  %dyn = getelementptr [4 x i32], [4 x i32]* @arr, i64 0, i64 %i
+  store i32 0, i32* %in
+  store i32 0, i32* %out
+  store i32 0, i32* %neg
+  store i32 0, i32* %flat
+  store i32 0, i32* %field
+  store i8 0, i8* %past
+  store i32 0, i32* %dyn
+  ret void
+}
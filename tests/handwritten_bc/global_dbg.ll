@@ -0,0 +1,28 @@
+; global variables with debug info, as for
+;   int counter = 3;
+;   namespace ns { const char *greeting = "hi"; }
+source_filename = "global_dbg.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@.str = private unnamed_addr constant [3 x i8] c"hi\00", align 1
+@counter = global i32 3, align 4, !dbg !0
+@_ZN2ns8greetingE = global i8* getelementptr inbounds ([3 x i8], [3 x i8]* @.str, i32 0, i32 0), align 8, !dbg !6
+
+!llvm.dbg.cu = !{!2}
+!llvm.module.flags = !{!13, !14}
+
+!0 = !DIGlobalVariableExpression(var: !1, expr: !DIExpression())
+!1 = distinct !DIGlobalVariable(name: "counter", scope: !2, file: !3, line: 1, type: !5, isLocal: false, isDefinition: true)
+!2 = distinct !DICompileUnit(language: DW_LANG_C_plus_plus, file: !3, producer: "clang", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, globals: !4)
+!3 = !DIFile(filename: "globals.cpp", directory: "/tmp")
+!4 = !{!0, !6}
+!5 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!6 = !DIGlobalVariableExpression(var: !7, expr: !DIExpression())
+!7 = distinct !DIGlobalVariable(name: "greeting", linkageName: "_ZN2ns8greetingE", scope: !8, file: !3, line: 2, type: !9, isLocal: false, isDefinition: true)
+!8 = !DINamespace(name: "ns", scope: null)
+!9 = !DIDerivedType(tag: DW_TAG_pointer_type, baseType: !10, size: 64)
+!10 = !DIDerivedType(tag: DW_TAG_const_type, baseType: !11)
+!11 = !DIBasicType(name: "char", size: 8, encoding: DW_ATE_signed_char)
+!13 = !{i32 2, !"Debug Info Version", i32 3}
+!14 = !{i32 7, !"Dwarf Version", i32 4}
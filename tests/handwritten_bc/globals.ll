@@ -0,0 +1,10 @@
+; global variables with various flags
+source_filename = "globals.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@filled_in_by_loader = externally_initialized global i32 0
+@ordinary = global i32 0
+@constant = constant i32 1
+@declared = external global i32
+@declared_constant = external constant [4 x i8]
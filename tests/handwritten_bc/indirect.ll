@@ -0,0 +1,42 @@
+; address-taken functions of various types, and an indirect call
+source_filename = "indirect.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@table = global [2 x void (i32)*] [void (i32)* @in_table, void (i32)* @also_in_table]
+@erased = global i8* bitcast (i32 (i32)* @returns_i32 to i8*)
+
+define void @in_table(i32 %x) {
+  ret void
+}
+
+define void @stored(i32 %x) {
+  ret void
+}
+
+define void @only_called(i32 %x) {
+  ret void
+}
+
+define void @takes_i64(i64 %x) {
+  ret void
+}
+
+define i32 @returns_i32(i32 %x) {
+  ret i32 %x
+}
+
+define void @also_in_table(i32 %x) {
+  ret void
+}
+
+define void @caller(void (i32)** %slot, void (i64)* %other) {
+  store void (i32)* @stored, void (i32)** %slot
+  call void @only_called(i32 0)
+  call void @use_i64_fn(void (i64)* @takes_i64)
+  %fp = load void (i32)*, void (i32)** %slot
+  call void %fp(i32 1)
+  ret void
+}
+
+declare void @use_i64_fn(void (i64)*)
@@ -0,0 +1,13 @@
+; an indirect branch with two possible destinations
+source_filename = "indirectbr.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @dispatch(i8* %addr) {
+entry:
+  indirectbr i8* %addr, [label %a, label %b]
+a:
+  ret i32 1
+b:
+  ret i32 2
+}
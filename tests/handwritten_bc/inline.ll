@@ -0,0 +1,34 @@
+; debug locations in inlined code, as produced at -O2 -g from
+;
+;   static int leaf(int x) { return x * 2; }
+;   static int inner(int x) { if (x) { return leaf(x) + 1; } return 0; }
+;   int outer(int x) { return inner(x); }
+source_filename = "inline.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @outer(i32 %x) !dbg !7 {
+  %1 = shl i32 %x, 1, !dbg !13
+  %2 = add i32 %1, 1, !dbg !16
+  ret i32 %2, !dbg !18
+}
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "clang version 9.0.0", isOptimized: true, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+!1 = !DIFile(filename: "inline.c", directory: "/tmp")
+!2 = !{}
+!3 = !{i32 2, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!5 = !DISubroutineType(types: !6)
+!6 = !{null}
+!7 = distinct !DISubprogram(name: "outer", scope: !1, file: !1, line: 3, type: !5, scopeLine: 3, spFlags: DISPFlagDefinition | DISPFlagOptimized, unit: !0)
+!8 = distinct !DISubprogram(name: "inner", scope: !1, file: !1, line: 2, type: !5, scopeLine: 2, spFlags: DISPFlagLocalToUnit | DISPFlagDefinition | DISPFlagOptimized, unit: !0)
+!9 = distinct !DILexicalBlock(scope: !8, file: !1, line: 2, column: 35)
+!10 = distinct !DISubprogram(name: "leaf", scope: !1, file: !1, line: 1, type: !5, scopeLine: 1, spFlags: DISPFlagLocalToUnit | DISPFlagDefinition | DISPFlagOptimized, unit: !0)
+!13 = !DILocation(line: 1, column: 35, scope: !10, inlinedAt: !14)
+!14 = distinct !DILocation(line: 2, column: 44, scope: !9, inlinedAt: !15)
+!15 = distinct !DILocation(line: 3, column: 27, scope: !7)
+!16 = !DILocation(line: 2, column: 52, scope: !9, inlinedAt: !15)
+!18 = !DILocation(line: 3, column: 20, scope: !7)
@@ -0,0 +1,28 @@
+; landing pads with catch and filter clauses
+source_filename = "landingpads.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@_ZTIi = external constant i8*
+
+declare void @may_throw()
+declare i32 @__gxx_personality_v0(...)
+
+define void @clauses() personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @may_throw() to label %next unwind label %catch_int
+
+next:
+  invoke void @may_throw() to label %done unwind label %filter
+
+done:
+  ret void
+
+catch_int:
+  %lp = landingpad { i8*, i32 } cleanup catch i8* bitcast (i8** @_ZTIi to i8*)
+  resume { i8*, i32 } %lp
+
+filter:
+  %lp2 = landingpad { i8*, i32 } filter [1 x i8*] [i8* bitcast (i8** @_ZTIi to i8*)] catch i8* null
+  resume { i8*, i32 } %lp2
+}
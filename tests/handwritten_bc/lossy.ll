@@ -0,0 +1,22 @@
+; constructs which this crate can't fully decode
+source_filename = "lossy.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+$shared = comdat any
+
+@in_comdat = global i32 0, comdat($shared)
+@plain = global i32 1
+
+define void @shared() comdat {
+  ret void
+}
+
+define i64 @read_sp() {
+  %sp = call i64 @llvm.read_register.i64(metadata !0)
+  ret i64 %sp
+}
+
+declare i64 @llvm.read_register.i64(metadata)
+
+!0 = !{!"rsp"}
@@ -0,0 +1,24 @@
+; local variables in allocas, as produced by clang -O0
+source_filename = "mem2reg.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @locals(i32 %n, i1 %c) {
+entry:
+  %x = alloca i32
+  %y = alloca i32
+  %unused = alloca i32
+  store i32 %n, i32* %x
+  store i32 0, i32* %y
+  br i1 %c, label %then, label %done
+then:
+  %a = load i32, i32* %x
+  store i32 %a, i32* %y
+  br label %done
+done:
+  %b = load i32, i32* %x
+  %cast = bitcast i32* %x to i8*
+  %v = load i32, i32* %y
+  %sum = add i32 %b, %v
+  ret i32 %sum
+}
@@ -0,0 +1,50 @@
+; blocks which are only reachable after noreturn calls, or not at all
+source_filename = "noreturn.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare void @abort() noreturn
+
+define void @die() noreturn {
+  call void @abort() noreturn
+  unreachable
+}
+
+define i32 @noreturn(i32 %x) {
+entry:
+  %cond = icmp slt i32 %x, 0
+  br i1 %cond, label %fail, label %ok
+
+fail:
+  call void @abort() noreturn
+  unreachable
+
+ok:
+  %cond2 = icmp eq i32 %x, 7
+  br i1 %cond2, label %fail2, label %check3
+
+fail2:
+  call void @abort() noreturn
+  br label %after_abort
+
+after_abort:
+  ret i32 -1
+
+check3:
+  %cond3 = icmp eq i32 %x, 8
+  br i1 %cond3, label %fail3, label %done
+
+fail3:
+  ; `noreturn` is only on the declaration of @die, not on the call
+  call void @die()
+  br label %after_die
+
+after_die:
+  ret i32 -2
+
+done:
+  ret i32 %x
+
+orphan:
+  ret i32 0
+}
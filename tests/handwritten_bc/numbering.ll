@@ -0,0 +1,23 @@
+; unnamed values, including a void invoke (which llvm-dis doesn't number, but
+; which llvm-ir assigns a Name::Number)
+source_filename = "numbering.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare void @may_throw()
+declare i32 @get()
+declare i32 @__gxx_personality_v0(...)
+
+define i32 @numbered(i32, i32 %named) personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+  %2 = add i32 %0, %named
+  invoke void @may_throw() to label %3 unwind label %6
+
+3:
+  %4 = call i32 @get()
+  %5 = add i32 %4, %2
+  ret i32 %5
+
+6:
+  %7 = landingpad { i8*, i32 } cleanup
+  ret i32 0
+}
@@ -0,0 +1,8 @@
+; parameter and return attributes, each on a different position
+source_filename = "param_attrs.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define signext i8 @f(i32 zeroext %a, i8* nonnull %b, i32 %c) {
+  ret i8 0
+}
@@ -0,0 +1,19 @@
+; constant expressions which do and don't reduce to a plain global reference,
+; and aggregates referencing several globals
+source_filename = "pointer_casts.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@g = global i8 0
+@arr = global [4 x i32] zeroinitializer
+
+@bitcast = global i32* bitcast (i8* @g to i32*)
+@zero_gep = global i32* getelementptr ([4 x i32], [4 x i32]* @arr, i64 0, i64 0)
+@nested = global i32 addrspace(1)* addrspacecast (i32* bitcast (i8* @g to i32*) to i32 addrspace(1)*)
+@nonzero_gep = global i32* getelementptr ([4 x i32], [4 x i32]* @arr, i64 0, i64 1)
+@ptrtoint = global i64 ptrtoint (i8* @g to i64)
+
+@a = global i8 1
+@b = global i8 2
+@pair = global { i8*, i8* } { i8* @a, i8* @b }
+@sums = global [2 x i64] [i64 ptrtoint (i8* @b to i64), i64 add (i64 ptrtoint (i8* @a to i64), i64 ptrtoint (i8* @b to i64))]
@@ -0,0 +1,24 @@
+; functions with prefix data, prologue data, and both
+source_filename = "prefix.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@info = global i32 0
+
+define void @with_prefix() prefix i32 123 {
+  ret void
+}
+
+define void @with_prologue() prologue i8 144 {
+  ret void
+}
+
+define void @with_both() prefix i32* @info prologue { i8, i8 } { i8 235, i8 6 } personality i32 (...)* @personality {
+  ret void
+}
+
+define void @with_neither() {
+  ret void
+}
+
+declare i32 @personality(...)
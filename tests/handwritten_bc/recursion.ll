@@ -0,0 +1,65 @@
+; self-recursive, mutually recursive, and leaf functions
+source_filename = "recursion.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare void @external()
+
+define i32 @fact(i32 %n) {
+entry:
+  %done = icmp eq i32 %n, 0
+  br i1 %done, label %base, label %rec
+
+base:
+  ret i32 1
+
+rec:
+  %m = sub i32 %n, 1
+  %r = call i32 @fact(i32 %m)
+  %p = mul i32 %n, %r
+  ret i32 %p
+}
+
+define i1 @is_even(i32 %n) {
+entry:
+  %done = icmp eq i32 %n, 0
+  br i1 %done, label %base, label %rec
+
+base:
+  ret i1 true
+
+rec:
+  %m = sub i32 %n, 1
+  %r = call i1 @is_odd(i32 %m)
+  ret i1 %r
+}
+
+define i1 @is_odd(i32 %n) {
+entry:
+  %done = icmp eq i32 %n, 0
+  br i1 %done, label %base, label %rec
+
+base:
+  ret i1 false
+
+rec:
+  %m = sub i32 %n, 1
+  %r = call i1 @is_even(i32 %m)
+  ret i1 %r
+}
+
+define i32 @leaf(i32 %x) {
+  %y = add i32 %x, 1
+  ret i32 %y
+}
+
+define void @calls_external() {
+  call void @external()
+  ret void
+}
+
+define i1 @calls_others(i32 %x) {
+  %y = call i32 @leaf(i32 %x)
+  %z = call i1 @is_even(i32 %y)
+  ret i1 %z
+}
@@ -0,0 +1,19 @@
+; named struct types which are, and aren't, recursive
+source_filename = "recursive_types.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%Node = type { i32, %Node* }
+%Even = type { %Odd* }
+%Odd = type { [2 x %Even*] }
+%Pair = type { i32, i32 }
+%Wrapper = type { %Pair, %Node*, void (%Wrapper*)* }
+%Holder = type { %Node }
+%Opaque = type opaque
+
+@node = global %Node zeroinitializer
+@even = global %Even zeroinitializer
+@pair = global %Pair zeroinitializer
+@wrapper = global %Wrapper zeroinitializer
+@holder = global %Holder zeroinitializer
+@opaque = external global %Opaque
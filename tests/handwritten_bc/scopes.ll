@@ -0,0 +1,42 @@
+; debug locations in nested lexical blocks, and one without a column, as in
+;
+;   int nested(int x) {
+;     if (x) {
+;       for (;;) { return x + 1; }
+;     }
+;     return 0;
+;   }
+source_filename = "scopes.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @nested(i32 %x) !dbg !7 {
+entry:
+  %c = icmp ne i32 %x, 0, !dbg !11
+  br i1 %c, label %then, label %else, !dbg !11
+
+then:
+  %y = add i32 %x, 1, !dbg !12
+  ret i32 %y, !dbg !12
+
+else:
+  ret i32 0, !dbg !13
+}
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!3, !4}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "clang version 9.0.0", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+!1 = !DIFile(filename: "scopes.c", directory: "/tmp")
+!2 = !{}
+!3 = !{i32 2, !"Dwarf Version", i32 4}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!5 = !DISubroutineType(types: !6)
+!6 = !{null}
+!7 = distinct !DISubprogram(name: "nested", scope: !1, file: !1, line: 1, type: !5, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!8 = distinct !DILexicalBlock(scope: !7, file: !1, line: 2, column: 10)
+!9 = distinct !DILexicalBlock(scope: !8, file: !1, line: 3, column: 5)
+!10 = distinct !DILexicalBlock(scope: !9, file: !1, line: 3, column: 14)
+!11 = !DILocation(line: 2, column: 7, scope: !7)
+!12 = !DILocation(line: 3, column: 25, scope: !10)
+!13 = !DILocation(line: 5, scope: !7)
@@ -0,0 +1,9 @@
+; shufflevectors with various masks
+source_filename = "shufflevector.ll"
+
+define <3 x i32> @shuffle(<4 x i32> %a, <4 x i32> %b) {
+  %mixed = shufflevector <4 x i32> %a, <4 x i32> %b, <3 x i32> <i32 0, i32 2, i32 undef>
+  %zero = shufflevector <4 x i32> %a, <4 x i32> undef, <3 x i32> zeroinitializer
+  %hi = shufflevector <4 x i32> %a, <4 x i32> %b, <3 x i32> <i32 7, i32 6, i32 5>
+  ret <3 x i32> %mixed
+}
@@ -0,0 +1,25 @@
+; blocks to split, whose successors have phis (including a loop)
+source_filename = "split.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @loop(i32 %n) {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ]
+  %sum = phi i32 [ 0, %entry ], [ %sum2, %body ]
+  %done = icmp eq i32 %i, %n
+  br i1 %done, label %exit, label %body
+
+body:
+  %sq = mul i32 %i, %i
+  %sum2 = add i32 %sum, %sq
+  %next = add i32 %i, 1
+  br label %header
+
+exit:
+  %result = phi i32 [ %sum, %header ]
+  ret i32 %result
+}
@@ -0,0 +1,22 @@
+; a table of function pointers, and a vtable-like struct containing one
+source_filename = "tables.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@handlers = constant [4 x void ()*] [void ()* @h0, void ()* @h1, void ()* null, void ()* @h3]
+@vtable = constant { i8*, [2 x void ()*] } { i8* null, [2 x void ()*] [void ()* @h1, void ()* @h0] }
+@zeroes = global [4 x void ()*] zeroinitializer
+@scalar = global i32 7
+@external = external global [4 x void ()*]
+
+define void @h0() {
+  ret void
+}
+
+define void @h1() {
+  ret void
+}
+
+define void @h3() {
+  ret void
+}
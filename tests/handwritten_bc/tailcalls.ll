@@ -0,0 +1,19 @@
+; calls with each kind of tail-call marker
+source_filename = "tailcalls.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare i32 @callee(i32)
+
+define i32 @caller(i32 %x) {
+  %1 = call i32 @callee(i32 %x)
+  %2 = tail call i32 @callee(i32 %1)
+  %"quoted = name" = notail call i32 @callee(i32 %2)
+  %3 = musttail call i32 @callee(i32 %x)
+  ret i32 %3
+}
+
+define i32 @quoted_musttail(i32 %x) {
+  %"a = b" = musttail call i32 @callee(i32 %x)
+  ret i32 %"a = b"
+}
@@ -0,0 +1,20 @@
+; functions compiled for different targets, as with clang -mavx and
+; __attribute__((target("arch=haswell")))
+source_filename = "target_features.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define void @avx() #0 {
+  ret void
+}
+
+define void @haswell() #1 {
+  ret void
+}
+
+define void @generic() {
+  ret void
+}
+
+attributes #0 = { nounwind "target-cpu"="x86-64" "target-features"="+sse,+avx" }
+attributes #1 = { "target-cpu"="haswell" }
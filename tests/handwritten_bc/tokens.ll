@@ -0,0 +1,32 @@
+; values of token type, from coroutine intrinsics and funclet-based EH
+source_filename = "tokens.ll"
+target datalayout = "e-m:w-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-windows-msvc"
+
+declare token @llvm.coro.id(i32, i8*, i8*, i8*)
+declare i8* @llvm.coro.begin(token, i8* writeonly)
+
+define i8* @coro() {
+entry:
+  %id = call token @llvm.coro.id(i32 0, i8* null, i8* null, i8* null)
+  %hdl = call i8* @llvm.coro.begin(token %id, i8* null)
+  ret i8* %hdl
+}
+
+declare void @may_throw()
+declare i32 @__CxxFrameHandler3(...)
+
+define void @funclets() personality i8* bitcast (i32 (...)* @__CxxFrameHandler3 to i8*) {
+entry:
+  invoke void @may_throw() to label %exit unwind label %dispatch
+
+dispatch:
+  %cs = catchswitch within none [label %handler] unwind to caller
+
+handler:
+  %cp = catchpad within %cs [i8* null, i32 64, i8* null]
+  catchret from %cp to label %exit
+
+exit:
+  ret void
+}
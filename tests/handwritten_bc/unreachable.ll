@@ -0,0 +1,27 @@
+; blocks which are not reachable from the entry block, one of which flows
+; into a phi in a reachable block
+source_filename = "unreachable.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define i32 @dead_blocks(i1 %c) {
+entry:
+  br i1 %c, label %left, label %right
+
+left:
+  br label %join
+
+right:
+  br label %join
+
+dead:
+  %d = add i32 1, 2
+  br label %join
+
+dead2:
+  br label %dead
+
+join:
+  %p = phi i32 [ 1, %left ], [ 2, %right ], [ %d, %dead ]
+  ret i32 %p
+}
@@ -0,0 +1,22 @@
+; a printf-like variadic function, and a call to it passing extra arguments
+source_filename = "varargs.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+@fmt = private constant [6 x i8] c"%d %d\00"
+
+define i32 @logf(i32 %level, i8* %fmt, ...) {
+  %ap = alloca i8*
+  %ap1 = bitcast i8** %ap to i8*
+  call void @llvm.va_start(i8* %ap1)
+  call void @llvm.va_end(i8* %ap1)
+  ret i32 %level
+}
+
+define i32 @log_two(i32 %a, i32 %b) {
+  %1 = call i32 (i32, i8*, ...) @logf(i32 0, i8* getelementptr ([6 x i8], [6 x i8]* @fmt, i32 0, i32 0), i32 %a, i32 %b)
+  ret i32 %1
+}
+
+declare void @llvm.va_start(i8*)
+declare void @llvm.va_end(i8*)
@@ -0,0 +1,19 @@
+; pointer casts with vector operands
+source_filename = "vector_casts.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+define <4 x i64> @ptrtoint(<4 x i8*> %p) {
+  %r = ptrtoint <4 x i8*> %p to <4 x i64>
+  ret <4 x i64> %r
+}
+
+define <2 x i32*> @inttoptr(<2 x i64> %i) {
+  %r = inttoptr <2 x i64> %i to <2 x i32*>
+  ret <2 x i32*> %r
+}
+
+define <4 x i8 addrspace(1)*> @addrspacecast(<4 x i8*> %p) {
+  %r = addrspacecast <4 x i8*> %p to <4 x i8 addrspace(1)*>
+  ret <4 x i8 addrspace(1)*> %r
+}
@@ -0,0 +1,21 @@
+; getelementptr with vector-of-pointers bases and vector indices
+source_filename = "vector_gep.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%struct.pair = type { i32, i64 }
+
+define <4 x i32*> @vector_base(<4 x i32*> %ptrs, <4 x i64> %idx) {
+  %1 = getelementptr i32, <4 x i32*> %ptrs, <4 x i64> %idx
+  ret <4 x i32*> %1
+}
+
+define <4 x i32*> @scalar_base(i32* %ptr, <4 x i64> %idx) {
+  %1 = getelementptr i32, i32* %ptr, <4 x i64> %idx
+  ret <4 x i32*> %1
+}
+
+define <2 x i64*> @struct_field(<2 x %struct.pair*> %ptrs) {
+  %1 = getelementptr %struct.pair, <2 x %struct.pair*> %ptrs, <2 x i64> zeroinitializer, <2 x i32> <i32 1, i32 1>
+  ret <2 x i64*> %1
+}
@@ -0,0 +1,1840 @@
+//! Tests against the hand-written `.ll` files in `tests/handwritten_bc`,
+//! each of which exercises some specific IR construct(s)
+
+use llvm_ir::instruction;
+use llvm_ir::instruction::{Atomicity, LandingPadClause, MemoryOrdering, RMWOperation, SynchronizationScope, TailCallKind};
+use llvm_ir::terminator;
+use llvm_ir::Terminator;
+use llvm_ir::CallGraph;
+use llvm_ir::Cfg;
+use llvm_ir::constant;
+use llvm_ir::Constant;
+use llvm_ir::DataLayout;
+use llvm_ir::debuginfo::{DIScope, DITag, MetadataNodeID};
+use llvm_ir::debugloc::{DebugLoc, Frame};
+use llvm_ir::DIType;
+use llvm_ir::DominatorTree;
+use llvm_ir::function::{AliasResult, MergeError, ParameterAttribute, SplitError};
+use llvm_ir::HasDebugLoc;
+use llvm_ir::lower::{TacInstr, TacOperand};
+use llvm_ir::Intrinsic;
+use llvm_ir::Module;
+use llvm_ir::module::SymbolKind;
+use llvm_ir::ModuleDiff;
+use llvm_ir::Name;
+use llvm_ir::Operand;
+use llvm_ir::ParseWarning;
+use llvm_ir::Type;
+use llvm_ir::Typed;
+use llvm_ir::TypedInModule;
+use llvm_ir::module::InstructionRef;
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::path::Path;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+#[test]
+fn call_sites() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    assert_eq!(module.ultimate_target(&Name::from("alias_of_alias")), Some(&Name::from("callee")));
+    assert_eq!(module.ultimate_target(&Name::from("callee")), Some(&Name::from("callee")));
+
+    let call_sites: Vec<_> = module.call_sites().collect();
+    assert_eq!(call_sites.len(), 5);
+
+    let callee = Name::from("callee");
+    let external = Name::from("external");
+    let caller_sites: Vec<_> = call_sites.iter().filter(|cs| cs.caller.name == "caller").collect();
+    assert_eq!(caller_sites.len(), 4);
+    assert!(caller_sites.iter().all(|cs| cs.call.is_left()));
+    assert_eq!(caller_sites[0].callee, Some(&callee));
+    assert!(caller_sites[0].is_direct());
+    assert_eq!(caller_sites[1].callee, None); // indirect call through %fptr
+    assert!(!caller_sites[1].is_direct());
+    assert_eq!(caller_sites[2].callee, Some(&callee)); // call through @alias_of_alias
+    assert_eq!(caller_sites[3].callee, Some(&external));
+
+    let invoke_site = call_sites.iter().find(|cs| cs.caller.name == "invoker").expect("Expected a call site in @invoker");
+    assert!(invoke_site.call.is_right());
+    assert_eq!(invoke_site.callee, Some(&external));
+
+    // make @callee_alias and @alias_of_alias alias each other: the call
+    // through @alias_of_alias is still direct, but has no ultimate callee
+    let mut cyclic = module.clone();
+    let alias = cyclic.global_aliases.iter_mut().find(|alias| alias.name == Name::from("callee_alias")).expect("Failed to find alias");
+    match &mut alias.aliasee {
+        Constant::GlobalReference { name, .. } => *name = Name::from("alias_of_alias"),
+        aliasee => panic!("Expected a global reference, got {:?}", aliasee),
+    }
+    assert_eq!(cyclic.ultimate_target(&Name::from("alias_of_alias")), None);
+    let caller_sites: Vec<_> = cyclic.call_sites().filter(|cs| cs.caller.name == "caller").collect();
+    assert_eq!(caller_sites[2].callee, None);
+    assert!(caller_sites[2].is_direct());
+    assert!(!caller_sites[1].is_direct());
+}
+
+#[test]
+fn called_function_names() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let caller = module.get_func_by_name("caller").expect("Failed to find function");
+    let insts = &caller.basic_blocks[0].instrs;
+    assert_eq!(insts[0].called_function_name(), Some("callee"));
+    assert_eq!(insts[1].called_function_name(), None); // indirect call through %fptr
+    assert_eq!(insts[2].called_function_name(), Some("alias_of_alias"));
+    assert_eq!(insts[3].called_function_name(), Some("external"));
+    let call: instruction::Call = insts[0].clone().try_into().expect("Should be a call");
+    assert_eq!(call.called_function_name(), Some("callee"));
+
+    let invoker = module.get_func_by_name("invoker").expect("Failed to find function");
+    assert_eq!(invoker.basic_blocks[0].term.called_function_name(), Some("external"));
+    assert_eq!(invoker.basic_blocks[1].term.called_function_name(), None);
+}
+
+#[test]
+fn unreachable_blocks() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/unreachable.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let mut func = module.get_func_by_name("dead_blocks").expect("Failed to find function").clone();
+    assert_eq!(func.unreachable_blocks(), vec![&Name::from("dead"), &Name::from("dead2")]);
+
+    func.remove_unreachable_blocks();
+    assert!(func.unreachable_blocks().is_empty());
+    let names: Vec<&Name> = func.basic_blocks.iter().map(|bb| &bb.name).collect();
+    assert_eq!(names, vec![&Name::from("entry"), &Name::from("left"), &Name::from("right"), &Name::from("join")]);
+    let join = func.get_bb_by_name(&Name::from("join")).expect("Failed to find block");
+    let phi: instruction::Phi = join.instrs[0].clone().try_into().expect("Should be a phi");
+    let incoming: Vec<&Name> = phi.incoming_values.iter().map(|(_, block)| block).collect();
+    assert_eq!(incoming, vec![&Name::from("left"), &Name::from("right")]);
+}
+
+#[test]
+fn callbr_successors() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/callbr.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("asm_goto").expect("Failed to find function");
+    let bb = func.get_bb_by_name(&Name::from("asm")).expect("Failed to find block");
+    let callbr: terminator::CallBr = bb.term.clone().try_into().expect("Should be a callbr");
+    assert_eq!(callbr.return_label, Name::from("normal"));
+    assert_eq!(callbr.other_labels, vec![Name::from("fail"), Name::from("retry")]);
+
+    let cfg = Cfg::new(func);
+    assert_eq!(cfg.successors(&Name::from("asm")), &[&Name::from("normal"), &Name::from("fail"), &Name::from("retry")]);
+    assert_eq!(cfg.predecessors(&Name::from("fail")), &[&Name::from("asm")]);
+    assert_eq!(cfg.predecessors(&Name::from("asm")), &[&Name::from("entry"), &Name::from("retry")]);
+    assert_eq!(cfg.reachable_blocks().len(), 5);
+}
+
+#[test]
+fn variadic_parameters() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/varargs.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let logf = module.get_func_by_name("logf").expect("Failed to find function");
+    assert!(logf.is_var_arg);
+    assert_eq!(logf.num_fixed_params(), 2);
+    assert_eq!(logf.param_type(0), Some(&Type::i32()));
+    assert_eq!(logf.param_type(1), Some(&Type::pointer_to(Type::i8())));
+    assert_eq!(logf.param_type(2), None);
+
+    let log_two = module.get_func_by_name("log_two").expect("Failed to find function");
+    assert!(!log_two.is_var_arg);
+    assert_eq!(log_two.num_fixed_params(), 2);
+    let call: instruction::Call = log_two.basic_blocks[0].instrs[0].clone().try_into().expect("Should be a call");
+    assert_eq!(call.arguments.len(), 4);
+}
+
+#[test]
+fn assume_and_expect() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/assume_expect.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("hints").expect("Failed to find function");
+    let insts = &func.basic_blocks[0].instrs;
+
+    assert_eq!(insts[1].as_intrinsic(), Some(Intrinsic::Assume {
+        condition: Operand::LocalOperand { name: Name::from("c"), ty: Type::bool() },
+    }));
+    assert_eq!(insts[2].as_intrinsic(), Some(Intrinsic::Expect {
+        value: Operand::LocalOperand { name: Name::from("x"), ty: Type::i64() },
+        expected: Operand::ConstantOperand(Constant::Int { bits: 64, value: 42 }),
+    }));
+    assert_eq!(insts[0].as_intrinsic(), None);
+}
+
+#[test]
+fn debugloc_scopes() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/scopes.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("nested").expect("Failed to find function");
+
+    let entry = func.get_bb_by_name(&Name::from("entry")).expect("Failed to find block");
+    let entry_loc = entry.instrs[0].get_debug_loc().as_ref().expect("Expected a debugloc");
+    let subprogram = entry_loc.scope.expect("Expected a scope");
+    assert_eq!(module.resolve_discope(subprogram), Some(&DIScope::Subprogram { name: Some("nested".to_owned()) }));
+    assert_eq!(entry_loc.subprogram_scope(&module), Some(subprogram));
+
+    // an instruction inside nested lexical blocks
+    let then = func.get_bb_by_name(&Name::from("then")).expect("Failed to find block");
+    let then_loc = then.instrs[0].get_debug_loc().as_ref().expect("Expected a debugloc");
+    assert_eq!(then_loc.col, Some(25));
+    assert_eq!(then_loc.function.as_deref(), Some("nested"));
+    let mut scope = then_loc.scope;
+    let mut depth = 0;
+    while let Some(DIScope::LexicalBlock { parent }) = scope.and_then(|id| module.resolve_discope(id)) {
+        scope = *parent;
+        depth += 1;
+    }
+    assert_eq!(depth, 3);
+    assert_eq!(scope, Some(subprogram));
+    assert_eq!(then_loc.subprogram_scope(&module), Some(subprogram));
+    assert_eq!(then.term.get_debug_loc().as_ref().and_then(|loc| loc.scope), then_loc.scope);
+
+    // a location with column 0, i.e., no column information
+    let else_bb = func.get_bb_by_name(&Name::from("else")).expect("Failed to find block");
+    let else_loc = else_bb.term.get_debug_loc().as_ref().expect("Expected a debugloc");
+    assert_eq!(else_loc.line, 5);
+    assert_eq!(else_loc.col, None);
+    assert_eq!(else_loc.subprogram_scope(&module), Some(subprogram));
+    assert_eq!(else_loc.to_string(), "/tmp/scopes.c, line 5");
+}
+
+#[test]
+fn debugloc_equality_across_modules() {
+    init_logging();
+    let module = Module::from_bc_path("tests/handwritten_bc/scopes.bc").expect("Failed to parse module");
+    let other = Module::from_bc_path("tests/handwritten_bc/scopes_other.bc").expect("Failed to parse module");
+    let then_loc = |module: &Module| {
+        let func = module.get_func_by_name("nested").expect("Failed to find function");
+        let then = func.get_bb_by_name(&Name::from("then")).expect("Failed to find block");
+        then.instrs[0].get_debug_loc().clone().expect("Expected a debugloc")
+    };
+    let (loc, other_loc) = (then_loc(&module), then_loc(&other));
+    // the scopes are numbered differently in the two modules ...
+    assert_ne!(loc.scope, other_loc.scope);
+    // ... but the locations are the same
+    assert_eq!(loc, other_loc);
+    assert_eq!(loc.cmp(&other_loc), std::cmp::Ordering::Equal);
+    let set: HashSet<DebugLoc> = vec![loc, other_loc].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn landingpad_clauses() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/landingpads.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("clauses").expect("Failed to find function");
+    let typeinfo = Constant::BitCast(Box::new(constant::BitCast {
+        operand: Constant::GlobalReference {
+            name: Name::from("_ZTIi"),
+            ty: Type::pointer_to(Type::i8()),
+        },
+        to_type: Type::pointer_to(Type::i8()),
+    }));
+
+    let bb = func.get_bb_by_name(&Name::from("catch_int")).expect("Failed to find block");
+    let lp: instruction::LandingPad = bb.instrs[0].clone().try_into().expect("Should be a landingpad");
+    assert!(lp.cleanup);
+    assert_eq!(lp.clauses, vec![LandingPadClause::Catch(typeinfo.clone())]);
+
+    let bb = func.get_bb_by_name(&Name::from("filter")).expect("Failed to find block");
+    let lp: instruction::LandingPad = bb.instrs[0].clone().try_into().expect("Should be a landingpad");
+    assert!(!lp.cleanup);
+    assert_eq!(lp.clauses.len(), 2);
+    match &lp.clauses[0] {
+        LandingPadClause::Filter(Constant::Array { elements, .. }) => assert_eq!(elements, &vec![typeinfo]),
+        clause => panic!("Expected a filter clause with an array, got {:?}", clause),
+    }
+    assert_eq!(lp.clauses[1], LandingPadClause::Catch(Constant::Null(Type::pointer_to(Type::i8()))));
+}
+
+#[test]
+fn split_blocks() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/split.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let mut func = module.get_func_by_name("loop").expect("Failed to find function").clone();
+
+    // split the loop body after its first instruction
+    func.split_block(&Name::from("body"), 1, Name::from("body.split")).expect("Failed to split block");
+    let names: Vec<&Name> = func.basic_blocks.iter().map(|bb| &bb.name).collect();
+    assert_eq!(names, vec![&Name::from("entry"), &Name::from("header"), &Name::from("body"), &Name::from("body.split"), &Name::from("exit")]);
+    let body = func.get_bb_by_name(&Name::from("body")).expect("Failed to find block");
+    assert_eq!(body.instrs.len(), 1);
+    assert_eq!(body.term, Terminator::Br(terminator::Br { dest: Name::from("body.split"), debugloc: None }));
+    let split = func.get_bb_by_name(&Name::from("body.split")).expect("Failed to find block");
+    assert_eq!(split.instrs.len(), 2);
+    assert_eq!(split.term.successors(), vec![&Name::from("header")]);
+
+    // the phis in the loop header now come from the new block
+    let header = func.get_bb_by_name(&Name::from("header")).expect("Failed to find block");
+    for inst in &header.instrs[0..2] {
+        let phi: instruction::Phi = inst.clone().try_into().expect("Should be a phi");
+        let incoming: Vec<&Name> = phi.incoming_values.iter().map(|(_, block)| block).collect();
+        assert_eq!(incoming, vec![&Name::from("entry"), &Name::from("body.split")]);
+    }
+
+    // splitting before the terminator moves only the terminator
+    func.split_block(&Name::from("header"), 3, Name::from("latch")).expect("Failed to split block");
+    let latch = func.get_bb_by_name(&Name::from("latch")).expect("Failed to find block");
+    assert!(latch.instrs.is_empty());
+    let exit = func.get_bb_by_name(&Name::from("exit")).expect("Failed to find block");
+    let phi: instruction::Phi = exit.instrs[0].clone().try_into().expect("Should be a phi");
+    assert_eq!(phi.incoming_values[0].1, Name::from("latch"));
+    assert_eq!(Cfg::new(&func).predecessors(&Name::from("header")), &[&Name::from("entry"), &Name::from("body.split")]);
+
+    assert_eq!(
+        func.split_block(&Name::from("nonexistent"), 0, Name::from("x")),
+        Err(SplitError::NoSuchBlock(Name::from("nonexistent"))),
+    );
+    assert_eq!(
+        func.split_block(&Name::from("body"), 2, Name::from("x")),
+        Err(SplitError::IndexOutOfRange { block: Name::from("body"), at: 2, len: 1 }),
+    );
+    assert_eq!(func.split_block(&Name::from("body"), 0, Name::from("sq")), Err(SplitError::NameInUse(Name::from("sq"))));
+    assert_eq!(func.split_block(&Name::from("header"), 1, Name::from("x")), Err(SplitError::SplitsPhis(Name::from("header"))));
+}
+
+#[test]
+fn merge_blocks() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/split.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let original = module.get_func_by_name("loop").expect("Failed to find function");
+
+    // merging undoes a split
+    let mut func = original.clone();
+    func.split_block(&Name::from("body"), 2, Name::from("body.split")).expect("Failed to split block");
+    assert_ne!(&func, original);
+    func.merge_into_predecessor(&Name::from("body.split")).expect("Failed to merge block");
+    assert_eq!(&func, original);
+
+    // `entry` falls through to `header`, but so does `body`
+    assert_eq!(func.merge_into_predecessor(&Name::from("header")), Err(MergeError::NotSinglePredecessor(Name::from("header"))));
+    // the entry block has no predecessors
+    assert_eq!(func.merge_into_predecessor(&Name::from("entry")), Err(MergeError::NotSinglePredecessor(Name::from("entry"))));
+    // `header` ends in a conditional branch
+    assert_eq!(func.merge_into_predecessor(&Name::from("exit")), Err(MergeError::PredecessorNotBr(Name::from("header"))));
+    assert_eq!(func.merge_into_predecessor(&Name::from("nonexistent")), Err(MergeError::NoSuchBlock(Name::from("nonexistent"))));
+    assert_eq!(&func, original);
+}
+
+#[test]
+fn alias_queries() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/alias.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("pointers").expect("Failed to find function");
+    let alias = |a: &str, b: &str| func.may_alias(&module, &Name::from(a), &Name::from(b));
+
+    assert_eq!(alias("a", "b"), AliasResult::NoAlias);
+    assert_eq!(alias("a", "a"), AliasResult::MustAlias);
+    assert_eq!(alias("a_elem", "a"), AliasResult::MayAlias);
+    assert_eq!(alias("a_elem", "b"), AliasResult::NoAlias);
+    assert_eq!(alias("a_first", "a"), AliasResult::MustAlias);
+    assert_eq!(alias("a_cast", "a_first"), AliasResult::MustAlias);
+
+    // globals, and pointers derived from them
+    assert_eq!(alias("g", "h"), AliasResult::NoAlias);
+    assert_eq!(alias("g_cast", "g"), AliasResult::MustAlias);
+    assert_eq!(alias("g_elem", "g_cast"), AliasResult::MayAlias);
+    assert_eq!(alias("g_elem", "h_cast"), AliasResult::NoAlias);
+    assert_eq!(alias("a", "g_cast"), AliasResult::NoAlias);
+
+    // a global alias may alias its aliasee, and unknown names may alias anything
+    assert_eq!(alias("g_alias_cast", "g"), AliasResult::MayAlias);
+    assert_eq!(alias("g_alias_cast", "h"), AliasResult::MayAlias);
+    assert_eq!(alias("nonexistent", "g"), AliasResult::MayAlias);
+    assert_eq!(alias("nonexistent", "a"), AliasResult::MayAlias);
+
+    // nothing is known about parameters
+    assert_eq!(alias("p", "a"), AliasResult::MayAlias);
+    assert_eq!(alias("p", "g"), AliasResult::MayAlias);
+    assert_eq!(alias("p_elem", "p"), AliasResult::MayAlias);
+}
+
+#[test]
+fn initializer_elements() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/tables.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let global = |name: &str| module.get_global_var_by_name(&Name::from(name)).expect("Failed to find global");
+    let func_ref = |name: &str| Constant::GlobalReference {
+        name: Name::from(name),
+        ty: Type::FuncType { result_type: Box::new(Type::VoidType), param_types: vec![], is_var_arg: false },
+    };
+
+    let handlers = global("handlers");
+    assert_eq!(handlers.initializer_element(&[3]), Some(&func_ref("h3")));
+    assert_eq!(handlers.initializer_element(&[0]), Some(&func_ref("h0")));
+    assert!(matches!(handlers.initializer_element(&[2]), Some(Constant::Null(_))));
+    assert_eq!(handlers.initializer_element(&[4]), None);
+    assert_eq!(handlers.initializer_element(&[3, 0]), None);
+    assert_eq!(handlers.initializer_element(&[]), handlers.initializer.as_ref());
+
+    let vtable = global("vtable");
+    assert_eq!(vtable.initializer_element(&[1, 0]), Some(&func_ref("h1")));
+    assert_eq!(vtable.initializer_element(&[1, 1]), Some(&func_ref("h0")));
+    assert_eq!(vtable.initializer_element(&[2]), None);
+
+    assert_eq!(global("zeroes").initializer_element(&[3]), None);
+    assert_eq!(global("scalar").initializer_element(&[0]), None);
+    assert_eq!(global("external").initializer_element(&[]), None);
+}
+
+#[test]
+fn three_address_code() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/arith.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("arith").unwrap();
+    let tac = func.to_three_address();
+    let lines: Vec<String> = tac.iter().map(|instr| instr.to_string()).collect();
+    assert_eq!(lines, vec![
+        "entry:",
+        "%sum = add %a, %b",
+        "%prod = mul %sum, i32 3",
+        "%max = load @limit",
+        "%cmp = icmp slt %prod, %max",
+        "br %cmp, %small, %big",
+        "small:",
+        "ret %prod",
+        "big:",
+        "%clamped = call @clamp(%prod)",
+        "store @limit, %clamped",
+        "ret %clamped",
+    ]);
+
+    assert_eq!(tac[2], TacInstr::Op {
+        dest: Some(Name::from("prod")),
+        op: "mul".to_owned(),
+        args: vec![
+            TacOperand::Temp(Name::from("sum")),
+            TacOperand::Const(Constant::Int { bits: 32, value: 3 }),
+        ],
+    });
+    assert_eq!(tac[9], TacInstr::Call {
+        dest: Some(Name::from("clamped")),
+        function: Some(TacOperand::Global(Name::from("clamp"))),
+        args: vec![TacOperand::Temp(Name::from("prod"))],
+    });
+}
+
+#[test]
+fn three_address_code_with_block_addresses() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/blockaddress.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("pick").unwrap();
+    let lines: Vec<String> = func.to_three_address().iter().map(|instr| instr.to_string()).collect();
+    assert_eq!(lines, vec![
+        "entry:",
+        "%addr = select %c, i8* blockaddress(@pick, %a), i8* blockaddress(@pick, %0)",
+        "indirectbr %addr, %a, %0",
+        "a:",
+        "ret i32 1",
+        "0:",
+        "ret i32 2",
+    ]);
+}
+
+#[test]
+fn recursive_struct_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/recursive_types.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let recursive = module.recursive_struct_types();
+    let expected: HashSet<String> = ["Node", "Even", "Odd", "Wrapper"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(recursive, expected);
+
+    // non-recursive structs containing recursive ones are still sized
+    let dl = DataLayout::parse(&module.data_layout).expect("Failed to parse data layout");
+    let holder = Type::NamedStructType {
+        name: "Holder".to_owned(),
+        ty: module.named_struct_types["Holder"].as_ref().map(std::sync::Arc::downgrade),
+    };
+    assert_eq!(dl.alloc_size(&holder), Some(16));
+}
+
+#[test]
+fn call_site_argument_attributes() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/callsite_attrs.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("caller").unwrap();
+    let entry = &func.basic_blocks[0];
+
+    let call: instruction::Call = entry.instrs[0].clone().try_into().expect("Should be a call");
+    let attrs = &call.argument_attributes;
+    assert_eq!(attrs.len(), 2);
+    assert_eq!(attrs[0].len(), 1);
+    assert!(attrs[0][0].is_enum_attribute_named("nocapture"));
+    assert_eq!(attrs[1].len(), 1);
+    assert!(attrs[1][0].is_enum_attribute_named("zeroext"));
+
+    // the callee declares its parameter `readonly`, but the call site doesn't
+    let call: instruction::Call = entry.instrs[1].clone().try_into().expect("Should be a call");
+    assert_eq!(call.argument_attributes, vec![vec![]]);
+
+    let invoke: terminator::Invoke = entry.term.clone().try_into().expect("Should be an invoke");
+    let attrs = &invoke.argument_attributes;
+    assert_eq!(attrs.len(), 2);
+    assert!(attrs[0][0].is_enum_attribute_named("nonnull"));
+    assert!(attrs[1].is_empty());
+}
+
+#[test]
+fn constant_pool() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/constant_pool.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let constants = module.all_constants();
+
+    // `i32 42` is used by two initializers and an instruction, but appears once
+    let forty_two = Constant::Int { bits: 32, value: 42 };
+    assert_eq!(constants.iter().filter(|c| **c == forty_two).count(), 1);
+    assert_eq!(constants[0], forty_two);
+    assert!(constants.contains(&Constant::Int { bits: 32, value: 7 }));
+    assert!(constants.contains(&Constant::GlobalReference { name: Name::from("answer"), ty: Type::i32() }));
+    assert!(constants.iter().any(|c| matches!(c, Constant::Array { .. })));
+    assert!(constants.iter().any(|c| matches!(c, Constant::BitCast(_))));
+    assert_eq!(constants.len(), 5);
+}
+
+#[test]
+fn indirectbr_destinations() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/indirectbr.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("dispatch").unwrap();
+    let indirectbr: terminator::IndirectBr = func.basic_blocks[0].term.clone().try_into().expect("Should be an indirectbr");
+    assert_eq!(indirectbr.operand, Operand::LocalOperand { name: Name::from("addr"), ty: Type::pointer_to(Type::i8()) });
+    assert_eq!(indirectbr.possible_dests, vec![Name::from("a"), Name::from("b")]);
+
+    let cfg = Cfg::new(func);
+    assert_eq!(cfg.successors(&Name::from("entry")), &[&Name::from("a"), &Name::from("b")]);
+    assert_eq!(cfg.predecessors(&Name::from("b")), &[&Name::from("entry")]);
+    assert!(func.unreachable_blocks().is_empty());
+}
+
+#[test]
+fn global_variable_debug_info() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/global_dbg.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let counter = module.global_vars.iter().find(|var| var.name == Name::from("counter")).unwrap();
+    let info = counter.debug_info(&module).expect("Should have debug info");
+    assert_eq!(info.name.as_deref(), Some("counter"));
+    assert_eq!(info.linkage_name, None);
+    assert_eq!(info.filename.as_deref(), Some("globals.cpp"));
+    assert_eq!(info.line, 1);
+    let ty = module.resolve_ditype(info.ty.unwrap()).unwrap();
+    assert_eq!(ty, &DIType::Basic { name: "int".to_owned(), size_in_bits: 32 });
+
+    let greeting = module.global_vars.iter().find(|var| var.name == Name::from("_ZN2ns8greetingE")).unwrap();
+    let info = greeting.debug_info(&module).expect("Should have debug info");
+    assert_eq!(info.name.as_deref(), Some("greeting"));
+    assert_eq!(info.linkage_name.as_deref(), Some("_ZN2ns8greetingE"));
+    assert_eq!(info.line, 2);
+    match module.resolve_ditype(info.ty.unwrap()) {
+        Some(DIType::Derived { tag: DITag::PointerType, .. }) => {},
+        ty => panic!("Expected a pointer type, got {:?}", ty),
+    }
+
+    let string = module.global_vars.iter().find(|var| var.name == Name::from(".str")).unwrap();
+    assert_eq!(string.debug_info(&module), None);
+
+    let mut stripped = module.clone();
+    stripped.strip_debug_info();
+    assert!(stripped.global_vars.iter().all(|var| var.debug_info(&stripped).is_none()));
+}
+
+#[test]
+fn address_spaces_used() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/addrspaces.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let expected: HashSet<u32> = [0, 1, 3].iter().copied().collect();
+    assert_eq!(module.address_spaces_used(), expected);
+
+    let path = Path::new("tests/handwritten_bc/arith.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let expected: HashSet<u32> = [0].iter().copied().collect();
+    assert_eq!(module.address_spaces_used(), expected);
+}
+
+#[test]
+fn parameter_attributes() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/param_attrs.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("f").expect("Failed to find function");
+    let has = |attrs: &[ParameterAttribute], name: &str| attrs.iter().any(|attr| attr.is_enum_attribute_named(name));
+
+    assert!(has(&func.return_attributes, "signext"));
+    assert_eq!(func.return_attributes.len(), 1);
+    // each parameter gets its own attributes, not those of the previous position
+    assert!(has(&func.parameters[0].attributes, "zeroext"));
+    assert_eq!(func.parameters[0].attributes.len(), 1);
+    assert!(has(&func.parameters[1].attributes, "nonnull"));
+    assert_eq!(func.parameters[1].attributes.len(), 1);
+    assert!(func.parameters[2].attributes.is_empty());
+}
+
+#[test]
+fn alloca_stores_and_loads() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/mem2reg.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("locals").unwrap();
+    let iref = |block: &str, index: usize| InstructionRef { function: "locals".to_owned(), block: Name::from(block), index };
+
+    assert_eq!(func.sole_store_to(&Name::from("x")), Some(iref("entry", 3)));
+    assert_eq!(func.loads_from(&Name::from("x")), vec![iref("then", 0), iref("done", 0)]);
+
+    // `y` is stored to twice
+    assert_eq!(func.sole_store_to(&Name::from("y")), None);
+    assert_eq!(func.loads_from(&Name::from("y")), vec![iref("done", 2)]);
+
+    assert_eq!(func.sole_store_to(&Name::from("unused")), None);
+    assert!(func.loads_from(&Name::from("unused")).is_empty());
+}
+
+#[test]
+fn byval_and_sret_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/byval_sret.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("f").unwrap();
+    let big = Type::NamedStructType { name: "Big".to_owned(), ty: None };
+
+    let out = &func.parameters[0];
+    assert_eq!(out.sret_type(), Some(&big));
+    assert_eq!(out.byval_type(), None);
+    assert!(out.attributes.contains(&ParameterAttribute::StructRet(big.clone())));
+    assert!(out.attributes.iter().any(|attr| attr.is_enum_attribute_named("sret")));
+    assert!(out.attributes.iter().any(|attr| attr.is_enum_attribute_named("noalias")));
+
+    let input = &func.parameters[1];
+    assert_eq!(input.byval_type(), Some(&big));
+    assert_eq!(input.sret_type(), None);
+
+    let plain = &func.parameters[2];
+    assert_eq!(plain.byval_type(), None);
+    assert_eq!(plain.sret_type(), None);
+
+    // call-site attributes carry the types too
+    let caller = module.get_func_by_name("caller").unwrap();
+    let call: instruction::Call = caller.basic_blocks[0].instrs[1].clone().try_into().expect("Should be a call");
+    let attrs = &call.argument_attributes;
+    assert!(attrs[0].contains(&ParameterAttribute::StructRet(big.clone())));
+    assert!(attrs[1].contains(&ParameterAttribute::ByVal(big.clone())));
+    assert!(attrs[2].is_empty());
+}
+
+#[test]
+fn target_features_and_cpu() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/target_features.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let avx = module.get_func_by_name("avx").unwrap();
+    assert_eq!(avx.target_features(), Some("+sse,+avx"));
+    assert_eq!(avx.target_cpu(), Some("x86-64"));
+
+    let haswell = module.get_func_by_name("haswell").unwrap();
+    assert_eq!(haswell.target_features(), None);
+    assert_eq!(haswell.target_cpu(), Some("haswell"));
+
+    let generic = module.get_func_by_name("generic").unwrap();
+    assert_eq!(generic.target_features(), None);
+    assert_eq!(generic.target_cpu(), None);
+}
+
+#[test]
+fn noreturn_reachability() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/noreturn.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("noreturn").expect("Failed to find function");
+    let cfg = Cfg::new(func);
+
+    assert_eq!(cfg.entry(), Some(&Name::from("entry")));
+    assert_eq!(cfg.successors(&Name::from("entry")), &[&Name::from("fail"), &Name::from("ok")]);
+    assert_eq!(cfg.predecessors(&Name::from("after_abort")), &[&Name::from("fail2")]);
+    assert!(cfg.successors(&Name::from("fail")).is_empty());
+
+    let expected: HashSet<Name> = ["entry", "fail", "ok", "fail2", "after_abort", "check3", "fail3", "after_die", "done"]
+        .iter()
+        .map(|&n| Name::from(n))
+        .collect();
+    assert_eq!(cfg.reachable_blocks(), expected);
+
+    let expected: HashSet<Name> = ["entry", "fail", "ok", "fail2", "check3", "fail3", "done"]
+        .iter()
+        .map(|&n| Name::from(n))
+        .collect();
+    assert_eq!(cfg.reachable_blocks_pruning_noreturn(&module), expected);
+}
+
+#[test]
+fn token_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/tokens.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("coro").expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+    let coro_id: &instruction::Call = &bb.instrs[0].clone().try_into().expect("Should be a call");
+    assert_eq!(coro_id.dest, Some(Name::from("id")));
+    assert_eq!(coro_id.get_type(), Type::TokenType);
+    let coro_begin: &instruction::Call = &bb.instrs[1].clone().try_into().expect("Should be a call");
+    assert_eq!(coro_begin.arguments[0].0, Operand::LocalOperand { name: Name::from("id"), ty: Type::TokenType });
+
+    let func = module.get_func_by_name("funclets").expect("Failed to find function");
+    let dispatch = func.get_bb_by_name(&Name::from("dispatch")).expect("Failed to find bb 'dispatch'");
+    let catchswitch: &terminator::CatchSwitch = &dispatch.term.clone().try_into().expect("Should be a catchswitch");
+    assert_eq!(catchswitch.get_type(), Type::TokenType);
+    let handler = func.get_bb_by_name(&Name::from("handler")).expect("Failed to find bb 'handler'");
+    let catchpad: &instruction::CatchPad = &handler.instrs[0].clone().try_into().expect("Should be a catchpad");
+    assert_eq!(catchpad.catch_switch, Operand::LocalOperand { name: Name::from("cs"), ty: Type::TokenType });
+    assert_eq!(catchpad.get_type(), Type::TokenType);
+}
+
+#[test]
+fn atomic_loads_and_stores() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/atomics.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("atomics").expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+
+    let plain: &instruction::Load = &bb.instrs[0].clone().try_into().expect("Should be a load");
+    assert_eq!(plain.atomicity, None);
+    assert!(!bb.instrs[0].is_atomic());
+    let acquire: &instruction::Load = &bb.instrs[1].clone().try_into().expect("Should be a load");
+    assert_eq!(acquire.atomicity, Some(Atomicity { synch_scope: SynchronizationScope::System, mem_ordering: MemoryOrdering::Acquire }));
+    assert_eq!(acquire.volatile, false);
+    assert!(bb.instrs[1].is_atomic());
+
+    let plain: &instruction::Store = &bb.instrs[2].clone().try_into().expect("Should be a store");
+    assert_eq!(plain.atomicity, None);
+    let release: &instruction::Store = &bb.instrs[3].clone().try_into().expect("Should be a store");
+    assert_eq!(release.atomicity, Some(Atomicity { synch_scope: SynchronizationScope::SingleThread, mem_ordering: MemoryOrdering::Release }));
+
+    // `volatile` and `atomic` are decoded independently
+    let both: &instruction::Load = &bb.instrs[4].clone().try_into().expect("Should be a load");
+    assert_eq!(both.volatile, true);
+    assert_eq!(both.atomicity, Some(Atomicity { synch_scope: SynchronizationScope::System, mem_ordering: MemoryOrdering::SequentiallyConsistent }));
+    assert!(bb.instrs[4].is_atomic());
+}
+
+#[test]
+fn dominator_tree() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/dominators.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("diamonds").expect("Failed to find function");
+    let domtree = DominatorTree::new(func);
+    let name = |n: &str| Name::from(n);
+    let names = |ns: &[&str]| ns.iter().map(|&n| Name::from(n)).collect::<HashSet<Name>>();
+
+    assert_eq!(domtree.idom(&name("entry")), None);
+    assert_eq!(domtree.idom(&name("merge")), Some(&name("entry")));
+    assert_eq!(domtree.idom(&name("latch")), Some(&name("body")));
+    assert_eq!(domtree.idom(&name("exit")), Some(&name("header")));
+    assert_eq!(domtree.idom(&name("dead")), None);
+    assert!(domtree.dominates(&name("entry"), &name("exit")));
+    assert!(domtree.dominates(&name("header"), &name("header")));
+    assert!(!domtree.dominates(&name("then"), &name("merge")));
+    assert!(!domtree.dominates(&name("dead"), &name("merge")));
+    assert_eq!(domtree.dominance_frontier(&name("then")), &[&name("merge")]);
+    assert_eq!(domtree.dominance_frontier(&name("latch")), &[&name("header")]);
+
+    // a variable defined in both arms of an if/else needs a phi where they meet
+    assert_eq!(domtree.phi_placement(&names(&["then", "else"])), names(&["merge"]));
+    // a variable defined in one arm of the loop's diamond needs a phi where
+    // the arms meet, and then (iteratively) at the loop header
+    assert_eq!(domtree.phi_placement(&names(&["left"])), names(&["latch", "header"]));
+    assert!(domtree.phi_placement(&names(&["entry"])).is_empty());
+}
+
+#[test]
+fn personality_and_unwind_attributes() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/cxx_eh.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("catches").expect("Failed to find function");
+    match &func.personality_function {
+        Some(Constant::BitCast(bc)) => match &bc.operand {
+            Constant::GlobalReference { name, .. } => assert_eq!(name, &Name::from("__gxx_personality_v0")),
+            c => panic!("Expected the bitcast operand to be a GlobalReference, got {:?}", c),
+        },
+        p => panic!("Expected personality function to be a bitcast, got {:?}", p),
+    }
+    assert!(func.has_enum_attribute("uwtable"));
+    assert!(func.has_enum_attribute("noinline"));
+    assert!(!func.has_enum_attribute("noreturn"));
+    assert_eq!(func.get_string_attribute("frame-pointer"), Some("all"));
+    assert_eq!(func.get_string_attribute("target-cpu"), None);
+
+    let func = module.get_func_by_name("no_eh").expect("Failed to find function");
+    assert_eq!(func.personality_function, None);
+    assert!(!func.has_enum_attribute("uwtable"));
+    assert_eq!(func.get_string_attribute("frame-pointer"), None);
+}
+
+#[test]
+fn operand_bundles() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/bundles.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let x = Operand::LocalOperand { name: Name::from("x"), ty: Type::i32() };
+
+    let func = module.get_func_by_name("deopt").expect("Failed to find function");
+    let instrs = &func.basic_blocks[0].instrs;
+    let call: instruction::Call = instrs[0].clone().try_into().expect("Should be a call");
+    assert_eq!(call.operand_bundles.len(), 1);
+    assert_eq!(call.operand_bundles[0].tag, "deopt");
+    assert_eq!(call.operand_bundles[0].operands[..], [x.clone(), Operand::ConstantOperand(Constant::Int { bits: 32, value: 7 })]);
+    assert_eq!(instrs[0].operands().len(), 4); // function, argument, and the two bundle operands
+
+    let call: instruction::Call = instrs[1].clone().try_into().expect("Should be a call");
+    let tags: Vec<&str> = call.operand_bundles.iter().map(|bundle| bundle.tag.as_str()).collect();
+    assert_eq!(tags, vec!["deopt", "empty", "gc-live"]);
+    assert_eq!(call.operand_bundles[0].operands.len(), 2);
+    assert!(call.operand_bundles[1].operands.is_empty());
+    assert_eq!(call.operand_bundles[2].operands.len(), 1);
+    assert_eq!(call.operand_bundles[2].operands[0], x);
+
+    let call: instruction::Call = instrs[2].clone().try_into().expect("Should be a call");
+    assert!(call.operand_bundles.is_empty());
+
+    let func = module.get_func_by_name("invokes").expect("Failed to find function");
+    let invoke: terminator::Invoke = func.basic_blocks[0].term.clone().try_into().expect("Should be an invoke");
+    assert_eq!(invoke.operand_bundles.len(), 1);
+    assert_eq!(invoke.operand_bundles[0].tag, "deopt");
+    assert_eq!(invoke.operand_bundles[0].operands.len(), 1);
+    assert_eq!(invoke.operand_bundles[0].operands[0], x);
+}
+
+#[test]
+fn address_taken_functions() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/indirect.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let address_taken = module.address_taken_functions();
+    let expected: HashSet<String> = ["in_table", "also_in_table", "returns_i32", "stored", "takes_i64"]
+        .iter()
+        .map(|&name| name.to_owned())
+        .collect();
+    assert_eq!(address_taken, expected);
+
+    // `callee` is the aliasee of a `GlobalAlias` and the personality function
+    // is used as a value, but `external` is only ever called
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let expected: HashSet<String> = ["callee", "__gxx_personality_v0"].iter().map(|&name| name.to_owned()).collect();
+    assert_eq!(module.address_taken_functions(), expected);
+}
+
+#[test]
+fn indirect_call_candidates() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/indirect.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("caller").expect("Failed to find function");
+    let call: instruction::Call = func.basic_blocks[0].instrs[4].clone().try_into().expect("Should be a call");
+    let candidates: Vec<&str> = module.candidates_for_indirect_call(&call).iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(candidates, vec!["in_table", "stored", "also_in_table"]);
+}
+
+#[test]
+fn extractvalue_result_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/aggregates.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("literal").expect("Failed to find function");
+    let ev: instruction::ExtractValue = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be an extractvalue");
+    assert_eq!(ev.indices, vec![1, 0]);
+    assert_eq!(ev.result_type(&module), Some(Type::i8()));
+    assert_eq!(ev.result_type(&module), Some(ev.get_type()));
+
+    let func = module.get_func_by_name("named").expect("Failed to find function");
+    let ev: instruction::ExtractValue = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be an extractvalue");
+    assert_eq!(ev.indices, vec![2, 3, 1]);
+    assert_eq!(ev.result_type(&module), Some(Type::i16()));
+    let ev: instruction::ExtractValue = func.basic_blocks[0].instrs[1].clone().try_into().expect("Should be an extractvalue");
+    match ev.result_type(&module) {
+        Some(Type::NamedStructType { name, .. }) => assert_eq!(name, "struct.inner"),
+        ty => panic!("Expected a NamedStructType, got {:?}", ty),
+    }
+
+    let mut out_of_range = ev.clone();
+    out_of_range.indices = vec![3];
+    assert_eq!(out_of_range.result_type(&module), None);
+}
+
+#[test]
+fn types_in_module() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/aggregates.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("gep_named").expect("Failed to find function");
+    let instr = &func.basic_blocks[0].instrs[0];
+    assert_eq!(instr.get_type_in(&module), Type::pointer_to(Type::i16()));
+    let gep: instruction::GetElementPtr = instr.clone().try_into().expect("Should be a gep");
+    assert_eq!(gep.get_type_in(&module), gep.get_type());
+
+    let func = module.get_func_by_name("load_const_gep").expect("Failed to find function");
+    let instr = &func.basic_blocks[0].instrs[0];
+    assert_eq!(instr.get_type_in(&module), Type::i8());
+    let load: instruction::Load = instr.clone().try_into().expect("Should be a load");
+    assert_eq!(load.address.get_type_in(&module), Type::pointer_to(Type::i8()));
+
+    // context-free cases are the same as `get_type()`
+    let func = module.get_func_by_name("named").expect("Failed to find function");
+    let instr = &func.basic_blocks[0].instrs[0];
+    assert_eq!(instr.get_type_in(&module), Type::i16());
+    let func = module.get_func_by_name("literal").expect("Failed to find function");
+    let instr = &func.basic_blocks[0].instrs[0];
+    assert_eq!(instr.get_type_in(&module), instr.get_type());
+}
+
+#[test]
+fn externally_initialized_globals() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/globals.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let var = module.get_global_var_by_name(&Name::from("filled_in_by_loader")).expect("Failed to find global");
+    assert!(var.externally_initialized);
+    assert!(!var.is_constant);
+    let var = module.get_global_var_by_name(&Name::from("ordinary")).expect("Failed to find global");
+    assert!(!var.externally_initialized);
+    let var = module.get_global_var_by_name(&Name::from("constant")).expect("Failed to find global");
+    assert!(!var.externally_initialized);
+    assert!(var.is_constant);
+}
+
+#[test]
+fn entry_points() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/entrypoints.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let callgraph = CallGraph::new(&module);
+    assert_eq!(callgraph.callees("api"), &["helper", "api"]);
+    assert_eq!(callgraph.callers("helper"), &["helper", "api"]);
+    assert_eq!(callgraph.callers("called_externally_visible"), &["main"]);
+    assert!(callgraph.callers("unused").is_empty());
+
+    let entry_points: Vec<&str> = module.likely_entry_points().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(entry_points, vec!["early_ctor", "late_ctor", "dtor", "kept", "compiler_kept", "api", "main"]);
+}
+
+#[test]
+fn recursive_and_leaf_functions() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/recursion.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let callgraph = CallGraph::new(&module);
+    assert!(callgraph.is_recursive("fact"));
+    assert!(callgraph.is_recursive("is_even"));
+    assert!(callgraph.is_recursive("is_odd"));
+    assert!(!callgraph.is_recursive("leaf"));
+    assert!(!callgraph.is_recursive("calls_others"));
+    assert!(!callgraph.is_leaf("fact"));
+    assert!(!callgraph.is_leaf("calls_others"));
+    assert!(callgraph.is_leaf("leaf"));
+    assert!(callgraph.is_leaf("calls_external"));
+
+    let leaves: Vec<&str> = module.leaf_functions().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(leaves, vec!["leaf", "calls_external"]);
+}
+
+#[test]
+fn functions_calling() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/recursion.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let callers = |target: &str| -> Vec<&str> { module.functions_calling(target).iter().map(|f| f.name.as_str()).collect() };
+    assert_eq!(callers("is_even"), vec!["is_odd", "calls_others"]);
+    assert_eq!(callers("fact"), vec!["fact"]);
+    assert_eq!(callers("external"), vec!["calls_external"]);
+    assert!(callers("calls_others").is_empty());
+    assert!(callers("nonexistent").is_empty());
+
+    // calls through aliases count as calls to the aliasee
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let callers: Vec<&str> = module.functions_calling("callee").iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(callers, vec!["caller"]);
+}
+
+#[test]
+fn call_graph_topo_order() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/recursion.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let callgraph = CallGraph::new(&module);
+    let groups = callgraph.topo_order();
+    assert_eq!(groups, vec![
+        vec!["fact".to_owned()],
+        vec!["is_even".to_owned(), "is_odd".to_owned()],
+        vec!["leaf".to_owned()],
+        vec!["calls_external".to_owned()],
+        vec!["calls_others".to_owned()],
+    ]);
+
+    // every function's callees are in its own group or an earlier one
+    for path in &["tests/handwritten_bc/recursion.bc", "tests/handwritten_bc/callsites.bc", "tests/basic_bc/linkedlist.bc", "tests/basic_bc/rust.bc"] {
+        let module = Module::from_bc_path(&Path::new(path)).expect("Failed to parse module");
+        let callgraph = CallGraph::new(&module);
+        let groups = callgraph.topo_order();
+        assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), module.functions.len());
+        let group_of = |name: &str| groups.iter().position(|group| group.iter().any(|f| f == name));
+        for func in &module.functions {
+            for callee in callgraph.callees(&func.name) {
+                if let Some(callee_group) = group_of(callee) {
+                    assert!(callee_group <= group_of(&func.name).unwrap(), "{} should come after {}", func.name, callee);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn global_ctors_and_dtors() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/entrypoints.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let ctors = module.global_ctors();
+    assert_eq!(ctors.len(), 2);
+    assert_eq!(ctors[0].priority, 101);
+    assert_eq!(ctors[0].function, Name::from("early_ctor"));
+    assert_eq!(ctors[0].associated_data, Some(Name::from("ctor_data")));
+    assert_eq!(ctors[1].priority, 200);
+    assert_eq!(ctors[1].function, Name::from("late_ctor"));
+    assert_eq!(ctors[1].associated_data, None);
+
+    let dtors = module.global_dtors();
+    assert_eq!(dtors.len(), 1);
+    assert_eq!(dtors[0].priority, 65535);
+    assert_eq!(dtors[0].function, Name::from("dtor"));
+
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert!(module.global_ctors().is_empty());
+}
+
+#[test]
+fn used_symbols() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/entrypoints.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert_eq!(module.used_symbols(), vec![Name::from("kept"), Name::from("ctor_data")]);
+    assert_eq!(module.compiler_used_symbols(), vec![Name::from("compiler_kept")]);
+
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert!(module.used_symbols().is_empty());
+}
+
+#[test]
+fn duplicate_symbols() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/entrypoints.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert!(module.find_duplicate_symbols().is_empty());
+
+    // LLVM won't let us parse a module with duplicate names, so make one by hand
+    let mut var = module.get_global_var_by_name(&Name::from("ctor_data")).unwrap().clone();
+    var.name = Name::from("helper");
+    module.global_vars.push(var);
+    let mut func = module.get_func_by_name("main").unwrap().clone();
+    func.basic_blocks.clear();
+    module.functions.push(func);
+    assert_eq!(module.find_duplicate_symbols(), vec![
+        ("helper".to_owned(), vec![SymbolKind::Function, SymbolKind::GlobalVariable]),
+        ("main".to_owned(), vec![SymbolKind::Function, SymbolKind::Declaration]),
+    ]);
+}
+
+#[test]
+fn module_diff() {
+    init_logging();
+    let old = Module::from_bc_path(&Path::new("tests/handwritten_bc/diff_old.bc")).expect("Failed to parse module");
+    let new = Module::from_bc_path(&Path::new("tests/handwritten_bc/diff_new.bc")).expect("Failed to parse module");
+
+    assert!(old.diff(&old).is_empty());
+
+    let diff: ModuleDiff = old.diff(&new);
+    assert_eq!(diff.added_functions, vec!["added".to_owned()]);
+    assert_eq!(diff.removed_functions, vec!["removed".to_owned()]);
+    assert_eq!(diff.changed_functions.len(), 1);
+    let modified = &diff.changed_functions[0];
+    assert_eq!(modified.name, "modified");
+    assert!(modified.added_blocks.is_empty());
+    assert!(modified.removed_blocks.is_empty());
+    assert_eq!(modified.changed_blocks, vec![Name::from("pos"), Name::from("exit")]);
+    assert_eq!(modified.old_instruction_count, 6);
+    assert_eq!(modified.new_instruction_count, 7);
+    assert_eq!(diff.added_globals, vec![Name::from("added_var")]);
+    assert!(diff.removed_globals.is_empty());
+    assert_eq!(diff.changed_globals, vec![Name::from("limit")]);
+
+    let reverse = new.diff(&old);
+    assert_eq!(reverse.added_functions, vec!["removed".to_owned()]);
+    assert_eq!(reverse.removed_functions, vec!["added".to_owned()]);
+    assert_eq!(reverse.removed_globals, vec![Name::from("added_var")]);
+
+    // functions are compared by their structural hashes
+    let func_hash = |module: &Module, name: &str| module.get_func_by_name(name).expect("Failed to find function").structural_hash();
+    assert_eq!(func_hash(&old, "unchanged"), func_hash(&new, "unchanged"));
+    assert_ne!(func_hash(&old, "modified"), func_hash(&new, "modified"));
+    assert_ne!(old.structural_hash(), new.structural_hash());
+    let reparsed = Module::from_bc_path(&Path::new("tests/handwritten_bc/diff_old.bc")).expect("Failed to parse module");
+    assert_eq!(old.structural_hash(), reparsed.structural_hash());
+
+    #[cfg(feature = "bincode")]
+    {
+        let serialized = bincode::serialize(&diff).expect("Failed to serialize diff");
+        let deserialized: ModuleDiff = bincode::deserialize(&serialized).expect("Failed to deserialize diff");
+        assert_eq!(deserialized, diff);
+    }
+
+    // changes to debug locations alone aren't reported
+    let mut moved = old.clone();
+    let debugloc = DebugLoc {
+        line: 42,
+        col: Some(3),
+        filename: "moved.c".to_owned(),
+        directory: None,
+        function: None,
+        inlined_at: None,
+        scope: None,
+    };
+    for func in &mut moved.functions {
+        func.debugloc = Some(debugloc.clone());
+        for bb in &mut func.basic_blocks {
+            if let Terminator::Ret(ret) = &mut bb.term {
+                ret.debugloc = Some(debugloc.clone());
+            }
+        }
+    }
+    for var in &mut moved.global_vars {
+        var.debugloc = Some(debugloc.clone());
+    }
+    assert_ne!(moved.functions, old.functions);
+    assert_eq!(moved.structural_hash(), old.structural_hash());
+    assert!(old.diff(&moved).is_empty());
+}
+
+#[test]
+fn gep_bounds() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/gep_bounds.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let dl = DataLayout::parse(&module.data_layout).expect("Failed to parse data layout");
+    let func = module.get_func_by_name("geps").expect("Failed to find function");
+    let geps: Vec<instruction::GetElementPtr> = func.basic_blocks[0]
+        .instrs
+        .iter()
+        .take(7)
+        .map(|inst| inst.clone().try_into().expect("Should be a getelementptr"))
+        .collect();
+
+    assert!(geps[0].in_bounds);
+    assert_eq!(geps[0].is_in_bounds_statically(&dl, &module), Some(true));
+    assert!(!geps[1].in_bounds);
+    assert_eq!(geps[1].is_in_bounds_statically(&dl, &module), Some(false));
+    assert_eq!(geps[2].is_in_bounds_statically(&dl, &module), Some(false));
+    assert_eq!(geps[3].is_in_bounds_statically(&dl, &module), Some(true)); // out of range of the inner array, but not of the object
+    assert_eq!(geps[4].is_in_bounds_statically(&dl, &module), Some(true));
+    assert_eq!(geps[5].is_in_bounds_statically(&dl, &module), Some(false));
+    assert_eq!(geps[6].is_in_bounds_statically(&dl, &module), None);
+}
+
+#[test]
+fn archives() {
+    init_logging();
+    for path in &["tests/handwritten_bc/archive.a", "tests/handwritten_bc/thin_archive.a"] {
+        let modules = Module::from_archive_path(Path::new(path)).expect("Failed to parse archive");
+        let names: Vec<&str> = modules.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["callsites.bc", "atomics.bc"]);
+        assert!(modules[0].1.get_func_by_name("caller").is_some());
+        assert!(modules[1].1.get_func_by_name("atomics").is_some());
+    }
+
+    assert!(Module::from_archive_path(Path::new("tests/handwritten_bc/callsites.bc")).is_err());
+}
+
+#[test]
+fn directories() {
+    init_logging();
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("directories");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("nested")).expect("Failed to create directory");
+    std::fs::copy("tests/handwritten_bc/callsites.bc", dir.join("a.bc")).expect("Failed to copy file");
+    std::fs::copy("tests/handwritten_bc/atomics.bc", dir.join("b.bc")).expect("Failed to copy file");
+    std::fs::write(dir.join("c.bc"), b"this is not bitcode").expect("Failed to write file");
+    std::fs::write(dir.join("notes.txt"), b"ignored").expect("Failed to write file");
+    std::fs::copy("tests/handwritten_bc/recursion.bc", dir.join("nested").join("d.bc")).expect("Failed to copy file");
+    std::fs::copy("tests/handwritten_bc/alias.ll", dir.join("e.ll")).expect("Failed to copy file");
+    std::fs::write(dir.join("f.ll"), b"define void @f( {").expect("Failed to write file");
+
+    let results = Module::from_directory(&dir, false);
+    let names: Vec<_> = results.iter().map(|(path, _)| path.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, vec!["a.bc", "b.bc", "c.bc", "e.ll", "f.ll"]);
+    assert!(results[0].1.as_ref().expect("Failed to parse module").get_func_by_name("caller").is_some());
+    assert!(results[1].1.as_ref().expect("Failed to parse module").get_func_by_name("atomics").is_some());
+    assert!(results[2].1.is_err());
+    assert!(results[3].1.as_ref().expect("Failed to parse module").get_func_by_name("pointers").is_some());
+    assert!(results[4].1.is_err());
+
+    let results = Module::from_directory(&dir, true);
+    assert_eq!(results.len(), 6);
+    assert_eq!(results.iter().filter(|(_, result)| result.is_ok()).count(), 4);
+    assert_eq!(results[5].0, dir.join("nested").join("d.bc"));
+
+    let results = Module::from_directory(dir.join("nonexistent"), true);
+    assert_eq!(results.len(), 1);
+    assert!(results[0].1.is_err());
+}
+
+#[test]
+fn value_numbering() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/numbering.bc");
+    let (module, numbering) = Module::from_bc_path_with_numbering(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("numbered").expect("Failed to find function");
+
+    // before the void invoke, llvm-ir's numbering agrees with llvm-dis
+    assert_eq!(func.parameters[0].name, Name::Number(0));
+    assert_eq!(numbering.get("numbered", &Name::Number(0)), Some(0));
+    assert_eq!(numbering.get("numbered", &Name::from("named")), None);
+    let add: &instruction::Add = &func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be an add");
+    assert_eq!(add.dest, Name::Number(2));
+    assert_eq!(numbering.get("numbered", &add.dest), Some(2));
+
+    // the void invoke has a `Name` in llvm-ir, but no number in llvm-dis
+    let invoke: &terminator::Invoke = &func.basic_blocks[0].term.clone().try_into().expect("Should be an invoke");
+    assert_eq!(numbering.get("numbered", &invoke.result), None);
+
+    // after it, numbers are off by one: llvm-dis prints `3:`, `%4 = call`, `6:`, and `%7 = landingpad`
+    let cont = &func.basic_blocks[1];
+    assert_eq!(numbering.get("numbered", &cont.name), Some(3));
+    let call: &instruction::Call = &cont.instrs[0].clone().try_into().expect("Should be a call");
+    assert_eq!(numbering.get("numbered", call.dest.as_ref().unwrap()), Some(4));
+    let lpad = &func.basic_blocks[2];
+    assert_eq!(numbering.get("numbered", &lpad.name), Some(6));
+    let landingpad: &instruction::LandingPad = &lpad.instrs[0].clone().try_into().expect("Should be a landingpad");
+    assert_eq!(numbering.get("numbered", &landingpad.dest), Some(7));
+}
+
+#[test]
+fn all_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/gep_bounds.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let types = module.all_types();
+
+    let arr = Type::ArrayType { element_type: Box::new(Type::i32()), num_elements: 4 };
+    let pair = module.named_struct_types.get("pair").expect("Failed to find struct type").as_ref().map(std::sync::Arc::downgrade);
+    let pair = Type::NamedStructType { name: "pair".to_owned(), ty: pair };
+    assert!(types.contains(&Type::i8()));
+    assert!(types.contains(&Type::i32()));
+    assert!(types.contains(&Type::i64()));
+    assert!(types.contains(&Type::VoidType));
+    assert!(types.contains(&Type::pointer_to(arr.clone())));
+    assert!(types.contains(&Type::ArrayType { element_type: Box::new(arr), num_elements: 2 }));
+    assert!(types.contains(&Type::pointer_to(Type::i32())));
+    assert!(types.contains(&pair));
+    assert!(types.contains(&Type::ArrayType { element_type: Box::new(pair), num_elements: 2 }));
+    assert!(types.contains(&Type::FuncType { result_type: Box::new(Type::VoidType), param_types: vec![Type::i64()], is_var_arg: false }));
+    assert!(!types.contains(&Type::double()));
+    // the body of %pair is not a type used in the module
+    assert!(!types.contains(&Type::StructType { element_types: vec![Type::i8(), Type::i32()], is_packed: false }));
+}
+
+#[test]
+fn debug_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/ditypes.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("locals").expect("Failed to find function");
+    let resolve = |id: Option<MetadataNodeID>| module.resolve_ditype(id.expect("Expected a type")).expect("Failed to resolve type");
+
+    // pair_t p;
+    let local_variables = func.local_variables(&module);
+    let p = &local_variables[0];
+    assert_eq!(p.name, "p");
+    let (struct_id, typedef_name) = match resolve(p.ty) {
+        DIType::Derived { tag: DITag::Typedef, name, base, .. } => (*base, name.as_deref()),
+        ty => panic!("Expected a typedef, got {:?}", ty),
+    };
+    assert_eq!(typedef_name, Some("pair_t"));
+    let members = match resolve(struct_id) {
+        DIType::Composite { tag: DITag::StructureType, name, members, size_in_bits, .. } => {
+            assert_eq!(name.as_deref(), Some("pair"));
+            assert_eq!(*size_in_bits, 128);
+            members.clone()
+        },
+        ty => panic!("Expected a struct, got {:?}", ty),
+    };
+    assert_eq!(members.len(), 2);
+    match resolve(Some(members[0])) {
+        DIType::Derived { tag: DITag::Member, name, base, offset_in_bits, .. } => {
+            assert_eq!(name.as_deref(), Some("count"));
+            assert_eq!(*offset_in_bits, 0);
+            assert_eq!(resolve(*base), &DIType::Basic { name: "int".to_owned(), size_in_bits: 32 });
+        },
+        ty => panic!("Expected a member, got {:?}", ty),
+    }
+    match resolve(Some(members[1])) {
+        DIType::Derived { tag: DITag::Member, name, base, offset_in_bits, .. } => {
+            assert_eq!(name.as_deref(), Some("name"));
+            assert_eq!(*offset_in_bits, 64);
+            match resolve(*base) {
+                DIType::Derived { tag: DITag::PointerType, base, .. } => {
+                    assert_eq!(resolve(*base).name(), Some("char"));
+                },
+                ty => panic!("Expected a pointer, got {:?}", ty),
+            }
+        },
+        ty => panic!("Expected a member, got {:?}", ty),
+    }
+
+    // const int arr[4];
+    let arr = &local_variables[1];
+    assert_eq!(arr.name, "arr");
+    match resolve(arr.ty) {
+        DIType::Composite { tag: DITag::ArrayType, base, members, .. } => {
+            assert!(members.is_empty());
+            match resolve(*base) {
+                DIType::Derived { tag: DITag::ConstType, base, .. } => assert_eq!(resolve(*base).name(), Some("int")),
+                ty => panic!("Expected a const type, got {:?}", ty),
+            }
+        },
+        ty => panic!("Expected an array, got {:?}", ty),
+    }
+    // the two variables share the DIType for `int`
+    assert_eq!(module.debug_types.iter().filter(|ty| ty.name() == Some("int")).count(), 1);
+}
+
+#[test]
+fn block_accessors() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/numbering.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("numbered").expect("Failed to find function");
+    let entry = func.entry_block().expect("Function should have an entry block");
+    assert_eq!(entry.name, Name::Number(1));
+    let add: &instruction::Add = &entry.first_instruction().expect("Entry block should have an instruction").clone().try_into().expect("Should be an add");
+    assert_eq!(add.dest, Name::Number(2));
+    assert_eq!(entry.last_non_terminator(), entry.first_instruction());
+    let invoke: &terminator::Invoke = &entry.terminator().clone().try_into().expect("Should be an invoke");
+    assert_eq!(invoke.return_label, Name::Number(4));  // llvm-ir numbers the void invoke itself as 3
+
+    let lpad = &func.basic_blocks[2];
+    assert!(lpad.first_instruction().is_some());
+    assert!(llvm_ir::Function::new("empty").entry_block().is_none());
+    assert!(llvm_ir::BasicBlock::new(Name::from("bb")).first_instruction().is_none());
+}
+
+#[test]
+fn shufflevector_masks() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/shufflevector.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("shuffle").expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+    let masks: Vec<Vec<i32>> = bb
+        .instrs
+        .iter()
+        .map(|inst| {
+            let shuffle: instruction::ShuffleVector = inst.clone().try_into().expect("Should be a shufflevector");
+            shuffle.mask()
+        })
+        .collect();
+    assert_eq!(masks, vec![vec![0, 2, -1], vec![0, 0, 0], vec![7, 6, 5]]);
+
+    let shuffle: instruction::ShuffleVector = bb.instrs[0].clone().try_into().expect("Should be a shufflevector");
+    assert_eq!(shuffle.get_type(), Type::VectorType { element_type: Box::new(Type::i32()), num_elements: 3 });
+}
+
+#[test]
+fn shufflevector_mask_from_text() {
+    let text = "%3 = shufflevector <4 x i32> %1, <4 x i32> %2, <2 x i32> <i32 0, i32 undef>";
+    assert_eq!(
+        instruction::ShuffleVector::mask_from_text(text, 2),
+        Ok(Constant::Vector(vec![Constant::Int { bits: 32, value: 0 }, Constant::Undef(Type::i32())])),
+    );
+    // wrong number of elements
+    assert!(instruction::ShuffleVector::mask_from_text(text, 3).is_err());
+    // not a shufflevector
+    assert!(instruction::ShuffleVector::mask_from_text("%x = add i32 %a, %b", 1).is_err());
+    // malformed elements
+    assert!(instruction::ShuffleVector::mask_from_text("shufflevector <2 x i32> <i32 x, i32 1>", 2).is_err());
+}
+
+#[test]
+fn returns() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/numbering.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("numbered").expect("Failed to find function");
+    let returns = func.returns();
+    assert_eq!(returns.len(), 2);
+    // llvm-ir's numbering is offset from the .ll's by the (numbered) void invoke
+    assert_eq!(returns[0].0.name, Name::Number(4));
+    assert_eq!(returns[1].0.name, Name::Number(7));
+    assert_eq!(func.return_values(), vec![
+        Some(&Operand::LocalOperand { name: Name::Number(6), ty: Type::i32() }),
+        Some(&Operand::ConstantOperand(Constant::Int { bits: 32, value: 0 })),
+    ]);
+
+    let path = Path::new("tests/handwritten_bc/shufflevector.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("shuffle").expect("Failed to find function");
+    assert_eq!(func.returns().len(), 1);
+}
+
+#[test]
+fn vector_geps() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/vector_gep.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let i32_ptrs = Type::VectorType { element_type: Box::new(Type::pointer_to(Type::i32())), num_elements: 4 };
+
+    let func = module.get_func_by_name("vector_base").expect("Failed to find function");
+    let gep: instruction::GetElementPtr = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be a gep");
+    assert_eq!(gep.address.get_type(), i32_ptrs);
+    assert_eq!(gep.get_type(), i32_ptrs);
+
+    let func = module.get_func_by_name("scalar_base").expect("Failed to find function");
+    let gep: instruction::GetElementPtr = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be a gep");
+    assert_eq!(gep.address.get_type(), Type::pointer_to(Type::i32()));
+    assert_eq!(gep.get_type(), i32_ptrs);
+
+    let func = module.get_func_by_name("struct_field").expect("Failed to find function");
+    let gep: instruction::GetElementPtr = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be a gep");
+    match gep.get_type() {
+        Type::VectorType { element_type, num_elements: 2 } => match *element_type {
+            Type::PointerType { pointee_type, .. } => assert_eq!(*pointee_type, Type::i64()),
+            ty => panic!("Expected a PointerType, got {:?}", ty),
+        },
+        ty => panic!("Expected a VectorType, got {:?}", ty),
+    }
+}
+
+#[test]
+fn vector_casts() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/vector_casts.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let vector = |element_type: Type, num_elements: usize| Type::VectorType { element_type: Box::new(element_type), num_elements };
+
+    let func = module.get_func_by_name("ptrtoint").expect("Failed to find function");
+    let ptrtoint: instruction::PtrToInt = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be a ptrtoint");
+    assert_eq!(ptrtoint.operand.get_type(), vector(Type::pointer_to(Type::i8()), 4));
+    assert_eq!(ptrtoint.get_type(), vector(Type::i64(), 4));
+
+    let func = module.get_func_by_name("inttoptr").expect("Failed to find function");
+    let inttoptr: instruction::IntToPtr = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be an inttoptr");
+    assert_eq!(inttoptr.operand.get_type(), vector(Type::i64(), 2));
+    assert_eq!(inttoptr.get_type(), vector(Type::pointer_to(Type::i32()), 2));
+
+    let func = module.get_func_by_name("addrspacecast").expect("Failed to find function");
+    let cast: instruction::AddrSpaceCast = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be an addrspacecast");
+    assert_eq!(cast.operand.get_type(), vector(Type::pointer_to(Type::i8()), 4));
+    assert_eq!(
+        cast.get_type(),
+        vector(Type::PointerType { pointee_type: Box::new(Type::i8()), addr_space: 1 }, 4),
+    );
+}
+
+#[test]
+fn static_stack_sizes() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/allocas.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let dl = DataLayout::parse(&module.data_layout).expect("Failed to parse data layout");
+    let stack_size = |name: &str| module.get_func_by_name(name).expect("Failed to find function").static_stack_size(&dl);
+
+    assert_eq!(stack_size("static"), Some(64 + 4));
+    // 10 x i32, and 2 x { i8, i64 }
+    assert_eq!(stack_size("arrays"), Some(10 * 4 + 2 * 16));
+    assert_eq!(stack_size("dynamic"), None);
+    assert_eq!(stack_size("none"), Some(0));
+}
+
+#[test]
+fn constant_display() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/constants.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let init = |name: &str| {
+        module
+            .get_global_var_by_name(&Name::from(name))
+            .expect("Failed to find global")
+            .initializer
+            .as_ref()
+            .expect("Should have an initializer")
+            .to_string()
+    };
+    assert_eq!(init("i"), "i32 -7");
+    assert_eq!(init("flag"), "i1 true");
+    assert_eq!(init("f"), "float 1.500000e+00");
+    assert_eq!(init("d"), "double 1.000000e-01");
+    assert_eq!(init("str"), r#"[7 x i8] c"hi \22x\22\00""#);
+    assert_eq!(init("vec"), "<2 x i64> <i64 1, i64 -1>");
+    assert_eq!(
+        init("pair"),
+        "%struct.pair { i32 5, i8* getelementptr inbounds ([7 x i8], [7 x i8]* @str, i64 0, i64 1) }",
+    );
+    assert_eq!(init("literal"), "{ i32, i8* } { i32 1, i8* null }");
+    assert_eq!(init("packed"), "<{ i8, i32 }> <{ i8 1, i32 2 }>");
+    assert_eq!(init("cmp"), r#"i1 icmp ult (i32* @i, i32* @"quoted global")"#);
+    assert_eq!(Constant::Float(llvm_ir::constant::Float::Double(0.1 + 0.2)).to_string(), "double 0x3FD3333333333334");
+    assert_eq!(init("half"), "half 0xH3C00");
+    assert_eq!(init("quad"), "fp128 0xL0123456789ABCDEF3FFF000000000001");
+    assert_eq!(init("x87"), "x86_fp80 0xK4000C90FDAA22168C000");
+    assert_eq!(init("ppc"), "ppc_fp128 0xM3FF00000000000000000000000000001");
+    assert_eq!(Constant::Undef(Type::i32()).to_string(), "i32 undef");
+}
+
+#[test]
+fn float_bits() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/constants.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let init = |name: &str| {
+        module.get_global_var_by_name(&Name::from(name)).expect("Failed to find global").initializer.clone()
+    };
+    use llvm_ir::constant::Float;
+    assert_eq!(init("half"), Some(Constant::Float(Float::Half(0x3C00))));
+    assert_eq!(init("quad"), Some(Constant::Float(Float::Quadruple(0x3FFF000000000001_0123456789ABCDEF))));
+    assert_eq!(init("x87"), Some(Constant::Float(Float::X86_FP80((0x4000, 0xC90FDAA22168C000)))));
+    assert_eq!(init("ppc"), Some(Constant::Float(Float::PPC_FP128((0x0000000000000001, 0x3FF0000000000000)))));
+}
+
+#[test]
+fn block_addresses() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/blockaddress.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let table = module
+        .get_global_var_by_name(&Name::from("table"))
+        .expect("Failed to find global")
+        .initializer
+        .as_ref()
+        .expect("Should have an initializer");
+    let addr = |block: Name| Constant::BlockAddress { function: Name::from("pick"), block };
+    assert_eq!(table, &Constant::Array {
+        element_type: Type::pointer_to(Type::i8()),
+        elements: vec![addr(Name::from("a")), addr(Name::from(0usize))],
+    });
+    assert_eq!(table.to_string(), "[2 x i8*] [i8* blockaddress(@pick, %a), i8* blockaddress(@pick, %0)]");
+
+    // the blocks are named the same as the blocks of the decoded function
+    let func = module.get_func_by_name("pick").expect("Failed to find function");
+    let names: Vec<&Name> = func.basic_blocks.iter().map(|bb| &bb.name).collect();
+    assert_eq!(names, vec![&Name::from("entry"), &Name::from("a"), &Name::from(0usize)]);
+}
+
+/// Printing every initializer in `constants.bc` into a new module and
+/// assembling it with `llvm-as` should reproduce the same constants
+#[test]
+#[ignore = "requires llvm-as on the PATH"]
+fn constant_display_reassembles() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/constants.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let mut text = format!("target datalayout = \"{}\"\n", module.data_layout);
+    let mut struct_names: Vec<&String> = module.named_struct_types.keys().collect();
+    struct_names.sort();
+    for name in struct_names {
+        let def = match &module.named_struct_types[name] {
+            Some(ty) => ty.read().unwrap().to_string(),
+            None => "opaque".to_owned(),
+        };
+        text += &format!("{} = type {}\n", Type::NamedStructType { name: name.clone(), ty: None }, def);
+    }
+    for var in &module.global_vars {
+        let name = match var.name.as_str() {
+            Some(name) if name.contains(' ') => format!("@\"{}\"", name),
+            Some(name) => format!("@{}", name),
+            None => format!("@{}", var.name.as_number().unwrap()),
+        };
+        text += &format!("{} = global {}\n", name, var.initializer.as_ref().expect("Should have an initializer"));
+    }
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let ll_path = dir.join("constant_display.ll");
+    let bc_path = dir.join("constant_display.bc");
+    std::fs::write(&ll_path, text).expect("Failed to write module");
+    let output = std::process::Command::new("llvm-as")
+        .arg(&ll_path)
+        .arg("-o")
+        .arg(&bc_path)
+        .output()
+        .expect("Failed to run llvm-as");
+    assert!(
+        output.status.success(),
+        "llvm-as failed on {}:\n{}",
+        ll_path.display(),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    let reparsed = Module::from_bc_path(&bc_path).expect("Failed to parse module");
+    assert_eq!(reparsed.global_vars.len(), module.global_vars.len());
+    for (var, reparsed_var) in module.global_vars.iter().zip(&reparsed.global_vars) {
+        assert_eq!(var.name, reparsed_var.name);
+        assert_eq!(var.initializer, reparsed_var.initializer);
+    }
+}
+
+#[test]
+fn parse_warnings() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/lossy.bc");
+    let (module, warnings) = Module::from_bc_path_verbose(&path).expect("Failed to parse module");
+    assert_eq!(module.global_vars.len(), 2);
+    assert_eq!(warnings, vec![
+        ParseWarning::ComdatName { global: Name::from("in_comdat") },
+        ParseWarning::ComdatName { global: Name::from("shared") },
+        ParseWarning::MetadataOperand {
+            location: InstructionRef { function: "read_sp".to_owned(), block: Name::from(0usize), index: 0 },
+        },
+    ]);
+    assert_eq!(warnings[0].to_string(), "global \"in_comdat\": comdat name could not be decoded");
+
+    // modules without such constructs (including debug intrinsics, whose
+    // metadata is decoded) produce no warnings
+    let (_, warnings) = Module::from_bc_path_verbose("tests/basic_bc/variables.bc-g").expect("Failed to parse module");
+    assert_eq!(warnings, vec![]);
+}
+
+#[test]
+fn tail_calls() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/tailcalls.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("caller").expect("Failed to find function");
+    let tails: Vec<TailCallKind> = func.basic_blocks[0]
+        .instrs
+        .iter()
+        .map(|instr| {
+            let call: instruction::Call = instr.clone().try_into().expect("Should be a call");
+            call.tail
+        })
+        .collect();
+    assert_eq!(tails, vec![TailCallKind::None, TailCallKind::Tail, TailCallKind::NoTail, TailCallKind::MustTail]);
+
+    let func = module.get_func_by_name("quoted_musttail").expect("Failed to find function");
+    let call: instruction::Call = func.basic_blocks[0].instrs[0].clone().try_into().expect("Should be a call");
+    assert_eq!(call.tail, TailCallKind::MustTail);
+}
+
+#[test]
+fn tail_call_kind_from_text() {
+    assert_eq!(TailCallKind::from_text("%5 = musttail call i32 @f(i32 %x)"), Some(TailCallKind::MustTail));
+    assert_eq!(TailCallKind::from_text("%\"a = b\" = tail call i32 @f()"), Some(TailCallKind::Tail));
+    assert_eq!(TailCallKind::from_text("notail call void @g()"), Some(TailCallKind::NoTail));
+    assert_eq!(TailCallKind::from_text("call void @g()"), Some(TailCallKind::None));
+    assert_eq!(TailCallKind::from_text("%x = add i32 %a, %b"), None);
+    assert_eq!(TailCallKind::from_text("%\""), None);
+}
+
+#[test]
+fn inline_chains() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/inline.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("outer").expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+    let frame = |function: &str, line: u32, col: u32| Frame { function: Some(function.to_owned()), line, col: Some(col) };
+
+    // `inner` inlined into `outer`; the location is in a lexical block of `inner`
+    let loc = bb.instrs[1].get_debug_loc().as_ref().expect("Should have a debugloc");
+    assert_eq!(loc.function.as_deref(), Some("inner"));
+    assert_eq!(module.inline_chain(loc), vec![frame("inner", 2, 52), frame("outer", 3, 27)]);
+
+    // `leaf` inlined into `inner` inlined into `outer`
+    let loc = bb.instrs[0].get_debug_loc().as_ref().expect("Should have a debugloc");
+    assert_eq!(module.inline_chain(loc), vec![frame("leaf", 1, 35), frame("inner", 2, 44), frame("outer", 3, 27)]);
+    let inlined_at = loc.inlined_at.as_ref().expect("Should be inlined");
+    assert_eq!(inlined_at.filename, "inline.c");
+    assert_eq!(inlined_at.directory.as_deref(), Some("/tmp"));
+
+    // not inlined
+    let loc = bb.term.get_debug_loc().as_ref().expect("Should have a debugloc");
+    assert_eq!(loc.inlined_at, None);
+    assert_eq!(module.inline_chain(loc), vec![frame("outer", 3, 20)]);
+}
+
+#[test]
+fn defined_and_external_globals() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/globals.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let defined: Vec<&Name> = module.defined_globals().map(|var| &var.name).collect();
+    assert_eq!(defined, vec![&Name::from("filled_in_by_loader"), &Name::from("ordinary"), &Name::from("constant")]);
+    let external: Vec<&Name> = module.external_globals().map(|var| &var.name).collect();
+    assert_eq!(external, vec![&Name::from("declared"), &Name::from("declared_constant")]);
+}
+
+#[test]
+fn strip_pointer_casts() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/pointer_casts.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let init = |name: &str| {
+        module
+            .get_global_var_by_name(&Name::from(name))
+            .expect("Failed to find global")
+            .initializer
+            .as_ref()
+            .expect("Should have an initializer")
+    };
+    let referenced = |c: &Constant| match c {
+        Constant::GlobalReference { name, .. } => Some(name.clone()),
+        _ => None,
+    };
+
+    assert_eq!(referenced(init("bitcast")), None);
+    assert_eq!(referenced(init("bitcast").strip_pointer_casts()), Some(Name::from("g")));
+    assert_eq!(referenced(init("zero_gep").strip_pointer_casts()), Some(Name::from("arr")));
+    assert_eq!(referenced(init("nested").strip_pointer_casts()), Some(Name::from("g")));
+
+    // these aren't pointer casts, so are returned unchanged
+    assert_eq!(init("nonzero_gep").strip_pointer_casts(), init("nonzero_gep"));
+    assert_eq!(init("ptrtoint").strip_pointer_casts(), init("ptrtoint"));
+    let g = Constant::GlobalReference { name: Name::from("g"), ty: Type::i8() };
+    assert_eq!(g.strip_pointer_casts(), &g);
+}
+
+#[test]
+fn referenced_globals() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/pointer_casts.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let init = |name: &str| {
+        module
+            .get_global_var_by_name(&Name::from(name))
+            .expect("Failed to find global")
+            .initializer
+            .as_ref()
+            .expect("Should have an initializer")
+    };
+
+    assert_eq!(init("pair").referenced_globals(), vec![&Name::from("a"), &Name::from("b")]);
+    // each global is reported once, in the order first encountered
+    assert_eq!(init("sums").referenced_globals(), vec![&Name::from("b"), &Name::from("a")]);
+    assert_eq!(init("nested").referenced_globals(), vec![&Name::from("g")]);
+    assert!(Constant::Int { bits: 8, value: 0 }.referenced_globals().is_empty());
+}
+
+#[test]
+fn atomicrmw_operations() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/atomicrmw.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = module.get_func_by_name("rmw").expect("Failed to find function");
+    let rmws: Vec<instruction::AtomicRMW> = func.basic_blocks[0]
+        .instrs
+        .iter()
+        .map(|instr| instr.clone().try_into().expect("Should be an atomicrmw"))
+        .collect();
+    let ops: Vec<RMWOperation> = rmws.iter().map(|rmw| rmw.operation).collect();
+    assert_eq!(ops, vec![RMWOperation::Add, RMWOperation::UMin, RMWOperation::FAdd, RMWOperation::FSub]);
+
+    let fadd = &rmws[2];
+    assert_eq!(fadd.address, Operand::LocalOperand { name: Name::from("f"), ty: Type::pointer_to(Type::single()) });
+    assert_eq!(fadd.get_type(), Type::single());
+    assert!(!fadd.volatile);
+    assert_eq!(fadd.atomicity.mem_ordering, MemoryOrdering::SequentiallyConsistent);
+    assert!(rmws[3].volatile);
+    assert_eq!(rmws[3].atomicity.synch_scope, SynchronizationScope::SingleThread);
+}
+
+#[test]
+fn atomicrmw_operation_from_text() {
+    assert_eq!(RMWOperation::from_text("%old = atomicrmw volatile xchg i32* %p, i32 1 seq_cst"), RMWOperation::Xchg);
+    assert_eq!(RMWOperation::from_text("%old = atomicrmw uinc_wrap ptr %p, i32 1 seq_cst"), RMWOperation::Unknown);
+    assert_eq!(RMWOperation::from_text("%x = add i32 %a, %b"), RMWOperation::Unknown);
+}
+
+#[test]
+fn prefix_and_prologue_data() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/prefix.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("with_prefix").expect("Failed to find function");
+    assert_eq!(func.prefix_data, Some(Constant::Int { bits: 32, value: 123 }));
+    assert_eq!(func.prologue_data, None);
+
+    let func = module.get_func_by_name("with_prologue").expect("Failed to find function");
+    assert_eq!(func.prefix_data, None);
+    assert_eq!(func.prologue_data, Some(Constant::Int { bits: 8, value: 144 }));
+
+    let func = module.get_func_by_name("with_both").expect("Failed to find function");
+    assert_eq!(func.prefix_data, Some(Constant::GlobalReference { name: Name::from("info"), ty: Type::i32() }));
+    match &func.prologue_data {
+        Some(Constant::Struct { values, .. }) => {
+            assert_eq!(values, &vec![Constant::Int { bits: 8, value: 235 }, Constant::Int { bits: 8, value: 6 }])
+        },
+        data => panic!("Expected a struct constant, got {:?}", data),
+    }
+    assert!(func.personality_function.is_some());
+
+    let func = module.get_func_by_name("with_neither").expect("Failed to find function");
+    assert_eq!(func.prefix_data, None);
+    assert_eq!(func.prologue_data, None);
+
+    // module-wide rewrites also apply to prefix and prologue data
+    let mut renamed = module.clone();
+    renamed.rename_symbol("info", "info2").expect("Failed to rename");
+    let func = renamed.get_func_by_name("with_both").expect("Failed to find function");
+    assert_eq!(func.prefix_data, Some(Constant::GlobalReference { name: Name::from("info2"), ty: Type::i32() }));
+
+    let mut mapped = module.clone();
+    mapped.map_types(|ty| if *ty == Type::i8() { Type::i16() } else { ty.clone() });
+    let func = mapped.get_func_by_name("with_prologue").expect("Failed to find function");
+    assert_eq!(func.prologue_data, Some(Constant::Int { bits: 16, value: 144 }));
+}
+
+#[test]
+fn garbage_collector_names() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/gc.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    let func = module.get_func_by_name("statepoint").expect("Failed to find function");
+    assert_eq!(func.garbage_collector_name.as_deref(), Some("statepoint-example"));
+    let func = module.get_func_by_name("shadow_stack").expect("Failed to find function");
+    assert_eq!(func.garbage_collector_name.as_deref(), Some("shadow-stack"));
+    let func = module.get_func_by_name("no_gc").expect("Failed to find function");
+    assert_eq!(func.garbage_collector_name, None);
+}
@@ -89,7 +89,7 @@ llvm_test!("tests/llvm_bc/weak-macho-3.5.ll.bc", weak_macho);
 
 use either::Either;
 use llvm_ir::*;
-use llvm_ir::instruction::{Atomicity, MemoryOrdering, SynchronizationScope};
+use llvm_ir::instruction::{Atomicity, MemoryOrdering, RMWOperation, SynchronizationScope};
 use std::convert::TryInto;
 use std::ops::Deref;
 use std::sync::{Arc, RwLock};
@@ -302,6 +302,7 @@ fn atomics() {
     assert_eq!(cmpxchg.atomicity, Atomicity { synch_scope: SynchronizationScope::System, mem_ordering: MemoryOrdering::Monotonic });
     assert_eq!(cmpxchg.failure_memory_ordering, MemoryOrdering::Monotonic);
     let atomicrmw: &instruction::AtomicRMW = &bb.instrs[8].clone().try_into().unwrap_or_else(|_| panic!("Expected an atomicrmw, got {:?}", &bb.instrs[8]));
+    assert_eq!(atomicrmw.operation, RMWOperation::Xchg);
     assert_eq!(atomicrmw.address, Operand::LocalOperand { name: Name::from("word"), ty: Type::pointer_to(Type::i32()) });
     assert_eq!(atomicrmw.value, Operand::ConstantOperand(Constant::Int { bits: 32, value: 12 }));
     assert_eq!(atomicrmw.dest, Name::from("atomicrmw.xchg"));
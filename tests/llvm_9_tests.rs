@@ -41,4 +41,5 @@ fn callbr_parsing() {
     let callbr: &terminator::CallBr = &bb.term.clone().try_into().unwrap_or_else(|_| panic!("Expected a callbr, got {:?}", &bb.term));
     assert!(callbr.function.is_left());
     assert_eq!(callbr.return_label, Name::from("normal"));
+    assert_eq!(callbr.other_labels, vec![Name::from("fail")]);
 }
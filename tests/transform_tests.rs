@@ -0,0 +1,530 @@
+//! Tests of the APIs which mutate `Module`s, `Function`s, etc.
+
+use llvm_ir::instruction;
+use llvm_ir::terminator;
+use llvm_ir::*;
+use std::convert::TryInto;
+use std::path::Path;
+use std::sync::Arc;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+#[test]
+fn renumber_dense_is_noop() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = &module.functions[0];
+    let mut renumbered = func.clone();
+    renumbered.renumber();
+    assert_eq!(&renumbered, func);
+}
+
+#[test]
+fn renumber_sparse() {
+    init_logging();
+    let mut func = Function::new("sparse");
+    func.return_type = Type::i32();
+    func.parameters.push(function::Parameter {
+        name: Name::Number(3),
+        ty: Type::i32(),
+        attributes: vec![],
+    });
+    func.parameters.push(function::Parameter {
+        name: Name::from("named"),
+        ty: Type::i32(),
+        attributes: vec![],
+    });
+
+    let mut entry = BasicBlock::new(Name::Number(7));
+    entry.instrs.push(Instruction::Add(instruction::Add {
+        operand0: Operand::LocalOperand { name: Name::Number(3), ty: Type::i32() },
+        operand1: Operand::LocalOperand { name: Name::from("named"), ty: Type::i32() },
+        dest: Name::Number(10),
+        debugloc: None,
+    }));
+    entry.term = Terminator::Br(terminator::Br { dest: Name::Number(20), debugloc: None });
+    func.basic_blocks.push(entry);
+
+    let mut exit = BasicBlock::new(Name::Number(20));
+    exit.instrs.push(Instruction::Phi(instruction::Phi {
+        incoming_values: vec![(Operand::LocalOperand { name: Name::Number(10), ty: Type::i32() }, Name::Number(7))],
+        dest: Name::Number(42),
+        to_type: Type::i32(),
+        debugloc: None,
+    }));
+    exit.term = Terminator::Ret(terminator::Ret {
+        return_operand: Some(Operand::LocalOperand { name: Name::Number(42), ty: Type::i32() }),
+        debugloc: None,
+    });
+    func.basic_blocks.push(exit);
+
+    func.renumber();
+
+    assert_eq!(func.parameters[0].name, Name::Number(0));
+    assert_eq!(func.parameters[1].name, Name::from("named"));
+    let entry = &func.basic_blocks[0];
+    assert_eq!(entry.name, Name::Number(1));
+    let add: &instruction::Add = &entry.instrs[0].clone().try_into().expect("Should be an add");
+    assert_eq!(add.operand0, Operand::LocalOperand { name: Name::Number(0), ty: Type::i32() });
+    assert_eq!(add.operand1, Operand::LocalOperand { name: Name::from("named"), ty: Type::i32() });
+    assert_eq!(add.dest, Name::Number(2));
+    let br: &terminator::Br = &entry.term.clone().try_into().expect("Should be a br");
+    assert_eq!(br.dest, Name::Number(3));
+    let exit = &func.basic_blocks[1];
+    assert_eq!(exit.name, Name::Number(3));
+    let phi: &instruction::Phi = &exit.instrs[0].clone().try_into().expect("Should be a phi");
+    assert_eq!(phi.incoming_values, vec![(Operand::LocalOperand { name: Name::Number(2), ty: Type::i32() }, Name::Number(1))]);
+    assert_eq!(phi.dest, Name::Number(4));
+    let ret: &terminator::Ret = &exit.term.clone().try_into().expect("Should be a ret");
+    assert_eq!(ret.return_operand, Some(Operand::LocalOperand { name: Name::Number(4), ty: Type::i32() }));
+}
+
+#[test]
+fn resolve_all_aliases() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let callee_ty = module.get_func_by_name("callee").expect("Failed to find function").get_type();
+    module.resolve_all_aliases();
+
+    let func = module.get_func_by_name("caller").expect("Failed to find function");
+    let call: &instruction::Call = &func.basic_blocks[0].instrs[2].clone().try_into().expect("Should be a call");
+    assert_eq!(
+        call.function,
+        either::Either::Right(Operand::ConstantOperand(Constant::GlobalReference { name: Name::from("callee"), ty: callee_ty }))
+    );
+
+    // the aliases themselves are untouched
+    assert_eq!(module.global_aliases.len(), 2);
+    let alias = module.get_global_alias_by_name(&Name::from("alias_of_alias")).expect("Failed to find alias");
+    match &alias.aliasee {
+        Constant::GlobalReference { name, .. } => assert_eq!(name, &Name::from("callee_alias")),
+        c => panic!("Expected aliasee to be a GlobalReference, got {:?}", c),
+    }
+}
+
+#[test]
+fn with_dest() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let func = &module.functions[0];
+    let (inst, add) = func
+        .basic_blocks
+        .iter()
+        .flat_map(|bb| bb.instrs.iter())
+        .find_map(|inst| match inst {
+            Instruction::Add(add) => Some((inst, add)),
+            _ => None,
+        })
+        .expect("Expected an add instruction");
+
+    let cloned = inst.with_dest(Name::from("fresh"));
+    assert_eq!(cloned.try_get_result(), Some(&Name::from("fresh")));
+    let cloned: instruction::Add = cloned.try_into().expect("Should be an add");
+    assert_eq!(cloned.operand0, add.operand0);
+    assert_eq!(cloned.operand1, add.operand1);
+    assert_eq!(cloned.debugloc, add.debugloc);
+
+    // instructions without a result are cloned unchanged
+    let store = func
+        .basic_blocks
+        .iter()
+        .flat_map(|bb| bb.instrs.iter())
+        .find(|inst| matches!(inst, Instruction::Store(_)))
+        .expect("Expected a store instruction");
+    assert_eq!(&store.with_dest(Name::from("fresh")), store);
+}
+
+#[test]
+fn map_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/gep_bounds.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert!(module.all_types().contains(&Type::i32()));
+    module.map_types(|ty| if ty == &Type::i32() { Type::i64() } else { ty.clone() });
+
+    let types = module.all_types();
+    assert!(!types.contains(&Type::i32()));
+    assert!(types.contains(&Type::pointer_to(Type::ArrayType { element_type: Box::new(Type::i64()), num_elements: 4 })));
+
+    // the named struct's body is rewritten in place, so references to it see the new body
+    let pair = module.named_struct_types.get("pair").expect("Failed to find struct type").clone().expect("Struct should not be opaque");
+    let expected_body = Type::StructType { element_types: vec![Type::i8(), Type::i64()], is_packed: false };
+    assert_eq!(*pair.read().unwrap(), expected_body);
+    let pairs = module.get_global_var_by_name(&Name::from("pairs")).expect("Failed to find global");
+    match &pairs.ty {
+        Type::PointerType { pointee_type, .. } => match &**pointee_type {
+            Type::ArrayType { element_type, .. } => match &**element_type {
+                Type::NamedStructType { ty: Some(weak), .. } => {
+                    let body = weak.upgrade().expect("Named struct should still be alive");
+                    assert!(Arc::ptr_eq(&body, &pair));
+                },
+                ty => panic!("Expected a named struct, got {:?}", ty),
+            },
+            ty => panic!("Expected an array, got {:?}", ty),
+        },
+        ty => panic!("Expected a pointer, got {:?}", ty),
+    }
+
+    // stores remain consistent: the stored value has the type the address points to
+    let func = module.get_func_by_name("geps").expect("Failed to find function");
+    let stores: Vec<&Instruction> = func.basic_blocks[0].instrs.iter().filter(|inst| matches!(inst, Instruction::Store(_))).collect();
+    assert_eq!(stores.len(), 7);
+    for inst in stores {
+        let store: instruction::Store = inst.clone().try_into().expect("Should be a store");
+        assert_eq!(store.address.get_type(), Type::pointer_to(store.value.get_type()));
+    }
+    let store: instruction::Store = func.basic_blocks[0].instrs[7].clone().try_into().expect("Should be a store");
+    assert_eq!(store.value, Operand::ConstantOperand(Constant::Int { bits: 64, value: 0 }));
+}
+
+#[test]
+fn canonicalize() {
+    init_logging();
+    let mut a = Module::from_bc_path(&Path::new("tests/handwritten_bc/canonical_a.bc")).expect("Failed to parse module");
+    let mut b = Module::from_bc_path(&Path::new("tests/handwritten_bc/canonical_b.bc")).expect("Failed to parse module");
+    assert_ne!(a.functions, b.functions);
+    assert_ne!(a.global_vars, b.global_vars);
+
+    a.canonicalize();
+    b.canonicalize();
+    let names: Vec<&str> = a.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["add", "bump"]);
+    let add = b.get_func_by_name("add").expect("Failed to find function");
+    assert_eq!(add.basic_blocks[0].instrs.len(), 1); // the llvm.dbg.value call is gone
+    assert!(add.basic_blocks[0].instrs[0].get_debug_loc().is_none());
+    assert!(b.debug_types.is_empty());
+
+    assert_eq!(format!("{:#?}", a.functions), format!("{:#?}", b.functions));
+    assert_eq!(format!("{:#?}", a.global_vars), format!("{:#?}", b.global_vars));
+    assert_eq!(format!("{:#?}", a.global_aliases), format!("{:#?}", b.global_aliases));
+}
+
+#[test]
+fn strip_debug_intrinsics() {
+    init_logging();
+    let is_dbg_call = |inst: &Instruction| match inst.as_intrinsic() {
+        Some(Intrinsic::DbgDeclare) | Some(Intrinsic::DbgValue) => true,
+        Some(Intrinsic::Other(name)) => name.starts_with("llvm.dbg."),
+        _ => false,
+    };
+    for path in &["tests/handwritten_bc/dbg_intrinsics.bc", "tests/basic_bc/variables.bc-g", "tests/basic_bc/rust.bc-g"] {
+        let mut module = Module::from_bc_path(&Path::new(path)).expect("Failed to parse module");
+        let all_instrs = |module: &Module| -> Vec<Instruction> {
+            module.functions.iter().flat_map(|f| &f.basic_blocks).flat_map(|bb| bb.instrs.clone()).collect()
+        };
+        let before = all_instrs(&module);
+        assert!(before.iter().any(is_dbg_call), "Expected llvm.dbg calls in {}", path);
+        assert!(module.functions.iter().any(|f| !f.local_variables(&module).is_empty()));
+        let debug_local_variables = module.debug_local_variables.clone();
+
+        module.strip_debug_intrinsics();
+        let after = all_instrs(&module);
+        assert!(!after.iter().any(is_dbg_call), "Expected llvm.dbg calls to be removed from {}", path);
+        assert_eq!(after, before.into_iter().filter(|inst| !is_dbg_call(inst)).collect::<Vec<_>>());
+        // only the calls are removed; debug info is untouched
+        assert!(after.iter().any(|inst| inst.get_debug_loc().is_some()));
+        assert_eq!(module.debug_local_variables, debug_local_variables);
+        assert!(module.functions.iter().all(|f| f.local_variables(&module).is_empty()));
+        module.verify().unwrap_or_else(|errors| panic!("Failed to verify {}: {:?}", path, errors));
+    }
+
+    // `llvm.dbg.label` isn't decoded specially, but is removed along with the others
+    let module = Module::from_bc_path(&Path::new("tests/handwritten_bc/dbg_intrinsics.bc")).expect("Failed to parse module");
+    let out = module.functions[0].get_bb_by_name(&Name::from("out")).expect("Failed to find block");
+    assert_eq!(out.instrs[0].as_intrinsic(), Some(Intrinsic::Other("llvm.dbg.label".to_owned())));
+}
+
+#[test]
+fn replace_function_body() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/arith.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let original = module.get_func_by_name("arith").unwrap().clone();
+
+    // a body using a value which is defined only in the old body is rejected
+    let mut bad = BasicBlock::new(Name::from("entry"));
+    bad.term = Terminator::Ret(terminator::Ret {
+        return_operand: Some(Operand::LocalOperand { name: Name::from("sum"), ty: Type::i32() }),
+        debugloc: None,
+    });
+    match module.replace_function_body("arith", vec![bad]) {
+        Err(module::ReplaceError::Invalid(errors)) => assert_eq!(errors, vec![VerifyError::UndefinedLocal {
+            function: "arith".to_owned(),
+            block: Name::from("entry"),
+            name: Name::from("sum"),
+        }]),
+        result => panic!("Expected ReplaceError::Invalid, got {:?}", result),
+    }
+    assert_eq!(module.get_func_by_name("arith"), Some(&original));
+
+    assert_eq!(module.replace_function_body("arith", vec![]), Err(module::ReplaceError::NoBlocks));
+    assert_eq!(
+        module.replace_function_body("clamp", vec![BasicBlock::new(Name::from("entry"))]),
+        Err(module::ReplaceError::NotFound("clamp".to_owned())),
+    );
+
+    // replace it with a trivial body returning the first parameter
+    let mut entry = BasicBlock::new(Name::from("entry"));
+    entry.term = Terminator::Ret(terminator::Ret {
+        return_operand: Some(Operand::LocalOperand { name: Name::from("a"), ty: Type::i32() }),
+        debugloc: None,
+    });
+    module.replace_function_body("arith", vec![entry.clone()]).expect("Replacement should succeed");
+    let func = module.get_func_by_name("arith").unwrap();
+    assert_eq!(func.basic_blocks, vec![entry]);
+    assert_eq!(func.parameters, original.parameters);
+    assert_eq!(func.return_type, original.return_type);
+    assert_eq!(func.get_type(), original.get_type());
+    assert_eq!(func.linkage, original.linkage);
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn strip_metadata() {
+    init_logging();
+    let mut module = Module::from_bc_path(&Path::new("tests/basic_bc/variables.bc-g")).expect("Failed to parse module");
+    assert!(!module.debug_types.is_empty());
+    let num_instrs = |module: &Module| -> usize {
+        module.functions.iter().flat_map(|f| &f.basic_blocks).map(|bb| bb.instrs.len()).sum()
+    };
+    let before = num_instrs(&module);
+
+    module.strip_metadata();
+    assert!(module.debug_types.is_empty());
+    assert!(module.debug_local_variables.is_empty());
+    for var in &module.global_vars {
+        assert_eq!(var.get_debug_loc(), &None);
+    }
+    for func in &module.functions {
+        assert_eq!(func.debugloc, None);
+        assert!(func.local_variables(&module).is_empty());
+        for bb in &func.basic_blocks {
+            for inst in &bb.instrs {
+                assert_eq!(inst.get_debug_loc(), &None);
+                match inst.as_intrinsic() {
+                    Some(Intrinsic::DbgDeclare) | Some(Intrinsic::DbgValue) => panic!("Expected llvm.dbg calls to be removed, found {:?}", inst),
+                    _ => {},
+                }
+            }
+            assert_eq!(bb.term.get_debug_loc(), &None);
+        }
+    }
+    assert!(num_instrs(&module) < before);
+}
+
+#[test]
+fn intern_strings() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/rust.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let mut interned = module.clone();
+    interned.intern_strings();
+    assert_eq!(interned.functions, module.functions);
+    assert_eq!(interned.global_vars, module.global_vars);
+    assert_eq!(format!("{:?}", interned.functions), format!("{:?}", module.functions));
+    assert_eq!(interned.functions[0].basic_blocks[0].name.to_string(), module.functions[0].basic_blocks[0].name.to_string());
+
+    // each function's entry block is named `start`, and those names should now share storage
+    let entry_names: Vec<&Arc<str>> = interned
+        .functions
+        .iter()
+        .filter_map(|func| match &func.basic_blocks[0].name {
+            Name::Interned(name) if &**name == "start" => Some(name),
+            _ => None,
+        })
+        .collect();
+    assert!(entry_names.len() > 1);
+    assert!(entry_names.iter().all(|name| Arc::ptr_eq(name, entry_names[0])));
+
+    // interned and non-interned names are interchangeable
+    let plain = Name::from("start");
+    let shared = Name::Interned("start".into());
+    assert_eq!(plain, shared);
+    assert_eq!(plain.cmp(&shared), std::cmp::Ordering::Equal);
+    assert!(shared < Name::Number(0));
+    assert_eq!(shared.as_str(), Some("start"));
+    assert_eq!(format!("{:?}", shared), format!("{:?}", plain));
+    assert_eq!(shared.to_string(), plain.to_string());
+    let set: std::collections::HashSet<Name> = vec![plain].into_iter().collect();
+    assert!(set.contains(&shared));
+}
+
+#[test]
+fn verify_parsed_modules() {
+    init_logging();
+    for path in &[
+        "tests/basic_bc/hello.bc",
+        "tests/basic_bc/loop.bc",
+        "tests/basic_bc/switch.bc",
+        "tests/basic_bc/variables.bc-g",
+        "tests/basic_bc/linkedlist.bc",
+        "tests/basic_bc/rust.bc-g",
+        "tests/handwritten_bc/cxx_eh.bc",
+    ] {
+        let module = Module::from_bc_path(&Path::new(path)).expect("Failed to parse module");
+        assert_eq!(module.verify(), Ok(()), "{}", path);
+        assert_eq!(module.verify_types(), Ok(()), "{}", path);
+    }
+}
+
+#[test]
+fn verify_types() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/arith.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    assert_eq!(module.verify_types(), Ok(()));
+
+    // store an `i64` through the `i32*` `@limit`, and add an `i64` to an `i32`
+    let func = module.functions.iter_mut().find(|func| func.name == "arith").unwrap();
+    let big = func.basic_blocks.iter_mut().find(|bb| bb.name == Name::from("big")).unwrap();
+    match &mut big.instrs[1] {
+        Instruction::Store(store) => store.value = Operand::ConstantOperand(Constant::Int { bits: 64, value: 0 }),
+        inst => panic!("Expected a store, got {:?}", inst),
+    }
+    match &mut func.basic_blocks[0].instrs[0] {
+        Instruction::Add(add) => add.operand1 = Operand::ConstantOperand(Constant::Int { bits: 64, value: 1 }),
+        inst => panic!("Expected an add, got {:?}", inst),
+    }
+    // the structural checks don't look at types
+    assert_eq!(module.verify(), Ok(()));
+
+    let errors = module.verify_types().unwrap_err();
+    assert_eq!(errors, vec![
+        TypeError::Mismatch {
+            function: "arith".to_owned(),
+            block: Name::from("entry"),
+            index: 0,
+            expected: Box::new(Type::i32()),
+            found: Box::new(Type::i64()),
+        },
+        TypeError::Mismatch {
+            function: "arith".to_owned(),
+            block: Name::from("big"),
+            index: 1,
+            expected: Box::new(Type::i32()),
+            found: Box::new(Type::i64()),
+        },
+    ]);
+    assert_eq!(errors[1].to_string(), "arith: block \"big\", instruction 1: expected type i32, found i64");
+}
+
+#[test]
+fn transform_strict_rolls_back() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let original = module.functions.clone();
+
+    // A `BasicBlock` can't lack a terminator, so remove the last block, along
+    // with its `ret`. The branches to it are left dangling.
+    let errors = module
+        .transform_strict(|module| {
+            module.functions[0].basic_blocks.pop();
+        })
+        .expect_err("Transform should fail verification");
+    assert_eq!(errors, vec![
+        VerifyError::UndefinedBlock { function: "loop".to_owned(), block: Name::from(2), target: Name::from(22) },
+        VerifyError::UndefinedBlock { function: "loop".to_owned(), block: Name::from(19), target: Name::from(22) },
+    ]);
+    assert_eq!(module.functions, original);
+
+    // a valid transform is kept
+    module
+        .transform_strict(|module| {
+            module.functions[0].basic_blocks[0].instrs.remove(2);
+        })
+        .expect("Removing the lifetime.start should be fine");
+    assert_eq!(module.functions[0].basic_blocks[0].instrs.len(), original[0].basic_blocks[0].instrs.len() - 1);
+}
+
+#[test]
+fn transform_keeps_invalid_changes() {
+    init_logging();
+    let path = Path::new("tests/basic_bc/loop.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+
+    // remove `%4 = bitcast`, which is used by the calls after it
+    let errors = module
+        .transform(|module| {
+            module.functions[0].basic_blocks[0].instrs.remove(1);
+        })
+        .expect_err("Transform should fail verification");
+    let undefined = |block: usize| VerifyError::UndefinedLocal {
+        function: "loop".to_owned(),
+        block: Name::from(block),
+        name: Name::from(4),
+    };
+    assert_eq!(errors, vec![undefined(2), undefined(2), undefined(22)]);
+    assert_eq!(module.functions[0].basic_blocks[0].instrs.len(), 5);
+
+    let mut func = module.functions[0].clone();
+    func.return_type = Type::i32();
+    module.functions = vec![func];
+    match module.verify() {
+        Err(errors) => assert!(errors.contains(&VerifyError::ReturnTypeMismatch {
+            function: "loop".to_owned(),
+            block: Name::from(22),
+            expected: Type::i32(),
+            found: Type::VoidType,
+        })),
+        Ok(()) => panic!("Expected a return type mismatch"),
+    }
+}
+
+#[test]
+fn rename_symbol() {
+    init_logging();
+    let path = Path::new("tests/handwritten_bc/callsites.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let callee_of = |module: &Module, instr: usize| -> Option<Name> {
+        let func = module.get_func_by_name("caller").expect("Failed to find function");
+        let call: instruction::Call = func.basic_blocks[0].instrs[instr].clone().try_into().expect("Should be a call");
+        match call.function {
+            either::Either::Right(Operand::ConstantOperand(Constant::GlobalReference { name, .. })) => Some(name),
+            _ => None,
+        }
+    };
+
+    module.rename_symbol("callee", "renamed").expect("Rename should succeed");
+    assert!(module.get_func_by_name("callee").is_none());
+    assert!(module.get_func_by_name("renamed").is_some());
+    assert_eq!(callee_of(&module, 0), Some(Name::from("renamed")));
+    assert_eq!(module.ultimate_target(&Name::from("alias_of_alias")), Some(&Name::from("renamed")));
+
+    // a function which is only declared
+    module.rename_symbol("external", "also_external").expect("Rename should succeed");
+    assert_eq!(callee_of(&module, 3), Some(Name::from("also_external")));
+    let invoker = module.get_func_by_name("invoker").expect("Failed to find function");
+    match &invoker.basic_blocks[0].term {
+        Terminator::Invoke(invoke) => match &invoke.function {
+            either::Either::Right(Operand::ConstantOperand(Constant::GlobalReference { name, .. })) => {
+                assert_eq!(name, &Name::from("also_external"))
+            },
+            f => panic!("Expected a direct invoke, got {:?}", f),
+        },
+        term => panic!("Expected an invoke, got {:?}", term),
+    }
+
+    let unchanged = module.clone();
+    assert_eq!(module.rename_symbol("renamed", "caller"), Err(module::RenameError::AlreadyExists("caller".to_owned())));
+    assert_eq!(module.rename_symbol("renamed", "also_external"), Err(module::RenameError::AlreadyExists("also_external".to_owned())));
+    assert_eq!(module.rename_symbol("callee", "whatever"), Err(module::RenameError::NotFound("callee".to_owned())));
+    assert_eq!(module.functions, unchanged.functions);
+
+    // references from llvm.global_ctors and llvm.used
+    let path = Path::new("tests/handwritten_bc/entrypoints.bc");
+    let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+    module.rename_symbol("early_ctor", "first_ctor").expect("Rename should succeed");
+    module.rename_symbol("ctor_data", "data").expect("Rename should succeed");
+    let ctors = module.global_ctors();
+    assert_eq!(ctors[0].function, Name::from("first_ctor"));
+    assert_eq!(ctors[0].associated_data, Some(Name::from("data")));
+    assert!(module.used_symbols().contains(&Name::from("data")));
+    assert!(module.get_global_var_by_name(&Name::from("data")).is_some());
+}